@@ -0,0 +1,539 @@
+//! 阈值告警
+//!
+//! 按 `config.yaml` 中 `alerts:` 的规则检查额度限制项，并通过 Webhook 推送、
+//! 执行 `hooks:` 中配置的钩子命令。
+//! 每条阈值规则在同一个额度窗口内只触发一次，窗口重置后重新计算；
+//! 去重状态保存在 `~/.glm/alert-state.json`。
+
+mod sink;
+
+pub use sink::{deliver, SinkConfig, SinkKind};
+
+use crate::api::{ApiData, LimitItem};
+use crate::cli_config::CliConfig;
+use crate::config::Language;
+use crate::error::CliError;
+use crate::hooks::{self, HookEvent};
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// 告警配置
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AlertConfig {
+    /// 告警规则（未配置时使用默认规则：Token 额度达到 80%、95% 以及窗口重置）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<AlertRule>,
+
+    /// 推送目标
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sinks: Vec<SinkConfig>,
+
+    /// 去重状态文件路径（可选，默认 `~/.glm/alert-state.json`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_file: Option<PathBuf>,
+}
+
+/// 单条告警规则
+///
+/// 设置 `threshold` 时为阈值规则（字段值与阈值比较），
+/// 设置 `on_reset: true` 时为窗口重置通知。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AlertRule {
+    /// 规则名称（可选，默认根据条件生成）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// 适用的额度类型（如 TOKENS_LIMIT，省略时适用于所有额度项）
+    #[serde(default, rename = "limit", skip_serializing_if = "Option::is_none")]
+    pub limit_type: Option<String>,
+
+    /// 比较的字段（默认 percentage）
+    #[serde(default)]
+    pub field: RuleField,
+
+    /// 比较运算符（默认 >=）
+    #[serde(default)]
+    pub op: CompareOp,
+
+    /// 阈值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+
+    /// 窗口重置时通知
+    #[serde(default)]
+    pub on_reset: bool,
+}
+
+/// 规则比较的 `LimitItem` 字段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleField {
+    /// 使用百分比
+    #[default]
+    Percentage,
+    /// 已使用量
+    CurrentValue,
+    /// 剩余量
+    Remaining,
+    /// 总额度
+    Usage,
+}
+
+impl RuleField {
+    fn value_of(self, item: &LimitItem) -> f64 {
+        match self {
+            RuleField::Percentage => item.percentage,
+            RuleField::CurrentValue => item.current_value as f64,
+            RuleField::Remaining => item.remaining as f64,
+            RuleField::Usage => item.usage as f64,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            RuleField::Percentage => "percentage",
+            RuleField::CurrentValue => "current_value",
+            RuleField::Remaining => "remaining",
+            RuleField::Usage => "usage",
+        }
+    }
+}
+
+/// 比较运算符
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CompareOp {
+    /// 大于等于
+    #[default]
+    #[serde(rename = ">=")]
+    Ge,
+    /// 大于
+    #[serde(rename = ">")]
+    Gt,
+    /// 小于等于
+    #[serde(rename = "<=")]
+    Le,
+    /// 小于
+    #[serde(rename = "<")]
+    Lt,
+}
+
+impl CompareOp {
+    fn matches(self, value: f64, threshold: f64) -> bool {
+        match self {
+            CompareOp::Ge => value >= threshold,
+            CompareOp::Gt => value > threshold,
+            CompareOp::Le => value <= threshold,
+            CompareOp::Lt => value < threshold,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            CompareOp::Ge => ">=",
+            CompareOp::Gt => ">",
+            CompareOp::Le => "<=",
+            CompareOp::Lt => "<",
+        }
+    }
+}
+
+impl AlertRule {
+    /// 阈值规则
+    pub fn threshold(limit_type: &str, threshold: f64) -> Self {
+        AlertRule {
+            name: None,
+            limit_type: Some(limit_type.to_string()),
+            field: RuleField::Percentage,
+            op: CompareOp::Ge,
+            threshold: Some(threshold),
+            on_reset: false,
+        }
+    }
+
+    /// 窗口重置通知规则
+    pub fn reset(limit_type: &str) -> Self {
+        AlertRule {
+            name: None,
+            limit_type: Some(limit_type.to_string()),
+            field: RuleField::Percentage,
+            op: CompareOp::Ge,
+            threshold: None,
+            on_reset: true,
+        }
+    }
+
+    /// 规则标识（用于去重与展示）
+    pub fn id(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        let scope = self.limit_type.as_deref().unwrap_or("*");
+        match self.threshold {
+            Some(threshold) if !self.on_reset => {
+                format!("{}:{}{}{}", scope, self.field.as_str(), self.op.as_str(), threshold)
+            }
+            _ => format!("{}:reset", scope),
+        }
+    }
+
+    fn applies_to(&self, item: &LimitItem) -> bool {
+        self.limit_type
+            .as_deref()
+            .is_none_or(|t| t.eq_ignore_ascii_case(&item.limit_type))
+    }
+}
+
+impl AlertConfig {
+    /// 实际生效的规则（未配置时使用默认规则）
+    pub fn effective_rules(&self) -> Vec<AlertRule> {
+        if !self.rules.is_empty() {
+            return self.rules.clone();
+        }
+        vec![
+            AlertRule::threshold("TOKENS_LIMIT", 80.0),
+            AlertRule::threshold("TOKENS_LIMIT", 95.0),
+            AlertRule::reset("TOKENS_LIMIT"),
+        ]
+    }
+
+    /// 去重状态文件路径
+    pub fn state_path(&self) -> Result<PathBuf> {
+        match &self.state_file {
+            Some(path) => Ok(path.clone()),
+            None => Ok(dirs::home_dir()
+                .ok_or_else(|| CliError::ConfigError("无法确定主目录".to_string()))?
+                .join(".glm/alert-state.json")),
+        }
+    }
+
+    /// 验证告警配置
+    pub fn validate(&self) -> Result<()> {
+        for (i, rule) in self.rules.iter().enumerate() {
+            if rule.threshold.is_none() && !rule.on_reset {
+                return Err(CliError::ConfigError(format!(
+                    "告警规则 #{} ({}) 既没有 threshold 也没有 on_reset",
+                    i + 1,
+                    rule.id()
+                ))
+                .suggest(["设置 threshold: 80 或 on_reset: true"])
+                .into());
+            }
+        }
+        for sink in &self.sinks {
+            sink.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// 一条触发的告警
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Alert {
+    /// 规则标识
+    pub rule: String,
+
+    /// 额度类型
+    pub limit_type: String,
+
+    /// 比较的字段（重置通知为 None）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+
+    /// 当前值
+    pub value: f64,
+
+    /// 额度项的使用百分比
+    pub percentage: f64,
+
+    /// 阈值（重置通知为 None）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+
+    /// 当前窗口的重置时间
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_reset_at: Option<DateTime<Utc>>,
+
+    /// 可读的告警消息
+    pub message: String,
+}
+
+/// 告警去重状态
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct AlertState {
+    /// 规则在哪个窗口已触发：`规则标识|额度类型` → 窗口标识
+    #[serde(default)]
+    pub fired: BTreeMap<String, i64>,
+
+    /// 各额度类型最近一次见到的窗口标识
+    #[serde(default)]
+    pub windows: BTreeMap<String, i64>,
+}
+
+impl AlertState {
+    /// 读取状态文件，不存在或无法解析时返回空状态
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// 写入状态文件
+    ///
+    /// 先写入同目录下的临时文件再重命名，`glm watch`、`glm alert` 与守护进程同时运行
+    /// 或中途崩溃时，状态文件不会被截断或交错写入。
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("alert-state.json");
+        let tmp = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+        let result = std::fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .and_then(|()| std::fs::rename(&tmp, path));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&tmp);
+        }
+        result?;
+        Ok(())
+    }
+}
+
+/// 检查使用情况，推送新触发的告警并执行对应的钩子
+///
+/// 推送或钩子至少一处成功（或两者都未配置）时保存去重状态；全部失败时不保存，
+/// 以便下次检查时重试。`dry_run` 时既不推送、不执行钩子，也不保存状态。
+pub async fn run(config: &CliConfig, data: &ApiData, lang: Language, dry_run: bool) -> Result<Vec<Alert>> {
+    let alerts_config = &config.alerts;
+    let state_path = alerts_config.state_path()?;
+    let mut state = AlertState::load(&state_path);
+    let alerts = evaluate(&alerts_config.effective_rules(), data, &mut state, Utc::now(), lang);
+
+    if dry_run {
+        return Ok(alerts);
+    }
+
+    let mut delivered = deliver(config, &alerts_config.sinks, &alerts).await?;
+    let mut hooks_configured = false;
+    for alert in &alerts {
+        let event = HookEvent::from_alert(alert);
+        hooks_configured |= config.hooks.command_for(&event).is_some();
+        if hooks::notify(&config.hooks, &event).await {
+            delivered += 1;
+        }
+    }
+
+    if alerts.is_empty() || (alerts_config.sinks.is_empty() && !hooks_configured) || delivered > 0 {
+        state.save(&state_path)?;
+    } else {
+        tracing::warn!("所有告警推送目标与钩子均失败，将在下次检查时重试");
+    }
+
+    Ok(alerts)
+}
+
+/// 额度项所在窗口的标识
+///
+/// 有重置时间时取重置时间（毫秒）；否则视为按月重置，取 `年 × 12 + 月`。
+fn window_id(item: &LimitItem, now: DateTime<Utc>) -> i64 {
+    item.next_reset_time
+        .unwrap_or_else(|| now.year() as i64 * 12 + now.month0() as i64)
+}
+
+/// 按规则检查使用情况，返回新触发的告警并更新去重状态
+pub fn evaluate(
+    rules: &[AlertRule],
+    data: &ApiData,
+    state: &mut AlertState,
+    now: DateTime<Utc>,
+    lang: Language,
+) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+
+    for item in &data.limits {
+        let window = window_id(item, now);
+        let previous_window = state.windows.insert(item.limit_type.clone(), window);
+        let reset = previous_window.is_some_and(|prev| prev != window);
+        let window_reset_at = item.next_reset_time.and_then(DateTime::from_timestamp_millis);
+
+        for rule in rules.iter().filter(|rule| rule.applies_to(item)) {
+            let key = format!("{}|{}", rule.id(), item.limit_type);
+
+            if rule.on_reset {
+                if reset {
+                    alerts.push(Alert {
+                        rule: rule.id(),
+                        limit_type: item.limit_type.clone(),
+                        field: None,
+                        value: item.percentage,
+                        percentage: item.percentage,
+                        threshold: None,
+                        window_reset_at,
+                        message: match lang {
+                            Language::Zh => format!(
+                                "{} 额度窗口已重置，当前使用 {:.1}%",
+                                item.limit_type, item.percentage
+                            ),
+                            Language::En => format!(
+                                "{} window has reset, now at {:.1}%",
+                                item.limit_type, item.percentage
+                            ),
+                        },
+                    });
+                }
+                continue;
+            }
+
+            let Some(threshold) = rule.threshold else {
+                continue;
+            };
+            let value = rule.field.value_of(item);
+            if !rule.op.matches(value, threshold) || state.fired.get(&key) == Some(&window) {
+                continue;
+            }
+
+            state.fired.insert(key, window);
+            let field = rule.field.as_str();
+            alerts.push(Alert {
+                rule: rule.id(),
+                limit_type: item.limit_type.clone(),
+                field: Some(field.to_string()),
+                value,
+                percentage: item.percentage,
+                threshold: Some(threshold),
+                window_reset_at,
+                message: match (lang, rule.field) {
+                    (Language::Zh, RuleField::Percentage) => format!(
+                        "{} 已使用 {:.1}%（阈值 {} {}%）",
+                        item.limit_type,
+                        value,
+                        rule.op.as_str(),
+                        threshold
+                    ),
+                    (Language::En, RuleField::Percentage) => format!(
+                        "{} is at {:.1}% (threshold {} {}%)",
+                        item.limit_type,
+                        value,
+                        rule.op.as_str(),
+                        threshold
+                    ),
+                    (Language::Zh, _) => format!(
+                        "{} 的 {} 为 {}（阈值 {} {}）",
+                        item.limit_type,
+                        field,
+                        value,
+                        rule.op.as_str(),
+                        threshold
+                    ),
+                    (Language::En, _) => format!(
+                        "{} {} is {} (threshold {} {})",
+                        item.limit_type,
+                        field,
+                        value,
+                        rule.op.as_str(),
+                        threshold
+                    ),
+                },
+            });
+        }
+    }
+
+    alerts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(percentage: f64, reset: i64) -> ApiData {
+        serde_json::from_value(serde_json::json!({
+            "limits": [
+                {"type": "TIME_LIMIT", "usage": 1000, "currentValue": 900},
+                {
+                    "type": "TOKENS_LIMIT",
+                    "usage": 1000,
+                    "currentValue": (percentage * 10.0) as u64,
+                    "percentage": percentage,
+                    "nextResetTime": reset
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_thresholds_fire_once_per_window() {
+        let rules = AlertConfig::default().effective_rules();
+        let mut state = AlertState::default();
+        let now = Utc::now();
+
+        assert!(evaluate(&rules, &usage(50.0, 1000), &mut state, now, Language::Zh).is_empty());
+
+        let alerts = evaluate(&rules, &usage(85.0, 1000), &mut state, now, Language::Zh);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, "TOKENS_LIMIT:percentage>=80");
+        assert_eq!(alerts[0].message, "TOKENS_LIMIT 已使用 85.0%（阈值 >= 80%）");
+
+        // 同一窗口内不重复触发
+        assert!(evaluate(&rules, &usage(90.0, 1000), &mut state, now, Language::Zh).is_empty());
+        let alerts = evaluate(&rules, &usage(96.0, 1000), &mut state, now, Language::Zh);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].threshold, Some(95.0));
+
+        // 窗口重置：发送重置通知，阈值重新计算
+        let alerts = evaluate(&rules, &usage(10.0, 2000), &mut state, now, Language::Zh);
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].message.contains("额度窗口已重置"));
+        let alerts = evaluate(&rules, &usage(81.0, 2000), &mut state, now, Language::Zh);
+        assert_eq!(alerts.len(), 1);
+    }
+
+    #[test]
+    fn test_custom_rules_from_yaml() {
+        let yaml = r#"
+rules:
+  - name: mcp-low
+    limit: TIME_LIMIT
+    field: remaining
+    op: "<"
+    threshold: 200
+sinks:
+  - kind: slack
+    url: https://hooks.slack.com/services/x
+"#;
+        let config: AlertConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+
+        let mut state = AlertState::default();
+        let alerts = evaluate(&config.effective_rules(), &usage(10.0, 1000), &mut state, Utc::now(), Language::En);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, "mcp-low");
+        assert_eq!(alerts[0].message, "TIME_LIMIT remaining is 100 (threshold < 200)");
+        assert_eq!(state.fired.get("mcp-low|TIME_LIMIT"), Some(&window_id(&usage(0.0, 0).limits[0], Utc::now())));
+    }
+
+    #[test]
+    fn test_rule_without_condition_is_rejected() {
+        let config: AlertConfig = serde_yaml::from_str("rules:\n  - limit: TOKENS_LIMIT\n").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_state_roundtrip() {
+        let path = std::env::temp_dir().join(format!("glm-alert-state-{}.json", std::process::id()));
+        let mut state = AlertState::default();
+        state.fired.insert("a|TOKENS_LIMIT".to_string(), 1);
+        state.save(&path).unwrap();
+        assert_eq!(AlertState::load(&path), state);
+
+        state.windows.insert("TOKENS_LIMIT".to_string(), 2);
+        state.save(&path).unwrap();
+        assert_eq!(AlertState::load(&path), state);
+        let tmp = path.with_file_name(format!(".{}.{}.tmp", path.file_name().unwrap().to_str().unwrap(), std::process::id()));
+        assert!(!tmp.exists());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(AlertState::load(&path), AlertState::default());
+    }
+}
@@ -0,0 +1,173 @@
+//! 告警推送目标
+//!
+//! 支持通用 JSON Webhook 以及飞书、钉钉、企业微信、Slack 机器人的消息格式。
+
+use super::Alert;
+use crate::api::http_client_builder;
+use crate::config::Config;
+use crate::error::CliError;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// 推送目标类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SinkKind {
+    /// 通用 JSON Webhook（推送完整的告警列表）
+    Webhook,
+    /// 飞书自定义机器人
+    Feishu,
+    /// 钉钉自定义机器人
+    Dingtalk,
+    /// 企业微信群机器人
+    Wecom,
+    /// Slack Incoming Webhook（及兼容格式，如 Mattermost）
+    Slack,
+}
+
+/// 推送目标配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SinkConfig {
+    /// 推送目标类型
+    pub kind: SinkKind,
+
+    /// Webhook 地址
+    pub url: String,
+
+    /// 名称（可选，用于日志）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl SinkConfig {
+    /// 用于日志的名称
+    pub fn label(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("{:?}", self.kind).to_lowercase())
+    }
+
+    /// 验证推送地址
+    pub fn validate(&self) -> Result<()> {
+        match reqwest::Url::parse(&self.url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+            _ => Err(CliError::ConfigError(format!("告警推送地址无效: {}", self.url))
+                .suggest(["请使用 http:// 或 https:// 开头的完整 Webhook 地址"])
+                .into()),
+        }
+    }
+
+    /// 生成该推送目标格式的请求体
+    pub fn payload(&self, alerts: &[Alert]) -> Value {
+        let text = alerts
+            .iter()
+            .map(|alert| format!("[GLM] {}", alert.message))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        match self.kind {
+            SinkKind::Webhook => json!({
+                "source": "glm",
+                "text": text,
+                "alerts": alerts,
+            }),
+            SinkKind::Feishu => json!({
+                "msg_type": "text",
+                "content": { "text": text },
+            }),
+            SinkKind::Dingtalk | SinkKind::Wecom => json!({
+                "msgtype": "text",
+                "text": { "content": text },
+            }),
+            SinkKind::Slack => json!({ "text": text }),
+        }
+    }
+}
+
+/// 将告警推送到所有目标
+///
+/// 单个目标失败只记录警告，不影响其他目标。返回推送成功的目标数量。
+pub async fn deliver(config: &Config, sinks: &[SinkConfig], alerts: &[Alert]) -> Result<usize> {
+    if alerts.is_empty() || sinks.is_empty() {
+        return Ok(0);
+    }
+
+    let client = http_client_builder(config)?
+        .build()
+        .map_err(|e| CliError::NetworkError(format!("创建 HTTP 客户端失败: {}", e)))?;
+
+    let mut delivered = 0;
+    for sink in sinks {
+        let result = client
+            .post(&sink.url)
+            .header("User-Agent", format!("glm-cli/{}", env!("CARGO_PKG_VERSION")))
+            .json(&sink.payload(alerts))
+            .send()
+            .await;
+        match result {
+            Ok(response) if response.status().is_success() => {
+                tracing::info!(sink = %sink.label(), alerts = alerts.len(), "告警已推送");
+                delivered += 1;
+            }
+            Ok(response) => {
+                tracing::warn!(sink = %sink.label(), status = response.status().as_u16(), "告警推送失败");
+            }
+            Err(e) => {
+                tracing::warn!(sink = %sink.label(), error = %e, "告警推送失败");
+            }
+        }
+    }
+
+    Ok(delivered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sink(kind: SinkKind) -> SinkConfig {
+        SinkConfig {
+            kind,
+            url: "https://example.com/hook".to_string(),
+            name: None,
+        }
+    }
+
+    fn alert() -> Alert {
+        Alert {
+            rule: "TOKENS_LIMIT:percentage>=80".to_string(),
+            limit_type: "TOKENS_LIMIT".to_string(),
+            field: Some("percentage".to_string()),
+            value: 85.0,
+            percentage: 85.0,
+            threshold: Some(80.0),
+            window_reset_at: None,
+            message: "TOKENS_LIMIT 已使用 85.0%（阈值 >= 80%）".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_payload_formats() {
+        let alerts = [alert()];
+        let text = "[GLM] TOKENS_LIMIT 已使用 85.0%（阈值 >= 80%）";
+
+        let webhook = sink(SinkKind::Webhook).payload(&alerts);
+        assert_eq!(webhook["alerts"][0]["value"], 85.0);
+        assert_eq!(webhook["text"], text);
+
+        assert_eq!(sink(SinkKind::Feishu).payload(&alerts)["content"]["text"], text);
+        assert_eq!(sink(SinkKind::Dingtalk).payload(&alerts)["msgtype"], "text");
+        assert_eq!(sink(SinkKind::Wecom).payload(&alerts)["text"]["content"], text);
+        assert_eq!(sink(SinkKind::Slack).payload(&alerts), json!({ "text": text }));
+    }
+
+    #[test]
+    fn test_sink_validate() {
+        assert!(sink(SinkKind::Slack).validate().is_ok());
+        let mut invalid = sink(SinkKind::Slack);
+        invalid.url = "ftp://example.com".to_string();
+        assert!(invalid.validate().is_err());
+        assert_eq!(invalid.label(), "slack");
+    }
+}
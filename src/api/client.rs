@@ -2,11 +2,38 @@
 //!
 //! 提供与智谱 AI GLM API 交互的客户端实现。
 
-use crate::api::{ApiResponse, ApiData};
-use crate::config::Config;
-use crate::error::CliError;
-use anyhow::Result;
-use std::time::Duration;
+use crate::api::{
+    check_plans_schema, check_usage_schema, ApiData, ApiErrorResponse, ApiPlan, ApiResponse,
+    PlanInfo, PlanResponse, UsageBreakdown, UsageDimension,
+};
+use crate::config::{ApiFlavor, Config, Region};
+#[cfg(feature = "cli")]
+use crate::daemon::{self, DaemonConfig};
+use crate::error::{CliError, Result};
+use crate::logging::redact_authorization;
+use crate::quota::Quota;
+use chrono::{DateTime, FixedOffset, Utc};
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// 未经解析的 HTTP 响应
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    /// HTTP 状态码
+    pub status: u16,
+    /// 响应体
+    pub body: String,
+    /// 响应头中的请求 ID（`x-request-id` 等）
+    pub request_id: Option<String>,
+}
+
+impl RawResponse {
+    /// HTTP 状态码是否为 2xx
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
 
 /// GLM API 客户端
 pub struct GlmClient {
@@ -14,142 +41,561 @@ pub struct GlmClient {
     client: reqwest::Client,
     /// API 配置
     config: Config,
+    /// 守护进程套接字：设置后优先读取守护进程的缓存
+    #[cfg(feature = "cli")]
+    daemon_socket: Option<PathBuf>,
+}
+
+/// 根据配置构建 HTTP 客户端构建器
+///
+/// 应用超时、代理、额外 CA 证书与 mTLS 客户端证书设置。
+pub(crate) fn http_client_builder(config: &Config) -> Result<reqwest::ClientBuilder> {
+    // 构建 HTTP 客户端，设置超时
+    let timeout = Duration::from_secs(config.timeout);
+    let mut builder = reqwest::Client::builder().timeout(timeout);
+
+    if let Some(secs) = config.connect_timeout {
+        builder = builder.connect_timeout(Duration::from_secs(secs));
+    }
+    if let Some(secs) = config.read_timeout {
+        builder = builder.read_timeout(Duration::from_secs(secs));
+    }
+
+    // 显式代理（未配置时 reqwest 会读取系统代理环境变量）
+    if let Some(proxy_url) = &config.proxy {
+        let proxy = reqwest::Proxy::all(proxy_url)
+            .map_err(|e| CliError::ConfigError(format!("代理地址无效 ({}): {}", proxy_url, e)))?
+            .no_proxy(
+                config
+                    .no_proxy
+                    .as_deref()
+                    .and_then(reqwest::NoProxy::from_string),
+            );
+        builder = builder.proxy(proxy);
+    }
+
+    // 额外信任的 CA 证书
+    if let Some(path) = &config.ca_cert {
+        let pem = std::fs::read(path).map_err(|e| {
+            CliError::ConfigError(format!("读取 CA 证书失败 ({}): {}", path.display(), e))
+        })?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| {
+            CliError::ConfigError(format!("解析 CA 证书失败 ({}): {}", path.display(), e))
+        })?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    // mTLS 客户端证书
+    if let (Some(cert_path), Some(key_path)) = (&config.client_cert, &config.client_key) {
+        let cert = std::fs::read(cert_path).map_err(|e| {
+            CliError::ConfigError(format!(
+                "读取客户端证书失败 ({}): {}",
+                cert_path.display(),
+                e
+            ))
+        })?;
+        let key = std::fs::read(key_path).map_err(|e| {
+            CliError::ConfigError(format!(
+                "读取客户端私钥失败 ({}): {}",
+                key_path.display(),
+                e
+            ))
+        })?;
+        let identity = reqwest::Identity::from_pkcs8_pem(&cert, &key)
+            .map_err(|e| CliError::ConfigError(format!("加载客户端证书失败: {}", e)))?;
+        builder = builder.identity(identity);
+    }
+
+    Ok(builder)
+}
+
+/// [`GlmClient`] 构建器
+///
+/// 由 [`GlmClient::builder`] 创建。不读取配置文件与环境变量，未设置的项使用
+/// [`Config::default`] 的默认值（中国大陆站、30 秒超时）。
+#[derive(Debug, Default)]
+pub struct GlmClientBuilder {
+    config: Config,
+    http_client: Option<reqwest::Client>,
+}
+
+impl GlmClientBuilder {
+    /// 以已有配置为基础（如 [`Config::load`] 读取的用户配置）
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// API 密钥
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.config.api_key = api_key.into();
+        self
+    }
+
+    /// API 地址（如 `https://bigmodel.cn/api`）
+    pub fn api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.config.api_url = api_url.into();
+        self.config.api_url_explicit = true;
+        self
+    }
+
+    /// 服务区域；未显式设置 API 地址时一并切换为该区域的默认地址
+    pub fn region(mut self, region: Region) -> Self {
+        self.config.set_region(region);
+        self
+    }
+
+    /// 接口形式（默认按 API 地址自动判断）
+    pub fn flavor(mut self, flavor: ApiFlavor) -> Self {
+        self.config.api_flavor = flavor;
+        self
+    }
+
+    /// 请求总超时（按秒向上取整，须在 1-300 秒之间）
+    ///
+    /// 通过 [`http_client`](Self::http_client) 注入 HTTP 客户端时，实际超时由该客户端决定。
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = (timeout.as_millis() as u64).div_ceil(1000);
+        self
+    }
+
+    /// 是否严格校验响应结构
+    pub fn strict_schema(mut self, strict: bool) -> Self {
+        self.config.strict_schema = strict;
+        self
+    }
+
+    /// 数据一致性检查发现问题时是否报错（默认仅记录警告）
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.config.strict = strict;
+        self
+    }
+
+    /// 是否允许非回环地址使用 HTTP
+    pub fn allow_insecure_http(mut self, allow: bool) -> Self {
+        self.config.allow_insecure_http = allow;
+        self
+    }
+
+    /// 使用调用方提供的 HTTP 客户端（共享连接池、自定义代理或 TLS 设置）
+    ///
+    /// 注入后配置中的超时、代理与证书设置不再生效。
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// 校验配置并创建客户端
+    ///
+    /// 密钥过短、地址不是 HTTPS、超时超出范围等配置问题返回 [`CliError::ConfigError`]。
+    pub fn build(self) -> Result<GlmClient> {
+        self.config.validate()?;
+
+        match self.http_client {
+            Some(client) => Ok(GlmClient {
+                client,
+                config: self.config,
+                #[cfg(feature = "cli")]
+                daemon_socket: None,
+            }),
+            None => GlmClient::new(self.config),
+        }
+    }
 }
 
 impl GlmClient {
+    /// 创建客户端构建器
+    ///
+    /// ```no_run
+    /// # async fn example() -> glm::Result<()> {
+    /// let client = glm::GlmClient::builder()
+    ///     .api_key("your-api-key")
+    ///     .timeout(std::time::Duration::from_secs(10))
+    ///     .build()?;
+    /// let quota = client.fetch_quota().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> GlmClientBuilder {
+        GlmClientBuilder::default()
+    }
+
     /// 创建新的 GLM API 客户端
     ///
+    /// 不校验配置（命令行程序在加载配置时已校验）；嵌入时建议使用 [`GlmClient::builder`]。
+    ///
     /// # 参数
     ///
     /// * `config`: API 配置
     pub fn new(config: Config) -> Result<Self> {
-        // 构建 HTTP 客户端，设置超时
-        let timeout = Duration::from_secs(config.timeout);
-        let client = reqwest::Client::builder()
-            .timeout(timeout)
+        let client = http_client_builder(&config)?
             .build()
             .map_err(|e| CliError::NetworkError(format!("创建 HTTP 客户端失败: {}", e)))?;
 
-        Ok(GlmClient { client, config })
+        Ok(GlmClient {
+            client,
+            config,
+            #[cfg(feature = "cli")]
+            daemon_socket: None,
+        })
+    }
+
+    /// 获取使用情况时优先读取 `glm daemon` 的缓存（`daemon.enabled: false` 时无效）
+    ///
+    /// 守护进程未运行或缓存不可用时自动回退为直接请求 API。仅 `cli` feature 可用。
+    #[cfg(feature = "cli")]
+    pub fn with_daemon(mut self, daemon: &DaemonConfig) -> Self {
+        self.daemon_socket = daemon.enabled.then(|| daemon.socket_path().ok()).flatten();
+        self
+    }
+
+    /// 客户端使用的配置
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// 获取额度
+    ///
+    /// 与 [`fetch_usage`](Self::fetch_usage) 相同，结果转换为 [`Quota`] 领域类型。
+    pub async fn fetch_quota(&self) -> Result<Quota> {
+        Ok(Quota::from(self.fetch_usage().await?))
     }
 
     /// 获取 API 使用情况
     ///
-    /// 发送 GET 请求到 `/api/monitor/usage/quota/limit` 端点，
-    /// 获取当前用户的 API 使用情况信息。
+    /// 发送 GET 请求到额度查询端点（监控接口为 `/monitor/usage/quota/limit`，
+    /// 计划接口为 `/plans`），获取当前用户的 API 使用情况信息。
+    /// 计划接口的订阅信息会转换为单个 `PLAN_QUOTA` 额度项。
+    ///
+    /// 每次获取后都会检查数据一致性：发现的问题记录为警告，
+    /// 配置 `strict` 时返回 [`CliError::ValidationError`]。
     ///
     /// # 返回
     ///
     /// 成功时返回 `ApiData`，失败时返回 `CliError`
     pub async fn fetch_usage(&self) -> Result<ApiData> {
-        let url = format!("{}/monitor/usage/quota/limit", self.config.api_url);
+        let data = self.fetch_usage_unchecked().await?;
+        self.check_consistency(&data)?;
+        Ok(data)
+    }
 
-        // 掩码 API 密钥用于日志（显示前 5 个字符）
-        let _masked_key = if self.config.api_key.len() > 5 {
-            format!("{}****{}", &self.config.api_key[..5], &self.config.api_key[self.config.api_key.len()-4..])
-        } else {
-            "****".to_string()
-        };
+    /// 获取使用情况（不做一致性检查）
+    async fn fetch_usage_unchecked(&self) -> Result<ApiData> {
+        #[cfg(feature = "cli")]
+        if let Some(socket) = &self.daemon_socket {
+            match daemon::query_usage(&self.config, socket).await {
+                Ok(reply) => {
+                    tracing::debug!(updated_at = %reply.updated_at, "使用守护进程缓存的使用情况");
+                    return Ok(reply.data);
+                }
+                Err(e) => {
+                    tracing::debug!(error = %format!("{:#}", e), "守护进程不可用，直接请求 API")
+                }
+            }
+        }
 
-        // 发送 HTTP GET 请求
-        let response = self.client
-            .get(&url)
+        let raw = self.fetch_usage_raw().await?;
+        self.check_response(&raw)?;
+
+        if self.config.flavor() == ApiFlavor::Plans {
+            let info = self.parse_plan(&raw)?;
+            return Ok(ApiData {
+                limits: vec![info.to_limit_item()],
+            });
+        }
+
+        // 解析成功响应
+        let body = &raw.body;
+        let api_response: ApiResponse = serde_json::from_str(body).map_err(|e| {
+            tracing::debug!(error = %e, body = %body, "解析 API 响应失败，原始响应体");
+            CliError::ParseError(format!("解析 API 响应失败: {}", e))
+        })?;
+        tracing::debug!(limits = api_response.data.limits.len(), "解析 API 响应成功");
+
+        Ok(api_response.data)
+    }
+
+    /// 获取订阅计划信息
+    ///
+    /// 计划接口直接映射订阅名称与有效期；监控接口没有订阅信息，
+    /// 取 TOKENS_LIMIT 额度项（不存在时取第一项）。
+    pub async fn fetch_plan(&self) -> Result<ApiPlan> {
+        if self.config.flavor() == ApiFlavor::Plans {
+            let raw = self.fetch_usage_raw().await?;
+            self.check_response(&raw)?;
+            let plan = ApiPlan::from_plan_info(&self.parse_plan(&raw)?);
+            if let Err(e) = plan.validate() {
+                if self.config.strict {
+                    return Err(CliError::ValidationError(format!(
+                        "订阅计划数据不一致: {}",
+                        e
+                    )));
+                }
+                tracing::warn!("{}", e);
+            }
+            return Ok(plan);
+        }
+
+        let data = self.fetch_usage().await?;
+        data.limits
+            .iter()
+            .find(|item| item.limit_type == "TOKENS_LIMIT")
+            .or_else(|| data.limits.first())
+            .map(ApiPlan::from_limit_item)
+            .ok_or_else(|| CliError::ParseError("API 响应中没有任何额度信息".to_string()))
+    }
+
+    /// 检查数据一致性，严格模式下发现问题即报错
+    fn check_consistency(&self, data: &ApiData) -> Result<()> {
+        let warnings = data.consistency_warnings(Utc::now());
+        for warning in &warnings {
+            tracing::warn!("{}", warning);
+        }
+        if self.config.strict && !warnings.is_empty() {
+            return Err(CliError::ValidationError(format!(
+                "API 返回的数据不一致\n\n原因:\n{}",
+                warnings.join("\n")
+            ))
+            .suggest(["这通常是上游接口的问题，去掉 --strict 可仅输出警告"]));
+        }
+        Ok(())
+    }
+
+    /// 检查响应是否成功，并在严格模式下校验响应结构
+    fn check_response(&self, raw: &RawResponse) -> Result<()> {
+        self.check_status(raw)?;
+
+        // 严格模式：逐字段核对响应结构
+        if self.config.strict_schema {
+            let value: serde_json::Value = serde_json::from_str(&raw.body)
+                .map_err(|e| CliError::ParseError(format!("响应不是合法的 JSON: {}", e)))?;
+            let issues = match self.config.flavor() {
+                ApiFlavor::Plans => check_plans_schema(&value),
+                _ => check_usage_schema(&value),
+            };
+            if !issues.is_empty() {
+                return Err(CliError::ParseError(format!(
+                    "响应结构与预期不一致（共 {} 处）\n\n原因:\n{}",
+                    issues.len(),
+                    issues
+                        .iter()
+                        .map(|issue| format!("- {}", issue))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                ))
+                .suggest(["接口格式可能已变更，去掉 --strict-schema 可使用宽松解析"]));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 检查 HTTP 状态码与业务错误
+    fn check_status(&self, raw: &RawResponse) -> Result<()> {
+        // 检查 HTTP 状态码
+        if !raw.is_success() {
+            return Err(self.handle_error_response(raw));
+        }
+
+        // HTTP 200 也可能携带业务错误（success: false 或 code != 200）
+        if let Ok(envelope) = serde_json::from_str::<ApiErrorResponse>(&raw.body) {
+            if envelope.is_business_error() {
+                return Err(self.handle_error_response(raw));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 解析计划接口的响应
+    fn parse_plan(&self, raw: &RawResponse) -> Result<PlanInfo> {
+        let body = &raw.body;
+        let response: PlanResponse = serde_json::from_str(body).map_err(|e| {
+            tracing::debug!(error = %e, body = %body, "解析计划接口响应失败，原始响应体");
+            CliError::ParseError(format!("解析计划接口响应失败: {}", e))
+        })?;
+        tracing::debug!(plan_id = %response.data.plan_id, "解析计划接口响应成功");
+        Ok(response.data)
+    }
+
+    /// 获取 API 使用情况的原始响应
+    ///
+    /// 仅在网络层失败时返回错误；任何 HTTP 状态码都会连同响应体原样返回，
+    /// 供诊断命令逐层判断问题所在。
+    pub async fn fetch_usage_raw(&self) -> Result<RawResponse> {
+        self.get_raw(&self.config.endpoint_url(), &[]).await
+    }
+
+    /// 获取按模型或工具统计的用量明细
+    ///
+    /// 仅监控接口提供用量明细端点（`/monitor/usage/model-usage`、
+    /// `/monitor/usage/tool-usage`）。时间范围按区域时区以
+    /// `YYYY-MM-DD HH:MM:SS` 格式传递。
+    pub async fn fetch_usage_breakdown(
+        &self,
+        by: UsageDimension,
+        start: DateTime<FixedOffset>,
+        end: DateTime<FixedOffset>,
+    ) -> Result<UsageBreakdown> {
+        if self.config.flavor() == ApiFlavor::Plans {
+            return Err(CliError::ConfigError("计划接口不提供用量明细".to_string()).suggest([
+                "用量明细需要监控接口，请将 api_url 设置为 https://bigmodel.cn/api（或 --region intl），或设置 api_flavor: monitor",
+            ]));
+        }
+
+        let url = format!(
+            "{}{}",
+            self.config.api_url.trim_end_matches('/'),
+            by.endpoint_path()
+        );
+        let query = [
+            ("startTime", start.format("%Y-%m-%d %H:%M:%S").to_string()),
+            ("endTime", end.format("%Y-%m-%d %H:%M:%S").to_string()),
+        ];
+        let raw = self.get_raw(&url, &query).await?;
+        self.check_status(&raw)?;
+
+        let body: serde_json::Value = serde_json::from_str(&raw.body).map_err(|e| {
+            tracing::debug!(error = %e, body = %raw.body, "解析用量明细失败，原始响应体");
+            CliError::ParseError(format!("解析用量明细失败: {}", e))
+        })?;
+        let breakdown = UsageBreakdown::from_response(by, start, end, &body)
+            .map_err(|e| CliError::ParseError(format!("解析用量明细失败: {}", e)))?;
+        tracing::debug!(entries = breakdown.entries.len(), "解析用量明细成功");
+
+        Ok(breakdown)
+    }
+
+    /// 发送带认证信息的 GET 请求并返回原始响应
+    async fn get_raw(&self, url: &str, query: &[(&str, String)]) -> Result<RawResponse> {
+        let request = self
+            .client
+            .get(url)
+            .query(query)
             .header("Authorization", format!("Bearer {}", self.config.api_key))
             .header("Content-Type", "application/json")
             .header("Accept", "application/json")
-            .header("User-Agent", format!("glm-cli/{}", env!("CARGO_PKG_VERSION")))
-            .send()
+            .header(
+                "User-Agent",
+                format!("glm-cli/{}", env!("CARGO_PKG_VERSION")),
+            )
+            .build()
+            .map_err(|e| CliError::NetworkError(format!("构建请求失败: {}", e)))?;
+
+        tracing::info!(method = %request.method(), url = %request.url(), "发送请求");
+        for (name, value) in request.headers() {
+            let value = value.to_str().unwrap_or("<非 ASCII>");
+            let value = if name == reqwest::header::AUTHORIZATION {
+                redact_authorization(value)
+            } else {
+                value.to_string()
+            };
+            tracing::debug!(header = %name, value = %value, "请求头");
+        }
+
+        // 发送 HTTP GET 请求
+        let started = Instant::now();
+        let response = self.client
+            .execute(request)
             .await
             .map_err(|e| {
                 // 提供友好的中文错误消息
                 if e.is_timeout() {
                     CliError::NetworkError(
-                        format!("API 请求超时（{}秒）\n\n原因: 服务器在 {} 秒内未响应\n\n建议:\n1. 请检查网络连接是否正常\n2. 请稍后重试\n3. 如问题持续，请联系支持团队",
+                        format!("API 请求超时（{}秒）\n\n原因: 服务器在 {} 秒内未响应",
                             self.config.timeout, self.config.timeout)
                     )
+                    .suggest(["请检查网络连接是否正常", "请稍后重试", "如问题持续，请联系支持团队"])
                 } else if e.is_connect() {
-                    CliError::NetworkError(
-                        format!("无法连接到服务器\n\n原因: 连接失败\n\n建议:\n1. 请检查网络连接\n2. 请确认 API URL 正确: {}\n3. 请检查防火墙设置",
-                            self.config.api_url)
-                    )
+                    CliError::NetworkError("无法连接到服务器\n\n原因: 连接失败".to_string()).suggest([
+                        "请检查网络连接".to_string(),
+                        format!("请确认 API URL 正确: {}", self.config.api_url),
+                        "请检查防火墙设置".to_string(),
+                    ])
                 } else {
                     CliError::NetworkError(format!("网络请求失败: {}", e))
                 }
+            })
+            .inspect_err(|e| {
+                tracing::info!(elapsed_ms = started.elapsed().as_millis() as u64, error = %e, "请求失败");
             })?;
 
-        let status = response.status();
-
-        // 检查 HTTP 状态码
-        if !status.is_success() {
-            // 尝试解析错误响应
-            let error_text = response.text().await.unwrap_or_else(|_| "无法读取错误响应".to_string());
-            return self.handle_error_response(status.as_u16(), error_text);
-        }
-
-        // 解析成功响应
-        let api_response: ApiResponse = response
-            .json()
+        let status = response.status().as_u16();
+        let request_id = ["x-request-id", "x-log-id", "request-id"]
+            .iter()
+            .find_map(|name| response.headers().get(*name))
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response
+            .text()
             .await
-            .map_err(|e| CliError::NetworkError(format!("解析 API 响应失败: {}", e)))?;
+            .map_err(|e| CliError::NetworkError(format!("读取 API 响应失败: {}", e)))?;
 
-        Ok(api_response.data)
+        tracing::info!(
+            status,
+            bytes = body.len(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "收到响应"
+        );
+        tracing::trace!(body = %body, "响应体");
+
+        Ok(RawResponse {
+            status,
+            body,
+            request_id,
+        })
     }
 
     /// 处理错误响应
     ///
-    /// 将 API 错误代码映射到友好的中文错误消息
-    fn handle_error_response(&self, code: u16, error_text: String) -> Result<ApiData> {
-        // 尝试解析错误响应
-        let error_resp: serde_json::Value = serde_json::from_str(&error_text)
-            .unwrap_or_else(|_| serde_json::json!({"code": code, "msg": error_text}));
-
-        let msg = error_resp["msg"]
-            .as_str()
-            .unwrap_or("未知错误")
-            .to_string();
-
-        // 根据错误代码提供友好的中文消息
-        let (description, suggestion) = match code {
-            400 => (
-                "请求格式错误",
-                "请检查请求格式，确保所有参数正确".to_string()
-            ),
-            401 => (
-                "认证失败",
-                "API 密钥无效或已过期\n\n建议:\n1. 请检查 API 密钥是否正确配置\n2. 请确认 API 密钥未过期\n3. 请访问智谱 AI 平台重新生成密钥".to_string()
-            ),
-            403 => (
-                "无权限访问",
-                "您的账户无权限访问此资源\n\n建议:\n请确认您的 API 密钥有访问权限\n如问题持续，请联系客服".to_string()
-            ),
+    /// 解析上游错误信封，将 API 错误代码映射到友好的中文错误消息。
+    /// HTTP 2xx 的业务错误按上游错误代码推断对应的 HTTP 语义。
+    fn handle_error_response(&self, raw: &RawResponse) -> CliError {
+        let envelope: Option<ApiErrorResponse> = serde_json::from_str(&raw.body).ok();
+        let upstream_msg = envelope
+            .as_ref()
+            .and_then(|e| e.message())
+            .map(str::to_string);
+        let upstream_code = envelope.as_ref().and_then(|e| e.upstream_code());
+        let request_id = envelope
+            .as_ref()
+            .and_then(|e| e.request_id.clone())
+            .or_else(|| raw.request_id.clone());
+
+        // HTTP 2xx 中无法映射的业务代码保留为业务错误，不按 HTTP 200 归类
+        let code = if raw.is_success() {
+            upstream_code.as_deref().and_then(status_for_business_code)
+        } else {
+            Some(raw.status)
+        };
+
+        let msg = match (&envelope, &upstream_msg) {
+            (_, Some(msg)) => msg.clone(),
+            (None, None) => raw.body.clone(),
+            (Some(_), None) => "未知错误".to_string(),
+        };
+
+        let Some(code) = code else {
+            return business_error(msg, upstream_msg, upstream_code, request_id);
+        };
+
+        // 根据错误代码提供友好的中文消息（修复建议见 CliError::suggestions）
+        let (description, detail) = match code {
+            400 => ("请求格式错误", None),
+            401 => ("认证失败", Some("API 密钥无效或已过期".to_string())),
+            403 => ("无权限访问", Some("您的账户无权限访问此资源".to_string())),
             404 => (
                 "API 端点不存在",
-                format!("API 端点不存在\n\n建议:\n请检查 API URL 配置是否正确: {}", self.config.api_url)
-            ),
-            429 => (
-                "请求过于频繁",
-                "请求过于频繁，已被限流\n\n建议:\n请稍后再试（建议等待 1-2 秒）".to_string()
-            ),
-            500 => (
-                "服务器内部错误",
-                "服务器内部错误\n\n建议:\n请稍后重试\n如问题持续，请联系支持团队".to_string()
-            ),
-            502 => (
-                "网关错误",
-                "网关错误\n\n建议:\n请稍后重试".to_string()
-            ),
-            503 => (
-                "服务暂时不可用",
-                "服务暂时不可用\n\n建议:\n请稍后重试".to_string()
-            ),
-            504 => (
-                "网关超时",
-                "网关超时\n\n建议:\n请稍后重试".to_string()
-            ),
-            _ => (
-                "未知错误",
-                "发生未知错误\n\n建议:\n请稍后重试\n如问题持续，请联系支持团队".to_string()
+                Some(format!("当前 API URL: {}", self.config.api_url)),
             ),
+            429 => ("请求过于频繁", Some("请求过于频繁，已被限流".to_string())),
+            500 => ("服务器内部错误", None),
+            502 => ("网关错误", None),
+            503 => ("服务暂时不可用", None),
+            504 => ("网关超时", None),
+            _ => ("未知错误", Some("发生未知错误".to_string())),
         };
 
         let full_msg = if !msg.is_empty() && msg != "未知错误" {
@@ -158,9 +604,74 @@ impl GlmClient {
             description.to_string()
         };
 
-        let error_msg = format!("{}\n\n{}", full_msg, suggestion);
+        // 附上上游错误代码与请求 ID，便于向平台反馈
+        let mut refs = Vec::new();
+        if let Some(upstream_code) = &upstream_code {
+            refs.push(format!("错误代码: {}", upstream_code));
+        }
+        if let Some(request_id) = &request_id {
+            refs.push(format!("请求 ID: {}", request_id));
+        }
+        let full_msg = if refs.is_empty() {
+            full_msg
+        } else {
+            format!("{}\n{}", full_msg, refs.join("，"))
+        };
 
-        Err(CliError::ApiError { code, msg: error_msg }.into())
+        let full_msg = match detail {
+            Some(detail) => format!("{}\n\n{}", full_msg, detail),
+            None => full_msg,
+        };
+
+        CliError::ApiError {
+            code,
+            msg: full_msg,
+            upstream_msg,
+            upstream_code,
+            request_id,
+        }
+    }
+}
+
+/// 无法映射为 HTTP 语义的上游业务错误
+fn business_error(
+    msg: String,
+    upstream_msg: Option<String>,
+    upstream_code: Option<String>,
+    request_id: Option<String>,
+) -> CliError {
+    let mut text = match upstream_code.as_deref() {
+        Some(code) => format!("上游返回业务错误 {}", code),
+        None => "上游返回业务错误".to_string(),
+    };
+    if !msg.is_empty() && msg != "未知错误" {
+        text.push_str(&format!("\n\n原因: {}", msg));
+    }
+    if let Some(request_id) = &request_id {
+        text.push_str(&format!("\n请求 ID: {}", request_id));
+    }
+    CliError::BusinessError {
+        msg: text,
+        upstream_msg,
+        upstream_code,
+        request_id,
+    }
+}
+
+/// 将上游业务错误代码映射为对应的 HTTP 状态码
+///
+/// 上游在 HTTP 200 响应中以业务代码表示错误：
+/// 1000-1004、1100 为认证失败，1110-1121 为账户状态问题，
+/// 1302-1305 为限流，1230-1234 为服务端错误。代码本身即为 HTTP 状态码时直接使用。
+fn status_for_business_code(code: &str) -> Option<u16> {
+    let code: u16 = code.parse().ok()?;
+    match code {
+        400..=599 => Some(code),
+        1000..=1004 | 1100 => Some(401),
+        1110..=1121 => Some(403),
+        1302..=1305 => Some(429),
+        1230..=1234 => Some(500),
+        _ => None,
     }
 }
 
@@ -171,8 +682,8 @@ mod tests {
     fn create_test_config() -> Config {
         Config {
             api_key: "test_api_key".to_string(),
-            api_url: default_api_url(),
             timeout: 30,
+            ..Config::default()
         }
     }
 
@@ -187,15 +698,151 @@ mod tests {
     fn test_client_creation_with_timeout() {
         let config = Config {
             api_key: "test_api_key".to_string(),
-            api_url: default_api_url(),
             timeout: 60,
+            ..Config::default()
         };
 
         let client = GlmClient::new(config);
         assert!(client.is_ok());
     }
-}
 
-fn default_api_url() -> String {
-    "https://bigmodel.cn/api".to_string()
+    #[test]
+    fn test_client_creation_with_network_settings() {
+        let config = Config {
+            api_key: "test_api_key".to_string(),
+            connect_timeout: Some(5),
+            read_timeout: Some(20),
+            proxy: Some("http://127.0.0.1:3128".to_string()),
+            no_proxy: Some("localhost,127.0.0.1".to_string()),
+            ..Config::default()
+        };
+
+        let client = GlmClient::new(config);
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn test_client_creation_with_missing_ca_cert() {
+        let config = Config {
+            api_key: "test_api_key".to_string(),
+            ca_cert: Some("/nonexistent/ca.pem".into()),
+            ..Config::default()
+        };
+
+        let client = GlmClient::new(config);
+        assert!(client.is_err());
+    }
+
+    fn raw(status: u16, body: &str) -> RawResponse {
+        RawResponse {
+            status,
+            body: body.to_string(),
+            request_id: Some("hdr-req-1".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_handle_error_response_http_error() {
+        let client = GlmClient::new(create_test_config()).unwrap();
+        let err = client.handle_error_response(&raw(
+            401,
+            r#"{"code":401,"msg":"Invalid API key","error":"unauthorized"}"#,
+        ));
+        match err {
+            CliError::ApiError {
+                code,
+                msg,
+                upstream_msg,
+                upstream_code,
+                request_id,
+            } => {
+                assert_eq!(code, 401);
+                assert!(msg.contains("认证失败"));
+                assert!(msg.contains("请求 ID: hdr-req-1"));
+                assert_eq!(upstream_msg.as_deref(), Some("Invalid API key"));
+                assert_eq!(upstream_code.as_deref(), Some("401"));
+                assert_eq!(request_id.as_deref(), Some("hdr-req-1"));
+            }
+            other => panic!("意外的错误类型: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_handle_error_response_business_error() {
+        let client = GlmClient::new(create_test_config()).unwrap();
+        let err = client.handle_error_response(&raw(
+            200,
+            r#"{"code":1302,"msg":"您当前使用该API的并发数过高","success":false,"requestId":"body-req-9"}"#,
+        ));
+        match err {
+            CliError::ApiError {
+                code,
+                upstream_code,
+                request_id,
+                ..
+            } => {
+                assert_eq!(code, 429);
+                assert_eq!(upstream_code.as_deref(), Some("1302"));
+                assert_eq!(request_id.as_deref(), Some("body-req-9"));
+            }
+            other => panic!("意外的错误类型: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_handle_error_response_unmapped_business_code() {
+        let client = GlmClient::new(create_test_config()).unwrap();
+        let err = client.handle_error_response(&raw(
+            200,
+            r#"{"code":9999,"msg":"账户已冻结","success":false}"#,
+        ));
+        assert_eq!(err.kind(), "business");
+        assert_eq!(err.exit_code(), crate::error::exit_code::API);
+        match err {
+            CliError::BusinessError {
+                msg,
+                upstream_msg,
+                upstream_code,
+                request_id,
+            } => {
+                assert!(msg.contains("上游返回业务错误 9999"));
+                assert!(msg.contains("原因: 账户已冻结"));
+                assert!(!msg.contains("200"));
+                assert_eq!(upstream_msg.as_deref(), Some("账户已冻结"));
+                assert_eq!(upstream_code.as_deref(), Some("9999"));
+                assert_eq!(request_id.as_deref(), Some("hdr-req-1"));
+            }
+            other => panic!("意外的错误类型: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_handle_error_response_non_json_body() {
+        let client = GlmClient::new(create_test_config()).unwrap();
+        let err = client.handle_error_response(&raw(502, "Bad Gateway"));
+        match err {
+            CliError::ApiError {
+                code,
+                msg,
+                upstream_msg,
+                ..
+            } => {
+                assert_eq!(code, 502);
+                assert!(msg.contains("原因: Bad Gateway"));
+                assert!(upstream_msg.is_none());
+            }
+            other => panic!("意外的错误类型: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_status_for_business_code() {
+        assert_eq!(status_for_business_code("1001"), Some(401));
+        assert_eq!(status_for_business_code("1113"), Some(403));
+        assert_eq!(status_for_business_code("1302"), Some(429));
+        assert_eq!(status_for_business_code("1234"), Some(500));
+        assert_eq!(status_for_business_code("404"), Some(404));
+        assert_eq!(status_for_business_code("9999"), None);
+        assert_eq!(status_for_business_code("abc"), None);
+    }
 }
@@ -3,7 +3,11 @@
 //! 提供 GLM API 客户端和数据类型定义。
 
 mod client;
+mod schema;
 mod types;
 
-pub use client::GlmClient;
+pub use client::{GlmClient, GlmClientBuilder, RawResponse};
+#[cfg(any(feature = "cli", feature = "otel"))]
+pub(crate) use client::http_client_builder;
+pub use schema::{check_plans_schema, check_usage_schema};
 pub use types::*;
@@ -0,0 +1,427 @@
+//! 响应结构的宽松解析与严格校验
+//!
+//! 上游接口字段可能随时调整（新增字段、数字以字符串返回、字段缺失或为 null），
+//! 默认采用宽松解析以保证工具可用；`--strict-schema` 模式下则逐字段报告与预期不一致之处。
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// 将数字、数字字符串或 null 解析为 `Option<u64>`
+pub(crate) fn lenient_u64<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<Value>::deserialize(deserializer)?;
+    match value.as_ref().map(lenient_unsigned) {
+        None => Ok(None),
+        Some(result) => result.map_err(serde::de::Error::custom),
+    }
+}
+
+/// 将数字、数字字符串或 null 解析为 `Option<i64>`
+pub(crate) fn lenient_i64<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<Value>::deserialize(deserializer)?;
+    match value.as_ref().map(lenient_signed) {
+        None => Ok(None),
+        Some(result) => result.map_err(serde::de::Error::custom),
+    }
+}
+
+/// 将数字、数字字符串（可带 `%`）或 null 解析为 `Option<f64>`
+pub(crate) fn lenient_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<Value>::deserialize(deserializer)?;
+    match value.as_ref().map(lenient_number) {
+        None => Ok(None),
+        Some(result) => result.map_err(serde::de::Error::custom),
+    }
+}
+
+/// 将 ISO 8601 日期时间、纯日期、毫秒时间戳或 null 解析为 `Option<DateTime<Utc>>`
+///
+/// 不带时区的时间按 UTC 处理。
+pub(crate) fn lenient_datetime<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<Value>::deserialize(deserializer)?;
+    match value {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) => Ok(n.as_i64().and_then(DateTime::from_timestamp_millis)),
+        Some(Value::String(s)) => parse_datetime(&s)
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom(format!("无法将字符串 {:?} 解析为日期时间", s))),
+        Some(other) => Err(serde::de::Error::custom(format!(
+            "期望日期时间，实际为 {}",
+            describe(&other)
+        ))),
+    }
+}
+
+/// 解析常见的日期时间字符串格式
+fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
+        return Some(dt.and_utc());
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+/// 把 JSON 值解释为数字
+pub(crate) fn lenient_number(value: &Value) -> Result<Option<f64>, String> {
+    match value {
+        Value::Null => Ok(None),
+        Value::Number(n) => Ok(n.as_f64()),
+        Value::String(s) => {
+            let trimmed = s.trim().trim_end_matches('%').trim();
+            if trimmed.is_empty() {
+                return Ok(None);
+            }
+            trimmed
+                .parse::<f64>()
+                .map(Some)
+                .map_err(|_| format!("无法将字符串 {:?} 解析为数字", s))
+        }
+        other => Err(format!("期望数字，实际为 {}", describe(other))),
+    }
+}
+
+/// 把 JSON 值解释为非负整数
+///
+/// 整数与整数字符串按 u64 精确解析，小数或带 `%` 的字符串才经由浮点数取整；
+/// 负数、非有限值与超出 u64 范围的值视为错误。
+pub(crate) fn lenient_unsigned(value: &Value) -> Result<Option<u64>, String> {
+    if let Some(n) = value.as_u64() {
+        return Ok(Some(n));
+    }
+    if let Some(n) = value.as_str().and_then(|s| s.trim().parse::<u64>().ok()) {
+        return Ok(Some(n));
+    }
+    match lenient_number(value)? {
+        None => Ok(None),
+        // u64::MAX as f64 向上取整为 2^64，本身已超出范围
+        Some(n) if n.is_finite() && n >= 0.0 && n.round() < u64::MAX as f64 => Ok(Some(n.round() as u64)),
+        Some(n) => Err(format!("期望非负整数，实际为 {}", n)),
+    }
+}
+
+/// 把 JSON 值解释为整数，规则同 [`lenient_unsigned`]
+pub(crate) fn lenient_signed(value: &Value) -> Result<Option<i64>, String> {
+    if let Some(n) = value.as_i64() {
+        return Ok(Some(n));
+    }
+    if let Some(n) = value.as_str().and_then(|s| s.trim().parse::<i64>().ok()) {
+        return Ok(Some(n));
+    }
+    match lenient_number(value)? {
+        None => Ok(None),
+        Some(n) if n.is_finite() && n.round() >= i64::MIN as f64 && n.round() < i64::MAX as f64 => {
+            Ok(Some(n.round() as i64))
+        }
+        Some(n) => Err(format!("期望整数，实际为 {}", n)),
+    }
+}
+
+/// 字段的预期类型
+#[derive(Debug, Clone, Copy)]
+enum Expected {
+    String,
+    Integer,
+    Number,
+    Bool,
+    Object,
+    Array,
+}
+
+impl Expected {
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            Expected::String => value.is_string(),
+            Expected::Integer => value.is_i64() || value.is_u64(),
+            Expected::Number => value.is_number(),
+            Expected::Bool => value.is_boolean(),
+            Expected::Object => value.is_object(),
+            Expected::Array => value.is_array(),
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Expected::String => "字符串",
+            Expected::Integer => "整数",
+            Expected::Number => "数字",
+            Expected::Bool => "布尔值",
+            Expected::Object => "对象",
+            Expected::Array => "数组",
+        }
+    }
+}
+
+/// 字段定义：(名称, 预期类型, 是否必填)
+type FieldSpec = (&'static str, Expected, bool);
+
+const ENVELOPE_FIELDS: &[FieldSpec] = &[
+    ("code", Expected::Integer, true),
+    ("msg", Expected::String, true),
+    ("data", Expected::Object, true),
+    ("success", Expected::Bool, true),
+];
+
+const DATA_FIELDS: &[FieldSpec] = &[("limits", Expected::Array, true)];
+
+const LIMIT_FIELDS: &[FieldSpec] = &[
+    ("type", Expected::String, true),
+    ("unit", Expected::Integer, true),
+    ("number", Expected::Integer, true),
+    ("usage", Expected::Integer, true),
+    ("currentValue", Expected::Integer, true),
+    ("remaining", Expected::Integer, true),
+    ("percentage", Expected::Number, true),
+    ("nextResetTime", Expected::Integer, false),
+];
+
+const PLAN_ENVELOPE_FIELDS: &[FieldSpec] = &[
+    ("code", Expected::Integer, true),
+    ("msg", Expected::String, true),
+    ("data", Expected::Object, true),
+    ("success", Expected::Bool, false),
+];
+
+const PLAN_FIELDS: &[FieldSpec] = &[
+    ("plan_id", Expected::String, true),
+    ("plan_name", Expected::String, true),
+    ("total_quota", Expected::Integer, true),
+    ("used_quota", Expected::Integer, true),
+    ("remaining_quota", Expected::Integer, true),
+    ("usage_percentage", Expected::Number, true),
+    ("start_date", Expected::String, true),
+    ("end_date", Expected::String, true),
+    ("token_type", Expected::String, true),
+];
+
+/// 严格校验计划接口（`/plans`）响应的结构
+///
+/// 与 [`check_usage_schema`] 相同，返回每一处与契约不一致的描述。
+pub fn check_plans_schema(body: &Value) -> Vec<String> {
+    let mut issues = Vec::new();
+    check_object(body, "", PLAN_ENVELOPE_FIELDS, &mut issues);
+
+    if let Some(data) = body.get("data").filter(|v| v.is_object()) {
+        check_object(data, "data", PLAN_FIELDS, &mut issues);
+    }
+
+    issues
+}
+
+/// 严格校验额度查询响应的结构
+///
+/// 返回每一处与预期不一致的描述，形如
+/// `data.limits[1].percentage: 期望数字，实际为字符串 "66"`；结构完全一致时返回空列表。
+pub fn check_usage_schema(body: &Value) -> Vec<String> {
+    let mut issues = Vec::new();
+    check_object(body, "", ENVELOPE_FIELDS, &mut issues);
+
+    if let Some(data) = body.get("data").filter(|v| v.is_object()) {
+        check_object(data, "data", DATA_FIELDS, &mut issues);
+        if let Some(limits) = data.get("limits").and_then(Value::as_array) {
+            for (i, item) in limits.iter().enumerate() {
+                let path = format!("data.limits[{}]", i);
+                if item.is_object() {
+                    check_object(item, &path, LIMIT_FIELDS, &mut issues);
+                    if let (Some(current), Some(remaining)) = (
+                        item.get("currentValue").and_then(Value::as_u64),
+                        item.get("remaining").and_then(Value::as_u64),
+                    ) {
+                        if current.checked_add(remaining).is_none() {
+                            issues.push(format!("{}: currentValue + remaining 超出整数范围", path));
+                        }
+                    }
+                } else {
+                    issues.push(format!("{}: 期望对象，实际为 {}", path, describe(item)));
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// 按字段定义校验一个 JSON 对象
+fn check_object(value: &Value, path: &str, fields: &[FieldSpec], issues: &mut Vec<String>) {
+    let join = |name: &str| {
+        if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", path, name)
+        }
+    };
+
+    let Some(object) = value.as_object() else {
+        let path = if path.is_empty() { "<根>" } else { path };
+        issues.push(format!("{}: 期望对象，实际为 {}", path, describe(value)));
+        return;
+    };
+
+    for (name, expected, required) in fields {
+        match object.get(*name) {
+            None if *required => issues.push(format!("{}: 缺少必填字段", join(name))),
+            None => {}
+            Some(v) if !expected.matches(v) => issues.push(format!(
+                "{}: 期望{}，实际为 {}",
+                join(name),
+                expected.label(),
+                describe(v)
+            )),
+            Some(_) => {}
+        }
+    }
+
+    for name in object.keys() {
+        if !fields.iter().any(|(known, _, _)| known == name) {
+            issues.push(format!("{}: 未知字段", join(name)));
+        }
+    }
+}
+
+/// 描述 JSON 值的类型与内容
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => format!("布尔值 {}", b),
+        Value::Number(n) => format!("数字 {}", n),
+        Value::String(s) => format!("字符串 {:?}", s),
+        Value::Array(_) => "数组".to_string(),
+        Value::Object(_) => "对象".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn valid_body() -> Value {
+        json!({
+            "code": 200,
+            "msg": "操作成功",
+            "success": true,
+            "data": {
+                "limits": [{
+                    "type": "TOKENS_LIMIT",
+                    "unit": 3,
+                    "number": 5,
+                    "usage": 200000000,
+                    "currentValue": 132374032,
+                    "remaining": 67625968,
+                    "percentage": 66,
+                    "nextResetTime": 1768328328345i64
+                }]
+            }
+        })
+    }
+
+    #[test]
+    fn test_check_usage_schema_valid() {
+        assert!(check_usage_schema(&valid_body()).is_empty());
+    }
+
+    #[test]
+    fn test_check_usage_schema_reports_each_divergence() {
+        let mut body = valid_body();
+        let item = &mut body["data"]["limits"][0];
+        item["percentage"] = json!("66");
+        item.as_object_mut().unwrap().remove("remaining");
+        item["newField"] = json!(1);
+        item["unit"] = Value::Null;
+
+        let issues = check_usage_schema(&body);
+        assert!(issues.contains(&"data.limits[0].percentage: 期望数字，实际为 字符串 \"66\"".to_string()));
+        assert!(issues.contains(&"data.limits[0].remaining: 缺少必填字段".to_string()));
+        assert!(issues.contains(&"data.limits[0].newField: 未知字段".to_string()));
+        assert!(issues.contains(&"data.limits[0].unit: 期望整数，实际为 null".to_string()));
+        assert_eq!(issues.len(), 4);
+    }
+
+    #[test]
+    fn test_check_usage_schema_reports_overflow() {
+        let mut body = valid_body();
+        body["data"]["limits"][0]["currentValue"] = json!(u64::MAX);
+        body["data"]["limits"][0]["remaining"] = json!(1);
+        let issues = check_usage_schema(&body);
+        assert!(issues.contains(&"data.limits[0]: currentValue + remaining 超出整数范围".to_string()));
+    }
+
+    #[test]
+    fn test_lenient_unsigned() {
+        assert_eq!(lenient_unsigned(&json!(9007199254740993u64)), Ok(Some(9007199254740993)));
+        assert_eq!(lenient_unsigned(&json!("9007199254740993")), Ok(Some(9007199254740993)));
+        assert_eq!(lenient_unsigned(&json!(u64::MAX)), Ok(Some(u64::MAX)));
+        assert_eq!(lenient_unsigned(&json!("66.6%")), Ok(Some(67)));
+        assert_eq!(lenient_unsigned(&Value::Null), Ok(None));
+        assert!(lenient_unsigned(&json!("inf")).is_err());
+        assert!(lenient_unsigned(&json!(1e30)).is_err());
+        assert!(lenient_unsigned(&json!(-1)).is_err());
+
+        assert_eq!(lenient_signed(&json!("-1768328328345")), Ok(Some(-1768328328345)));
+        assert!(lenient_signed(&json!("NaN")).is_err());
+    }
+
+    #[test]
+    fn test_check_plans_schema() {
+        let mut body = json!({
+            "code": 200,
+            "msg": "success",
+            "data": {
+                "plan_id": "premium_plan",
+                "plan_name": "高级版",
+                "total_quota": 1000000,
+                "used_quota": 250000,
+                "remaining_quota": 750000,
+                "usage_percentage": 25.0,
+                "start_date": "2026-01-01T00:00:00Z",
+                "end_date": "2026-12-31T23:59:59Z",
+                "token_type": "tokens"
+            }
+        });
+        assert!(check_plans_schema(&body).is_empty());
+
+        body["data"]["total_quota"] = json!("1000000");
+        let issues = check_plans_schema(&body);
+        assert_eq!(issues, vec!["data.total_quota: 期望整数，实际为 字符串 \"1000000\"".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_datetime() {
+        let expected = DateTime::parse_from_rfc3339("2026-12-31T00:00:00Z").unwrap();
+        assert_eq!(parse_datetime("2026-12-31T00:00:00Z"), Some(expected.to_utc()));
+        assert_eq!(parse_datetime("2026-12-31T08:00:00+08:00"), Some(expected.to_utc()));
+        assert_eq!(parse_datetime("2026-12-31 00:00:00"), Some(expected.to_utc()));
+        assert_eq!(parse_datetime("2026-12-31"), Some(expected.to_utc()));
+        assert_eq!(parse_datetime("明天"), None);
+    }
+
+    #[test]
+    fn test_lenient_number() {
+        assert_eq!(lenient_number(&json!(66)), Ok(Some(66.0)));
+        assert_eq!(lenient_number(&json!("66")), Ok(Some(66.0)));
+        assert_eq!(lenient_number(&json!(" 66.5% ")), Ok(Some(66.5)));
+        assert_eq!(lenient_number(&Value::Null), Ok(None));
+        assert!(lenient_number(&json!("abc")).is_err());
+        assert!(lenient_number(&json!([1])).is_err());
+    }
+}
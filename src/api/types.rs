@@ -2,11 +2,17 @@
 //!
 //! 定义与 GLM API 交互时使用的所有数据结构。
 
+use crate::api::schema::{lenient_datetime, lenient_f64, lenient_i64, lenient_number, lenient_u64};
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Utc};
+use std::collections::BTreeMap;
 
 /// 单个额度限制项
+///
+/// 反序列化采用宽松模式：数字可以是字符串，
+/// 缺失的 `remaining` / `percentage` 等字段会根据其他字段推算。
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(from = "RawLimitItem")]
 pub struct LimitItem {
     /// 限制类型（TIME_LIMIT 或 TOKENS_LIMIT）
     #[serde(rename = "type")]
@@ -34,6 +40,74 @@ pub struct LimitItem {
     /// 下次重置时间（仅 TOKENS_LIMIT 有此字段）
     #[serde(rename = "nextResetTime", skip_serializing_if = "Option::is_none")]
     pub next_reset_time: Option<i64>,
+
+    /// 未识别的字段（原样保留）
+    #[serde(flatten, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// 宽松解析用的原始额度限制项
+///
+/// 所有数值字段均可缺失、为 null 或以字符串形式出现。
+#[derive(Debug, Clone, Deserialize)]
+struct RawLimitItem {
+    #[serde(rename = "type")]
+    limit_type: String,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    unit: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    number: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    usage: Option<u64>,
+    #[serde(rename = "currentValue", default, deserialize_with = "lenient_u64")]
+    current_value: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    remaining: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_f64")]
+    percentage: Option<f64>,
+    #[serde(rename = "nextResetTime", default, deserialize_with = "lenient_i64")]
+    next_reset_time: Option<i64>,
+    #[serde(flatten)]
+    extra: BTreeMap<String, serde_json::Value>,
+}
+
+impl From<RawLimitItem> for LimitItem {
+    /// 补全缺失字段：总额度 = 已使用 + 剩余，剩余 = 总额度 - 已使用，
+    /// 百分比 = 已使用 / 总额度 × 100
+    ///
+    /// 已使用与剩余之和溢出时总额度按 0 处理，由一致性检查报告。
+    fn from(raw: RawLimitItem) -> Self {
+        let usage = raw
+            .usage
+            .or_else(|| raw.current_value?.checked_add(raw.remaining?))
+            .unwrap_or(0);
+        let current_value = raw
+            .current_value
+            .or_else(|| raw.remaining.map(|r| usage.saturating_sub(r)))
+            .unwrap_or(0);
+        let remaining = raw
+            .remaining
+            .unwrap_or_else(|| usage.saturating_sub(current_value));
+        let percentage = raw.percentage.unwrap_or_else(|| {
+            if usage == 0 {
+                0.0
+            } else {
+                current_value as f64 / usage as f64 * 100.0
+            }
+        });
+
+        LimitItem {
+            limit_type: raw.limit_type,
+            unit: raw.unit.unwrap_or(0),
+            number: raw.number.unwrap_or(0),
+            usage,
+            current_value,
+            remaining,
+            percentage,
+            next_reset_time: raw.next_reset_time,
+            extra: raw.extra,
+        }
+    }
 }
 
 /// API 响应数据
@@ -43,26 +117,184 @@ pub struct ApiData {
     pub limits: Vec<LimitItem>,
 }
 
+/// 上游百分比通常取整，与重新计算的值相差超过该阈值才视为不一致
+const PERCENTAGE_TOLERANCE: f64 = 1.0;
+
+impl ApiData {
+    /// 检查数据一致性
+    ///
+    /// 对每个额度项运行 [`ApiPlan::validate`]，并交叉核对
+    /// `usage = currentValue + remaining`、重新计算的百分比，以及重置时间是否已过去。
+    /// 返回发现的全部不一致之处，数据一致时返回空列表。
+    pub fn consistency_warnings(&self, now: DateTime<Utc>) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        for item in &self.limits {
+            let plan = ApiPlan::from_limit_item(item);
+            let name = &item.limit_type;
+
+            if let Err(e) = plan.validate() {
+                warnings.push(format!("{}: {}", name, e));
+            }
+
+            if item.current_value.checked_add(item.remaining) != Some(item.usage) {
+                warnings.push(format!(
+                    "{}: 已使用量 ({}) + 剩余量 ({}) 不等于总额度 ({})",
+                    name, item.current_value, item.remaining, item.usage
+                ));
+            }
+
+            if item.usage > 0 {
+                let computed = item.current_value as f64 / item.usage as f64 * 100.0;
+                if (computed - item.percentage).abs() > PERCENTAGE_TOLERANCE {
+                    warnings.push(format!(
+                        "{}: 上报的使用百分比 ({}%) 与计算值 ({:.1}%) 不一致",
+                        name, item.percentage, computed
+                    ));
+                }
+            }
+
+            if let Some(reset_time) = plan.next_reset_time {
+                if reset_time < now {
+                    warnings.push(format!(
+                        "{}: 下次重置时间 ({}) 已过去",
+                        name,
+                        reset_time.format("%Y-%m-%d %H:%M:%S UTC")
+                    ));
+                }
+            }
+        }
+
+        warnings
+    }
+}
+
 /// API 成功响应
 #[derive(Debug, Deserialize)]
 pub struct ApiResponse {
     /// HTTP 状态码（200 表示成功）
+    #[serde(default)]
     pub code: u16,
 
     /// 响应消息
+    #[serde(default)]
     pub msg: String,
 
     /// 响应数据
     pub data: ApiData,
 
     /// 是否成功
+    #[serde(default = "default_success")]
     pub success: bool,
 }
 
-/// 显示用的 API 计划信息（从 TOKENS_LIMIT 提取）
-#[derive(Debug, Clone)]
+fn default_success() -> bool {
+    true
+}
+
+/// 计划接口（`/paas/v4/plans`）返回的订阅信息
+///
+/// 与额度限制项相同采用宽松解析，缺失的额度字段会根据其他字段推算。
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlanInfo {
+    /// 计划唯一标识符
+    #[serde(default)]
+    pub plan_id: String,
+
+    /// 计划名称
+    #[serde(default)]
+    pub plan_name: String,
+
+    /// 总额度
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub total_quota: Option<u64>,
+
+    /// 已使用量
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub used_quota: Option<u64>,
+
+    /// 剩余量
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub remaining_quota: Option<u64>,
+
+    /// 使用百分比（0-100）
+    #[serde(default, deserialize_with = "lenient_f64")]
+    pub usage_percentage: Option<f64>,
+
+    /// 计划开始日期
+    #[serde(default, deserialize_with = "lenient_datetime")]
+    pub start_date: Option<DateTime<Utc>>,
+
+    /// 计划结束日期
+    #[serde(default, deserialize_with = "lenient_datetime")]
+    pub end_date: Option<DateTime<Utc>>,
+
+    /// 额度类型（tokens 或 requests）
+    #[serde(default)]
+    pub token_type: String,
+}
+
+/// 计划接口的成功响应
+#[derive(Debug, Deserialize)]
+pub struct PlanResponse {
+    /// HTTP 状态码（200 表示成功）
+    #[serde(default)]
+    pub code: u16,
+
+    /// 响应消息
+    #[serde(default)]
+    pub msg: String,
+
+    /// 订阅信息
+    pub data: PlanInfo,
+}
+
+/// 由计划接口转换而来的额度限制项类型
+pub const PLAN_QUOTA: &str = "PLAN_QUOTA";
+
+impl PlanInfo {
+    /// 转换为额度限制项，使计划接口的数据也能走通用的输出与一致性检查
+    ///
+    /// 计划名称、有效期等信息保存在 `extra` 中（`planId`、`planName`、
+    /// `startDate`、`endDate`、`tokenType`）。
+    pub fn to_limit_item(&self) -> LimitItem {
+        let raw = RawLimitItem {
+            limit_type: PLAN_QUOTA.to_string(),
+            unit: None,
+            number: None,
+            usage: self.total_quota,
+            current_value: self.used_quota,
+            remaining: self.remaining_quota,
+            percentage: self.usage_percentage,
+            next_reset_time: None,
+            extra: BTreeMap::new(),
+        };
+        let mut item = LimitItem::from(raw);
+
+        let mut put = |key: &str, value: serde_json::Value| {
+            item.extra.insert(key.to_string(), value);
+        };
+        put("planId", self.plan_id.clone().into());
+        put("planName", self.plan_name.clone().into());
+        put("tokenType", self.token_type.clone().into());
+        if let Some(start) = self.start_date {
+            put("startDate", start.to_rfc3339().into());
+        }
+        if let Some(end) = self.end_date {
+            put("endDate", end.to_rfc3339().into());
+        }
+        item
+    }
+}
+
+/// 显示用的 API 计划信息（从 TOKENS_LIMIT 或计划接口提取）
+#[derive(Debug, Clone, Serialize)]
 pub struct ApiPlan {
-    /// 限制类型
+    /// 计划标识符（仅计划接口提供）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan_id: Option<String>,
+
+    /// 计划名称或限制类型
     pub plan_name: String,
 
     /// 总额度（token 数）
@@ -79,25 +311,58 @@ pub struct ApiPlan {
 
     /// 下次重置时间（可选）
     pub next_reset_time: Option<DateTime<Utc>>,
+
+    /// 计划开始日期（仅计划接口提供）
+    pub start_date: Option<DateTime<Utc>>,
+
+    /// 计划结束日期（仅计划接口提供）
+    pub end_date: Option<DateTime<Utc>>,
+
+    /// 额度类型（tokens 或 requests，仅计划接口提供）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
 }
 
 impl ApiPlan {
     /// 从 LimitItem 创建 ApiPlan
     pub fn from_limit_item(item: &LimitItem) -> Self {
+        let extra_str = |key: &str| {
+            item.extra
+                .get(key)
+                .and_then(serde_json::Value::as_str)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let extra_date = |key: &str| {
+            extra_str(key)
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc))
+        };
+
         ApiPlan {
+            plan_id: extra_str("planId"),
             plan_name: match item.limit_type.as_str() {
                 "TOKENS_LIMIT" => "Token 额度".to_string(),
                 "TIME_LIMIT" => "时间限制".to_string(),
+                PLAN_QUOTA => extra_str("planName").unwrap_or_else(|| "订阅计划".to_string()),
                 _ => item.limit_type.clone(),
             },
             total_quota: item.usage,
             used_quota: item.current_value,
             remaining_quota: item.remaining,
             usage_percentage: item.percentage,
-            next_reset_time: item.next_reset_time.and_then(|ts| DateTime::from_timestamp_millis(ts)),
+            next_reset_time: item.next_reset_time.and_then(DateTime::from_timestamp_millis),
+            start_date: extra_date("startDate"),
+            end_date: extra_date("endDate"),
+            token_type: extra_str("tokenType"),
         }
     }
 
+    /// 从计划接口的订阅信息创建 ApiPlan
+    pub fn from_plan_info(info: &PlanInfo) -> Self {
+        ApiPlan::from_limit_item(&info.to_limit_item())
+    }
+
     /// 验证 ApiPlan 数据的完整性
     pub fn validate(&self) -> Result<(), String> {
         if self.total_quota == 0 {
@@ -118,21 +383,267 @@ impl ApiPlan {
             ));
         }
 
+        if let (Some(start), Some(end)) = (self.start_date, self.end_date) {
+            if end < start {
+                return Err(format!(
+                    "计划结束日期 ({}) 早于开始日期 ({})",
+                    end.format("%Y-%m-%d"),
+                    start.format("%Y-%m-%d")
+                ));
+            }
+        }
+
         Ok(())
     }
 }
 
 /// API 错误响应
-#[derive(Debug, Clone, Deserialize)]
+///
+/// 兼容上游的几种错误格式：
+///
+/// * 监控接口业务错误: `{"code": 1001, "msg": "...", "success": false}`
+/// * 契约文档格式: `{"code": 401, "msg": "...", "error": "unauthorized"}`
+/// * 开放平台格式: `{"error": {"code": "1113", "message": "..."}}`
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct ApiErrorResponse {
-    /// 错误代码
-    pub code: u16,
+    /// 错误代码（上游可能返回数字或字符串）
+    #[serde(default)]
+    pub code: Option<serde_json::Value>,
 
     /// 错误消息
-    pub msg: String,
+    #[serde(default)]
+    pub msg: Option<String>,
+
+    /// 是否成功
+    #[serde(default)]
+    pub success: Option<bool>,
+
+    /// 错误类型或错误详情
+    #[serde(default)]
+    pub error: Option<ApiErrorField>,
+
+    /// 请求 ID
+    #[serde(default, alias = "requestId", alias = "request_id")]
+    pub request_id: Option<String>,
+}
+
+/// 错误响应中的 `error` 字段
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ApiErrorField {
+    /// 错误类型字符串，如 `"unauthorized"`
+    Kind(String),
+
+    /// 错误详情对象
+    Detail {
+        /// 错误代码
+        #[serde(default)]
+        code: Option<serde_json::Value>,
+        /// 错误消息
+        #[serde(default)]
+        message: Option<String>,
+    },
+}
+
+impl ApiErrorResponse {
+    /// 上游错误代码（统一为字符串）
+    ///
+    /// 优先取 `error.code`，其次取顶层 `code`。
+    pub fn upstream_code(&self) -> Option<String> {
+        let nested = match &self.error {
+            Some(ApiErrorField::Detail { code, .. }) => code.as_ref(),
+            _ => None,
+        };
+        nested.or(self.code.as_ref()).and_then(|code| match code {
+            serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+    }
+
+    /// 上游错误消息
+    pub fn message(&self) -> Option<&str> {
+        let nested = match &self.error {
+            Some(ApiErrorField::Detail { message, .. }) => message.as_deref(),
+            _ => None,
+        };
+        nested
+            .or(self.msg.as_deref())
+            .filter(|m| !m.is_empty())
+    }
+
+    /// HTTP 2xx 响应中是否携带了业务错误
+    ///
+    /// `success` 为 `false`、存在 `error` 字段，或 `code` 不是 200 时视为错误。
+    pub fn is_business_error(&self) -> bool {
+        if self.success == Some(false) || self.error.is_some() {
+            return true;
+        }
+        match self.upstream_code() {
+            Some(code) => code != "200",
+            None => false,
+        }
+    }
+}
+
+/// 用量明细的统计维度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum UsageDimension {
+    /// 按模型统计 token 消耗
+    Model,
+    /// 按 MCP 工具（联网搜索、视觉理解等）统计调用次数
+    Tool,
+}
+
+impl UsageDimension {
+    /// 用量明细端点相对于 `api_url` 的路径
+    pub fn endpoint_path(self) -> &'static str {
+        match self {
+            UsageDimension::Model => "/monitor/usage/model-usage",
+            UsageDimension::Tool => "/monitor/usage/tool-usage",
+        }
+    }
+}
+
+/// 单个模型或工具的用量
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageEntry {
+    /// 模型或工具名称
+    pub name: String,
+
+    /// token 消耗
+    pub tokens: u64,
 
-    /// 错误类型
-    pub error: String,
+    /// 调用次数
+    pub calls: u64,
+}
+
+/// 按模型或工具统计的用量明细
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageBreakdown {
+    /// 统计维度
+    pub by: UsageDimension,
+
+    /// 统计开始时间
+    pub start: DateTime<FixedOffset>,
+
+    /// 统计结束时间
+    pub end: DateTime<FixedOffset>,
+
+    /// 各模型或工具的用量（按 token 消耗、调用次数降序）
+    pub entries: Vec<UsageEntry>,
+}
+
+/// 名称字段的候选键
+const NAME_KEYS: &[&str] = &["modelName", "model", "toolName", "tool", "name"];
+/// token 消耗字段的候选键
+const TOKEN_KEYS: &[&str] = &["totalTokens", "tokens", "tokenUsage", "totalUsage", "usage"];
+/// 调用次数字段的候选键
+const CALL_KEYS: &[&str] = &["callCount", "calls", "count", "requestCount", "times"];
+/// 明细列表字段的候选键
+const LIST_KEYS: &[&str] = &["list", "items", "records", "rows", "details"];
+
+impl UsageBreakdown {
+    /// 从用量明细响应中提取各模型或工具的用量
+    ///
+    /// 接口未公开文档，解析采用宽松模式：`data` 可以是明细数组、包含明细数组的对象，
+    /// 或“名称 → 数量”的映射；同名条目合并累加。
+    pub fn from_response(
+        by: UsageDimension,
+        start: DateTime<FixedOffset>,
+        end: DateTime<FixedOffset>,
+        body: &serde_json::Value,
+    ) -> Result<Self, String> {
+        let data = body.get("data").ok_or("响应中缺少 data 字段")?;
+        let mut entries: Vec<UsageEntry> = Vec::new();
+
+        for entry in usage_entries(data, by)? {
+            match entries.iter_mut().find(|e| e.name == entry.name) {
+                Some(existing) => {
+                    existing.tokens += entry.tokens;
+                    existing.calls += entry.calls;
+                }
+                None => entries.push(entry),
+            }
+        }
+        entries.sort_by_key(|e| std::cmp::Reverse((e.tokens, e.calls)));
+
+        Ok(UsageBreakdown {
+            by,
+            start,
+            end,
+            entries,
+        })
+    }
+
+    /// token 消耗合计
+    pub fn total_tokens(&self) -> u64 {
+        self.entries.iter().map(|e| e.tokens).sum()
+    }
+
+    /// 调用次数合计
+    pub fn total_calls(&self) -> u64 {
+        self.entries.iter().map(|e| e.calls).sum()
+    }
+}
+
+/// 按响应形态提取明细条目
+fn usage_entries(data: &serde_json::Value, by: UsageDimension) -> Result<Vec<UsageEntry>, String> {
+    use serde_json::Value;
+
+    let items = match data {
+        Value::Null => return Ok(Vec::new()),
+        Value::Array(items) => items,
+        Value::Object(object) => {
+            let list = LIST_KEYS
+                .iter()
+                .filter_map(|key| object.get(*key))
+                .chain(object.values())
+                .find_map(Value::as_array);
+            match list {
+                Some(items) => items,
+                // “名称 → 数量”映射
+                None => {
+                    return Ok(object
+                        .iter()
+                        .filter_map(|(name, value)| {
+                            let n = lenient_number(value).ok().flatten()?.max(0.0).round() as u64;
+                            Some(match by {
+                                UsageDimension::Model => UsageEntry { name: name.clone(), tokens: n, calls: 0 },
+                                UsageDimension::Tool => UsageEntry { name: name.clone(), tokens: 0, calls: n },
+                            })
+                        })
+                        .collect())
+                }
+            }
+        }
+        other => return Err(format!("data 应为对象或数组，实际为 {}", other)),
+    };
+
+    let field = |item: &Value, keys: &[&str]| {
+        keys.iter()
+            .filter_map(|key| item.get(*key))
+            .find_map(|v| lenient_number(v).ok().flatten())
+            .map(|n| n.max(0.0).round() as u64)
+            .unwrap_or(0)
+    };
+
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let name = NAME_KEYS
+                .iter()
+                .filter_map(|key| item.get(*key))
+                .find_map(Value::as_str)?;
+            Some(UsageEntry {
+                name: name.to_string(),
+                tokens: field(item, TOKEN_KEYS),
+                calls: field(item, CALL_KEYS),
+            })
+        })
+        .collect())
 }
 
 #[cfg(test)]
@@ -149,6 +660,7 @@ mod tests {
             remaining: 67625968,
             percentage: 66.0,
             next_reset_time: Some(1768328328345),
+            extra: BTreeMap::new(),
         }
     }
 
@@ -196,6 +708,231 @@ mod tests {
         assert!(plan.validate().is_err());
     }
 
+    #[test]
+    fn test_plan_info_from_contract() {
+        let response: PlanResponse = serde_json::from_str(
+            r#"{"code":200,"msg":"success","data":{"plan_id":"premium_plan","plan_name":"高级版",
+                "total_quota":1000000,"used_quota":250000,"remaining_quota":750000,
+                "usage_percentage":25.0,"start_date":"2026-01-01T00:00:00Z",
+                "end_date":"2026-12-31T23:59:59Z","token_type":"tokens"}}"#,
+        )
+        .unwrap();
+        let plan = ApiPlan::from_plan_info(&response.data);
+
+        assert_eq!(plan.plan_id.as_deref(), Some("premium_plan"));
+        assert_eq!(plan.plan_name, "高级版");
+        assert_eq!(plan.total_quota, 1000000);
+        assert_eq!(plan.remaining_quota, 750000);
+        assert_eq!(plan.usage_percentage, 25.0);
+        assert_eq!(plan.token_type.as_deref(), Some("tokens"));
+        assert_eq!(plan.start_date.unwrap().to_rfc3339(), "2026-01-01T00:00:00+00:00");
+        assert_eq!(plan.end_date.unwrap().to_rfc3339(), "2026-12-31T23:59:59+00:00");
+        assert!(plan.validate().is_ok());
+    }
+
+    #[test]
+    fn test_plan_info_lenient_and_date_validation() {
+        let info: PlanInfo = serde_json::from_str(
+            r#"{"plan_name":"基础版","total_quota":"1000","used_quota":100,
+                "start_date":"2026-12-31","end_date":"2026-01-01"}"#,
+        )
+        .unwrap();
+        let item = info.to_limit_item();
+        assert_eq!(item.limit_type, PLAN_QUOTA);
+        assert_eq!(item.remaining, 900);
+        assert_eq!(item.percentage, 10.0);
+
+        let plan = ApiPlan::from_plan_info(&info);
+        assert!(plan.validate().unwrap_err().contains("早于开始日期"));
+    }
+
+    fn usage_range() -> (DateTime<FixedOffset>, DateTime<FixedOffset>) {
+        (
+            DateTime::parse_from_rfc3339("2026-10-12T00:00:00+08:00").unwrap(),
+            DateTime::parse_from_rfc3339("2026-10-18T23:59:59+08:00").unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_usage_breakdown_from_list() {
+        let (start, end) = usage_range();
+        let body = serde_json::json!({
+            "code": 200,
+            "data": {"list": [
+                {"modelName": "glm-4.6", "totalTokens": "1200", "callCount": 3},
+                {"modelName": "glm-4.5-air", "totalTokens": 5000, "callCount": 10},
+                {"modelName": "glm-4.6", "totalTokens": 800, "callCount": 1},
+                {"totalTokens": 1}
+            ]}
+        });
+        let breakdown = UsageBreakdown::from_response(UsageDimension::Model, start, end, &body).unwrap();
+
+        assert_eq!(breakdown.entries.len(), 2);
+        assert_eq!(breakdown.entries[0], UsageEntry { name: "glm-4.5-air".to_string(), tokens: 5000, calls: 10 });
+        assert_eq!(breakdown.entries[1], UsageEntry { name: "glm-4.6".to_string(), tokens: 2000, calls: 4 });
+        assert_eq!(breakdown.total_tokens(), 7000);
+        assert_eq!(breakdown.total_calls(), 14);
+    }
+
+    #[test]
+    fn test_usage_breakdown_from_map_and_array() {
+        let (start, end) = usage_range();
+        let body = serde_json::json!({"data": {"web-search": 12, "vision": "3"}});
+        let breakdown = UsageBreakdown::from_response(UsageDimension::Tool, start, end, &body).unwrap();
+        assert_eq!(breakdown.entries[0].name, "web-search");
+        assert_eq!(breakdown.entries[0].calls, 12);
+        assert_eq!(breakdown.total_calls(), 15);
+
+        let body = serde_json::json!({"data": [{"toolName": "vision", "count": 2}]});
+        let breakdown = UsageBreakdown::from_response(UsageDimension::Tool, start, end, &body).unwrap();
+        assert_eq!(breakdown.entries[0].calls, 2);
+
+        assert!(UsageBreakdown::from_response(UsageDimension::Tool, start, end, &serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn test_consistency_warnings_clean() {
+        let data = ApiData {
+            limits: vec![create_test_limit_item()],
+        };
+        let now = DateTime::from_timestamp_millis(1768328328345 - 1000).unwrap();
+        assert!(data.consistency_warnings(now).is_empty());
+    }
+
+    #[test]
+    fn test_consistency_warnings_detects_mismatches() {
+        let mut item = create_test_limit_item();
+        item.remaining = 1;
+        item.percentage = 10.0;
+        let data = ApiData { limits: vec![item] };
+        let now = DateTime::from_timestamp_millis(1768328328345 + 1000).unwrap();
+
+        let warnings = data.consistency_warnings(now);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].contains("不等于总额度"));
+        assert!(warnings[1].contains("与计算值 (66.2%) 不一致"));
+        assert!(warnings[2].contains("已过去"));
+    }
+
+    #[test]
+    fn test_consistency_warnings_runs_plan_validation() {
+        let mut item = create_test_limit_item();
+        item.current_value = 300000000;
+        let data = ApiData { limits: vec![item] };
+        let now = DateTime::from_timestamp_millis(0).unwrap();
+        let warnings = data.consistency_warnings(now);
+        assert!(warnings.iter().any(|w| w.contains("不能超过总额度")));
+    }
+
+    #[test]
+    fn test_limit_item_lenient_numbers() {
+        let item: LimitItem = serde_json::from_str(
+            r#"{"type":"TOKENS_LIMIT","unit":"3","number":5,"usage":"1000","currentValue":250,"remaining":"750","percentage":"25","nextResetTime":"1768328328345"}"#,
+        )
+        .unwrap();
+        assert_eq!(item.unit, 3);
+        assert_eq!(item.usage, 1000);
+        assert_eq!(item.remaining, 750);
+        assert_eq!(item.percentage, 25.0);
+        assert_eq!(item.next_reset_time, Some(1768328328345));
+    }
+
+    #[test]
+    fn test_limit_item_derives_missing_fields() {
+        let item: LimitItem = serde_json::from_str(
+            r#"{"type":"TIME_LIMIT","usage":1000,"currentValue":164,"remaining":null}"#,
+        )
+        .unwrap();
+        assert_eq!(item.remaining, 836);
+        assert!((item.percentage - 16.4).abs() < 1e-9);
+        assert_eq!(item.unit, 0);
+
+        let item: LimitItem = serde_json::from_str(
+            r#"{"type":"TIME_LIMIT","currentValue":164,"remaining":836}"#,
+        )
+        .unwrap();
+        assert_eq!(item.usage, 1000);
+    }
+
+    #[test]
+    fn test_limit_item_overflowing_total() {
+        let item: LimitItem = serde_json::from_str(
+            r#"{"type":"TOKENS_LIMIT","currentValue":18446744073709551615,"remaining":1}"#,
+        )
+        .unwrap();
+        assert_eq!(item.usage, 0);
+        let data = ApiData { limits: vec![item] };
+        assert!(data
+            .consistency_warnings(Utc::now())
+            .iter()
+            .any(|w| w.contains("不等于总额度")));
+    }
+
+    #[test]
+    fn test_limit_item_preserves_unknown_fields() {
+        let item: LimitItem = serde_json::from_str(
+            r#"{"type":"TOKENS_LIMIT","usage":10,"currentValue":1,"remaining":9,"percentage":10,"windowName":"5h"}"#,
+        )
+        .unwrap();
+        assert_eq!(item.extra.get("windowName"), Some(&serde_json::json!("5h")));
+
+        let json = serde_json::to_value(&item).unwrap();
+        assert_eq!(json["windowName"], "5h");
+    }
+
+    #[test]
+    fn test_limit_item_rejects_garbage_number() {
+        let result: Result<LimitItem, _> =
+            serde_json::from_str(r#"{"type":"TOKENS_LIMIT","usage":"很多"}"#);
+        assert!(result.is_err());
+        let result: Result<LimitItem, _> =
+            serde_json::from_str(r#"{"type":"TOKENS_LIMIT","currentValue":"inf","remaining":1}"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_error_response_monitor_business_error() {
+        let resp: ApiErrorResponse = serde_json::from_str(
+            r#"{"code":1001,"msg":"Header中未收到Authorization参数","success":false}"#,
+        )
+        .unwrap();
+        assert!(resp.is_business_error());
+        assert_eq!(resp.upstream_code().as_deref(), Some("1001"));
+        assert_eq!(resp.message(), Some("Header中未收到Authorization参数"));
+    }
+
+    #[test]
+    fn test_error_response_contract_format() {
+        let resp: ApiErrorResponse = serde_json::from_str(
+            r#"{"code":401,"msg":"Invalid API key","error":"unauthorized"}"#,
+        )
+        .unwrap();
+        assert!(resp.is_business_error());
+        assert_eq!(resp.upstream_code().as_deref(), Some("401"));
+        assert_eq!(resp.message(), Some("Invalid API key"));
+    }
+
+    #[test]
+    fn test_error_response_open_platform_format() {
+        let resp: ApiErrorResponse = serde_json::from_str(
+            r#"{"error":{"code":"1113","message":"您的账户已欠费"},"request_id":"req-123"}"#,
+        )
+        .unwrap();
+        assert!(resp.is_business_error());
+        assert_eq!(resp.upstream_code().as_deref(), Some("1113"));
+        assert_eq!(resp.message(), Some("您的账户已欠费"));
+        assert_eq!(resp.request_id.as_deref(), Some("req-123"));
+    }
+
+    #[test]
+    fn test_error_response_success_envelope() {
+        let resp: ApiErrorResponse = serde_json::from_str(
+            r#"{"code":200,"msg":"操作成功","success":true,"data":{"limits":[]}}"#,
+        )
+        .unwrap();
+        assert!(!resp.is_business_error());
+    }
+
     #[test]
     fn test_api_plan_time_limit() {
         let item = LimitItem {
@@ -207,6 +944,7 @@ mod tests {
             remaining: 836,
             percentage: 16.0,
             next_reset_time: None,
+            extra: BTreeMap::new(),
         };
         let plan = ApiPlan::from_limit_item(&item);
         assert_eq!(plan.plan_name, "时间限制");
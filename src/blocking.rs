@@ -0,0 +1,75 @@
+//! 同步客户端（需启用 `blocking` feature）
+//!
+//! 供没有异步运行时的程序（小工具、构建脚本等）查询额度。[`GlmBlockingClient`]
+//! 内部持有一个单线程 tokio 运行时并在其上驱动 [`GlmClient`]，因此请求方式、
+//! 响应解析与错误映射都与异步客户端一致。
+//!
+//! 不要在异步上下文中调用其方法：在 tokio 运行时内阻塞等待会导致 panic，
+//! 异步程序应直接使用 [`GlmClient`]。
+
+use crate::api::{ApiData, ApiPlan, GlmClient, GlmClientBuilder};
+use crate::config::Config;
+use crate::error::Result;
+use crate::quota::Quota;
+use tokio::runtime::{Builder, Runtime};
+
+/// 同步 GLM API 客户端
+///
+/// ```no_run
+/// # fn example() -> glm::Result<()> {
+/// let client = glm::GlmClient::builder()
+///     .api_key("your-api-key")
+///     .build_blocking()?;
+/// let quota = client.fetch_quota()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct GlmBlockingClient {
+    /// 实际发送请求的异步客户端
+    inner: GlmClient,
+    /// 驱动请求的单线程运行时
+    runtime: Runtime,
+}
+
+impl GlmBlockingClient {
+    /// 创建同步客户端
+    ///
+    /// 与 [`GlmClient::new`] 相同，不校验配置；嵌入时建议使用
+    /// [`GlmClientBuilder::build_blocking`]。
+    pub fn new(config: Config) -> Result<Self> {
+        Self::from_client(GlmClient::new(config)?)
+    }
+
+    /// 包装已创建的异步客户端
+    pub fn from_client(inner: GlmClient) -> Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(GlmBlockingClient { inner, runtime })
+    }
+
+    /// 客户端使用的配置
+    pub fn config(&self) -> &Config {
+        self.inner.config()
+    }
+
+    /// 获取 API 使用情况，语义与 [`GlmClient::fetch_usage`] 相同
+    pub fn fetch_usage(&self) -> Result<ApiData> {
+        self.runtime.block_on(self.inner.fetch_usage())
+    }
+
+    /// 获取额度，语义与 [`GlmClient::fetch_quota`] 相同
+    pub fn fetch_quota(&self) -> Result<Quota> {
+        self.runtime.block_on(self.inner.fetch_quota())
+    }
+
+    /// 获取订阅计划信息，语义与 [`GlmClient::fetch_plan`] 相同
+    pub fn fetch_plan(&self) -> Result<ApiPlan> {
+        self.runtime.block_on(self.inner.fetch_plan())
+    }
+}
+
+impl GlmClientBuilder {
+    /// 校验配置并创建同步客户端
+    pub fn build_blocking(self) -> Result<GlmBlockingClient> {
+        GlmBlockingClient::from_client(self.build()?)
+    }
+}
@@ -0,0 +1,267 @@
+//! 命令行程序的配置
+//!
+//! 快照记录、告警、钩子、额度守卫与守护进程只由 `glm` 命令行程序使用，
+//! 不属于库的 [`Config`]。它们与连接配置写在同一个配置文件的顶层：
+//!
+//! ```yaml
+//! api_key: sk.xxxxxxxxxxxxxx
+//! history: true
+//! alerts:
+//!   sinks: []
+//! daemon:
+//!   interval: 60
+//! ```
+
+use crate::alert::AlertConfig;
+use crate::config::{expand_home, Config};
+use crate::daemon::DaemonConfig;
+use crate::error::{CliError, Result};
+use crate::guard::GuardConfig;
+use crate::hooks::HookConfig;
+use serde::Deserialize;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+
+/// 命令行程序的配置：库的 [`Config`] 加上仅命令行使用的配置段
+///
+/// 通过 `Deref` 直接访问 [`Config`] 的字段与方法。
+#[derive(Debug, Clone, Deserialize)]
+pub struct CliConfig {
+    /// 连接与显示配置
+    #[serde(skip)]
+    pub base: Config,
+
+    /// 每次成功查询后记录使用情况快照（默认 true）
+    ///
+    /// 快照写入 `~/.glm/history.jsonl`，供 `glm report` 统计。
+    #[serde(default = "default_true")]
+    pub history: bool,
+
+    /// 阈值告警（`glm alert` / `glm watch`）
+    #[serde(default)]
+    pub alerts: AlertConfig,
+
+    /// 额度事件钩子命令
+    #[serde(default)]
+    pub hooks: HookConfig,
+
+    /// `glm guard` 的额度下限与复查设置
+    #[serde(default)]
+    pub guard: GuardConfig,
+
+    /// 后台守护进程（`glm daemon`）
+    #[serde(default)]
+    pub daemon: DaemonConfig,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for CliConfig {
+    fn default() -> Self {
+        CliConfig::from(Config::default())
+    }
+}
+
+impl From<Config> for CliConfig {
+    /// 以库的配置为基础，命令行配置段取默认值
+    fn from(base: Config) -> Self {
+        CliConfig {
+            base,
+            history: default_true(),
+            alerts: AlertConfig::default(),
+            hooks: HookConfig::default(),
+            guard: GuardConfig::default(),
+            daemon: DaemonConfig::default(),
+        }
+    }
+}
+
+impl Deref for CliConfig {
+    type Target = Config;
+
+    fn deref(&self) -> &Config {
+        &self.base
+    }
+}
+
+impl DerefMut for CliConfig {
+    fn deref_mut(&mut self) -> &mut Config {
+        &mut self.base
+    }
+}
+
+impl CliConfig {
+    /// 从环境变量和配置文件加载配置
+    ///
+    /// 连接配置的加载规则见 [`Config::load`]；命令行配置段只来自配置文件，
+    /// 其中 `history` 与 `daemon.enabled` 可由环境变量 `GLM_HISTORY` / `GLM_DAEMON` 覆盖。
+    pub fn load() -> Result<Self> {
+        let file = Config::read_config_file()?;
+        let base = Config::load_from(file.as_ref())?;
+        let mut config = match &file {
+            Some((config_path, content)) => Self::from_yaml(content).map_err(|e| {
+                CliError::ConfigError(format!("配置文件格式错误 {}: {}", config_path.display(), e))
+            })?,
+            None => CliConfig::default(),
+        };
+        config.base = base;
+        config.apply_env();
+        config.expand_paths();
+        Ok(config)
+    }
+
+    /// 加载 `~/.glm/profiles/` 下的额外配置（`*.yaml` / `*.yml`，按文件名排序）
+    ///
+    /// 供 `glm tui` 为每份配置显示一个标签页。目录不存在时返回空列表。
+    pub fn load_profiles() -> Result<Vec<Self>> {
+        Self::load_profiles_from(&Config::profiles_dir()?)
+    }
+
+    fn load_profiles_from(dir: &Path) -> Result<Vec<Self>> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(CliError::ConfigError(format!(
+                    "无法读取配置目录 {}: {}",
+                    dir.display(),
+                    e
+                )))
+            }
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file()
+                    && matches!(path.extension().and_then(|ext| ext.to_str()), Some("yaml" | "yml"))
+            })
+            .collect();
+        paths.sort();
+        paths.iter().map(|path| Self::load_profile(path)).collect()
+    }
+
+    /// 加载一份额外配置
+    ///
+    /// 名称取 `profile` 字段，未设置时取文件名。快照历史属于主配置，
+    /// 额外配置不记录快照，也不经由守护进程查询。
+    fn load_profile(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            CliError::ConfigError(format!("无法读取配置文件 {}: {}", path.display(), e))
+        })?;
+        let mut config = Self::from_yaml(&content).map_err(|e| {
+            CliError::ConfigError(format!("配置文件格式错误 {}: {}", path.display(), e))
+        })?;
+        config.base = Config::from_profile(path, &content)?;
+        if config.profile.is_none() {
+            config.profile = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+        }
+        config.history = false;
+        config.daemon.enabled = false;
+        config.expand_paths();
+        config.validate().map_err(|e| {
+            CliError::ConfigError(format!("配置文件 {} 无效: {}", path.display(), e))
+        })?;
+        Ok(config)
+    }
+
+    /// 解析配置文件中的命令行配置段（`base` 取默认值）
+    fn from_yaml(content: &str) -> std::result::Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(content)
+    }
+
+    /// 使用环境变量覆盖命令行配置段
+    fn apply_env(&mut self) {
+        if let Ok(value) = std::env::var("GLM_HISTORY") {
+            self.history = !matches!(value.as_str(), "0" | "false" | "no");
+        }
+        if let Ok(value) = std::env::var("GLM_DAEMON") {
+            self.daemon.enabled = !matches!(value.as_str(), "0" | "false" | "no");
+        }
+    }
+
+    /// 展开告警状态文件与守护进程套接字路径开头的 `~`
+    fn expand_paths(&mut self) {
+        for path in [&mut self.alerts.state_file, &mut self.daemon.socket].into_iter().flatten() {
+            *path = expand_home(path);
+        }
+    }
+
+    /// 验证配置（连接配置与钩子、守卫、守护进程配置段）
+    ///
+    /// 告警规则只在 `glm alert` / `glm watch` 等用到时由调用方单独验证。
+    pub fn validate(&self) -> Result<()> {
+        self.base.validate()?;
+        self.hooks.validate()?;
+        self.guard.validate()?;
+        self.daemon.validate()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Region;
+
+    #[test]
+    fn test_sections_from_yaml() {
+        let yaml = "api_key: valid_api_key\nhistory: false\nhooks:\n  on_reset: echo reset\ndaemon:\n  interval: 120\n";
+        let config = CliConfig::from_yaml(yaml).unwrap();
+        assert!(!config.history);
+        assert_eq!(config.hooks.on_reset.as_deref(), Some("echo reset"));
+        assert_eq!(config.daemon.interval, 120);
+        // 连接配置由 Config::load 负责，此处保持默认值
+        assert!(config.api_key.is_empty());
+        assert!(CliConfig::from_yaml("daemon: [1]").is_err());
+    }
+
+    #[test]
+    fn test_expand_paths() {
+        let home = dirs::home_dir().unwrap();
+        let mut config = CliConfig::default();
+        config.alerts.state_file = Some(PathBuf::from("~/.glm/alert-state.json"));
+        config.daemon.socket = Some(PathBuf::from("~"));
+        config.expand_paths();
+        assert_eq!(config.alerts.state_file, Some(home.join(".glm/alert-state.json")));
+        assert_eq!(config.daemon.socket, Some(home));
+    }
+
+    #[test]
+    fn test_load_profiles() {
+        let dir = std::env::temp_dir().join(format!("glm-profiles-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("b-intl.yaml"), "api_key: test_api_key_intl\nregion: intl\n").unwrap();
+        std::fs::write(
+            dir.join("a-team.yml"),
+            "api_key: test_api_key_team\nprofile: team\nhistory: true\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let profiles = CliConfig::load_profiles_from(&dir).unwrap();
+        let names: Vec<_> = profiles.iter().map(|p| p.profile.as_deref().unwrap()).collect();
+        assert_eq!(names, ["team", "b-intl"]);
+        assert_eq!(profiles[1].api_url, Region::Intl.default_api_url());
+        assert!(profiles.iter().all(|p| !p.history && !p.daemon.enabled));
+
+        std::fs::write(dir.join("c-bad.yaml"), "api_key: short\n").unwrap();
+        let err = CliConfig::load_profiles_from(&dir).unwrap_err();
+        assert!(err.to_string().contains("c-bad.yaml"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(CliConfig::load_profiles_from(&dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_validate_sections() {
+        let mut config = CliConfig::from(Config {
+            api_key: "test_api_key_12345".to_string(),
+            ..Config::default()
+        });
+        assert!(config.validate().is_ok());
+        config.daemon.interval = 1;
+        assert_eq!(config.validate().unwrap_err().exit_code(), crate::error::exit_code::CONFIG);
+    }
+}
@@ -4,109 +4,688 @@
 //! 环境变量优先级高于配置文件。
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use anyhow::Result;
+use std::path::{Path, PathBuf};
+use crate::output::DisplayZone;
+use crate::error::{CliError, Result};
+
+/// 构造配置错误（`CliError::ConfigError`），以便进程退出码能够区分错误类别
+macro_rules! config_err {
+    ($($arg:tt)*) => {
+        CliError::ConfigError(format!($($arg)*))
+    };
+}
+
+/// API 接口形式
+///
+/// 智谱开放平台存在两套额度查询接口：
+/// 监控接口 `{api_url}/monitor/usage/quota/limit`（默认 `https://bigmodel.cn/api`）
+/// 与计划接口 `{api_url}/plans`（`https://open.bigmodel.cn/api/paas/v4`）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiFlavor {
+    /// 根据 `api_url` 自动判断（路径包含 `/paas/v4` 时使用计划接口）
+    #[default]
+    Auto,
+    /// 监控接口，返回各项额度限制
+    Monitor,
+    /// 计划接口，返回订阅名称与有效期
+    Plans,
+}
+
+impl ApiFlavor {
+    /// 解析为具体的接口形式（`Auto` 按 URL 判断）
+    pub fn resolve(self, api_url: &str) -> ApiFlavor {
+        match self {
+            ApiFlavor::Auto if api_url.trim_end_matches('/').contains("/paas/v4") => ApiFlavor::Plans,
+            ApiFlavor::Auto => ApiFlavor::Monitor,
+            other => other,
+        }
+    }
+
+    /// 额度查询端点相对于 `api_url` 的路径
+    pub fn endpoint_path(self) -> &'static str {
+        match self {
+            ApiFlavor::Plans => "/plans",
+            ApiFlavor::Auto | ApiFlavor::Monitor => "/monitor/usage/quota/limit",
+        }
+    }
+
+    /// 配置中使用的名称
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiFlavor::Auto => "auto",
+            ApiFlavor::Monitor => "monitor",
+            ApiFlavor::Plans => "plans",
+        }
+    }
+}
+
+impl std::str::FromStr for ApiFlavor {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(ApiFlavor::Auto),
+            "monitor" => Ok(ApiFlavor::Monitor),
+            "plans" => Ok(ApiFlavor::Plans),
+            other => Err(format!("未知的接口形式: {}（可选: auto、monitor、plans）", other)),
+        }
+    }
+}
+
+/// 服务区域
+///
+/// 决定默认的 API 地址、显示语言，以及按哪个时区理解额度重置时间。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Region {
+    /// 中国大陆站（bigmodel.cn）
+    #[default]
+    Cn,
+    /// 国际站（z.ai）
+    Intl,
+}
+
+impl Region {
+    /// 区域默认的 API 地址
+    pub fn default_api_url(self) -> &'static str {
+        match self {
+            Region::Cn => "https://bigmodel.cn/api",
+            Region::Intl => "https://api.z.ai/api",
+        }
+    }
+
+    /// 区域默认的显示语言
+    pub fn default_language(self) -> Language {
+        match self {
+            Region::Cn => Language::Zh,
+            Region::Intl => Language::En,
+        }
+    }
+
+    /// 额度按月重置所依据的时区（中国大陆站为 UTC+8，国际站为 UTC）
+    pub fn reset_offset(self) -> chrono::FixedOffset {
+        let hours = match self {
+            Region::Cn => 8,
+            Region::Intl => 0,
+        };
+        chrono::FixedOffset::east_opt(hours * 3600).expect("时区偏移有效")
+    }
+
+    /// 配置中使用的名称
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Region::Cn => "cn",
+            Region::Intl => "intl",
+        }
+    }
+}
+
+impl std::str::FromStr for Region {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cn" => Ok(Region::Cn),
+            "intl" => Ok(Region::Intl),
+            other => Err(format!("未知的区域: {}（可选: cn、intl）", other)),
+        }
+    }
+}
+
+/// 使用情况输出的显示语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    /// 简体中文
+    Zh,
+    /// 英文
+    En,
+}
+
+impl Language {
+    /// 按语言选择文案
+    pub fn pick<'a>(self, zh: &'a str, en: &'a str) -> &'a str {
+        match self {
+            Language::Zh => zh,
+            Language::En => en,
+        }
+    }
+}
+
+impl std::str::FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "zh" | "zh-cn" => Ok(Language::Zh),
+            "en" => Ok(Language::En),
+            other => Err(format!("未知的显示语言: {}（可选: zh、en）", other)),
+        }
+    }
+}
 
 /// 用户配置
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
-    /// GLM API 密钥
+    /// GLM API 密钥（可由环境变量 `GLM_API_KEY` 提供）
+    #[serde(default)]
     pub api_key: String,
 
-    /// GLM API 端点 URL（可选，有默认值）
+    /// 服务区域（可选，默认 cn）
+    ///
+    /// 未显式设置 `api_url` 时据此选择 API 地址。
+    #[serde(default)]
+    pub region: Region,
+
+    /// 显示语言（可选，默认随区域：cn 为中文，intl 为英文）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+
+    /// 显示时间所用的时区（可选，默认本地时区）
+    ///
+    /// 可为 IANA 名称（`Asia/Shanghai`）、`UTC`、固定偏移（`+08:00`）或 `local`。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+
+    /// 配置名称（可选）
+    ///
+    /// 作为导出指标的 `profile` 标签，用于区分多套配置（如团队账号与个人账号）的数据。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+
+    /// GLM API 端点 URL（可选，默认取区域对应的地址）
     #[serde(default = "default_api_url")]
     pub api_url: String,
 
+    /// `api_url` 是否为显式指定（配置文件、`GLM_API_URL` 或构建器），不参与序列化
+    ///
+    /// 显式指定时切换区域不会改写 API 地址。
+    #[serde(skip)]
+    pub api_url_explicit: bool,
+
+    /// API 接口形式（可选，默认根据 `api_url` 自动判断）
+    #[serde(default)]
+    pub api_flavor: ApiFlavor,
+
     /// 请求超时时间（秒，可选，默认 30）
     #[serde(default = "default_timeout")]
     pub timeout: u64,
+
+    /// 是否允许使用明文 HTTP 的 API URL（默认 false）
+    ///
+    /// 回环地址（localhost、127.0.0.1、::1）始终允许 HTTP，便于对接本地测试服务。
+    #[serde(default)]
+    pub allow_insecure_http: bool,
+
+    /// 严格校验响应结构（默认 false）
+    ///
+    /// 开启后响应中任何字段缺失、类型不符或出现未知字段都会报错，
+    /// 而不是宽松解析。
+    #[serde(default)]
+    pub strict_schema: bool,
+
+    /// 数据一致性检查发现问题时报错（默认 false，仅输出警告）
+    ///
+    /// 每次查询使用情况后都会核对额度数据，见 [`crate::api::ApiData::consistency_warnings`]。
+    #[serde(default)]
+    pub strict: bool,
+
+    /// 连接超时时间（秒，可选，未设置时仅受 `timeout` 限制）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+
+    /// 读取超时时间（秒，可选，未设置时仅受 `timeout` 限制）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_timeout: Option<u64>,
+
+    /// 显式代理地址（可选，如 `http://proxy.corp:8080`）
+    ///
+    /// 未设置时沿用系统环境变量 `HTTPS_PROXY` / `HTTP_PROXY`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+
+    /// 不走代理的主机列表（可选，逗号分隔，如 `localhost,.corp`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+
+    /// 额外信任的 CA 证书文件路径（PEM 格式，可包含多个证书）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<PathBuf>,
+
+    /// 客户端证书文件路径（PEM 格式，用于 mTLS）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<PathBuf>,
+
+    /// 客户端私钥文件路径（PKCS#8 PEM 格式，与 `client_cert` 配合使用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            api_key: String::new(),
+            region: Region::Cn,
+            language: None,
+            timezone: None,
+            profile: None,
+            api_url: default_api_url(),
+            api_url_explicit: false,
+            api_flavor: ApiFlavor::Auto,
+            timeout: default_timeout(),
+            allow_insecure_http: false,
+            strict_schema: false,
+            strict: false,
+            connect_timeout: None,
+            read_timeout: None,
+            proxy: None,
+            no_proxy: None,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+        }
+    }
 }
 
 fn default_api_url() -> String {
-    "https://bigmodel.cn/api".to_string()
+    Region::Cn.default_api_url().to_string()
 }
 
 fn default_timeout() -> u64 {
     30
 }
 
+/// 读取并解析数值型环境变量
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok().and_then(|s| s.parse().ok())
+}
+
+/// 展开路径开头的 `~`（`~` 或 `~/...`）为用户主目录
+///
+/// 其他形式（如 `~user/...`）以及无法确定主目录时原样返回。
+pub(crate) fn expand_home(path: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => match dirs::home_dir() {
+            Some(home) => home.join(rest),
+            None => path.to_path_buf(),
+        },
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// 检查配置中引用的文件是否存在
+fn check_file_exists(path: &Path) -> Result<()> {
+    if !path.is_file() {
+        return Err(config_err!("证书文件不存在\n\n原因: 找不到文件 {}", path.display())
+            .suggest(["请检查配置中的证书路径是否正确"]));
+    }
+    Ok(())
+}
+
 impl Config {
     /// 从环境变量和配置文件加载配置
     ///
     /// 优先级: 环境变量 > 配置文件 > 默认值
+    ///
+    /// 配置文件存在时作为基础配置，环境变量逐项覆盖其中的同名字段。
     pub fn load() -> Result<Self> {
-        // 1. 尝试从环境变量加载
+        Self::load_from(Self::read_config_file()?.as_ref())
+    }
+
+    /// 读取配置文件，返回文件路径与内容（文件不存在时为 `None`）
+    pub(crate) fn read_config_file() -> Result<Option<(PathBuf, String)>> {
+        match Self::config_file_path() {
+            Ok(config_path) if config_path.exists() => {
+                tracing::debug!(path = %config_path.display(), "读取配置文件");
+                let content = std::fs::read_to_string(&config_path).map_err(|e| {
+                    config_err!("无法读取配置文件 {}: {}", config_path.display(), e)
+                })?;
+                Ok(Some((config_path, content)))
+            }
+            Ok(config_path) => {
+                tracing::debug!(path = %config_path.display(), "配置文件不存在");
+                Ok(None)
+            }
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// 以 [`read_config_file`](Self::read_config_file) 读取的内容为基础加载配置
+    pub(crate) fn load_from(file: Option<&(PathBuf, String)>) -> Result<Self> {
+        // 1. 配置文件作为基础配置
+        let mut config = match file {
+            Some((config_path, content)) => Some(Self::from_yaml(content).map_err(|e| {
+                config_err!("配置文件格式错误 {}: {}", config_path.display(), e)
+            })?),
+            None => None,
+        };
+
+        // 2. 环境变量覆盖
         if let Ok(api_key) = std::env::var("GLM_API_KEY") {
-            return Ok(Config {
-                api_key,
-                api_url: std::env::var("GLM_API_URL")
-                    .unwrap_or_else(|_| default_api_url()),
-                timeout: std::env::var("GLM_TIMEOUT")
-                    .ok()
-                    .and_then(|s| s.parse().ok())
-                    .unwrap_or_else(default_timeout),
-            });
-        }
-
-        // 2. 尝试从配置文件加载
-        let config_path = dirs::home_dir()
-            .ok_or_else(||
-                anyhow::anyhow!("无法确定主目录")
-            )?
-            .join(".glm/config.yaml");
-
-        if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path)?;
-            let config: Config = serde_yaml::from_str(&content)?;
-            return Ok(config);
+            tracing::debug!("使用环境变量 GLM_API_KEY 中的 API 密钥");
+            config
+                .get_or_insert_with(Config::default)
+                .api_key = api_key;
         }
 
         // 3. 未找到配置
-        Err(anyhow::anyhow!(
-            "未找到 API 密钥配置。\n\n请选择以下方式之一配置：\n\
-             1. 设置环境变量: export GLM_API_KEY=\"sk.xxxxxxxxxxxxxx\"\n\
-             2. 创建配置文件: ~/.glm/config.yaml\n\n\
-             配置文件示例:\n\
-             api_key: sk.xxxxxxxxxxxxxx\n\
-             api_url: https://open.bigmodel.cn/api/paas/v4\n\
-             timeout: 30"
-        ))
+        let Some(mut config) = config.filter(|c| !c.api_key.is_empty()) else {
+            return Err(config_err!(
+                "未找到 API 密钥配置。\n\n\
+                 配置文件示例:\n\
+                 api_key: sk.xxxxxxxxxxxxxx\n\
+                 api_url: https://open.bigmodel.cn/api/paas/v4\n\
+                 timeout: 30"
+            )
+            .suggest([
+                "设置环境变量: export GLM_API_KEY=\"sk.xxxxxxxxxxxxxx\"",
+                "创建配置文件: ~/.glm/config.yaml",
+            ]));
+        };
+
+        config.apply_region_default();
+        config.apply_env();
+        config.expand_paths();
+        tracing::info!(
+            api_url = %config.api_url,
+            api_key = %config.masked_key(),
+            timeout = config.timeout,
+            proxy = config.proxy.as_deref().unwrap_or("-"),
+            "配置加载完成"
+        );
+        Ok(config)
+    }
+
+    /// 解析额外配置（`~/.glm/profiles/*.yaml`）的连接配置
+    ///
+    /// 额外配置各自独立，不受环境变量覆盖。
+    #[cfg(feature = "cli")]
+    pub(crate) fn from_profile(path: &Path, content: &str) -> Result<Self> {
+        let mut config = Self::from_yaml(content)
+            .map_err(|e| config_err!("配置文件格式错误 {}: {}", path.display(), e))?;
+        config.apply_region_default();
+        config.expand_paths();
+        Ok(config)
+    }
+
+    /// 解析配置文件内容，并记录 `api_url` 是否显式指定
+    fn from_yaml(content: &str) -> std::result::Result<Self, serde_yaml::Error> {
+        let value: serde_yaml::Value = serde_yaml::from_str(content)?;
+        let explicit = value.get("api_url").is_some_and(|url| !url.is_null());
+        let mut config: Config = serde_yaml::from_value(value)?;
+        config.api_url_explicit = explicit;
+        Ok(config)
+    }
+
+    /// 配置文件指定了区域但未指定 `api_url` 时，使用区域对应的地址
+    fn apply_region_default(&mut self) {
+        if !self.api_url_explicit {
+            self.api_url = self.region.default_api_url().to_string();
+        }
+    }
+
+    /// 使用环境变量覆盖配置字段
+    fn apply_env(&mut self) {
+        if let Ok(api_url) = std::env::var("GLM_API_URL") {
+            self.api_url = api_url;
+            self.api_url_explicit = true;
+        }
+        if let Ok(region) = std::env::var("GLM_REGION") {
+            match region.parse() {
+                Ok(region) => self.set_region(region),
+                Err(e) => tracing::warn!("忽略环境变量 GLM_REGION: {}", e),
+            }
+        }
+        if let Ok(language) = std::env::var("GLM_LANGUAGE") {
+            match language.parse() {
+                Ok(language) => self.language = Some(language),
+                Err(e) => tracing::warn!("忽略环境变量 GLM_LANGUAGE: {}", e),
+            }
+        }
+        if let Ok(timezone) = std::env::var("GLM_TIMEZONE") {
+            self.timezone = Some(timezone);
+        }
+        if let Ok(profile) = std::env::var("GLM_PROFILE") {
+            self.profile = Some(profile).filter(|profile| !profile.is_empty());
+        }
+        if let Ok(flavor) = std::env::var("GLM_API_FLAVOR") {
+            match flavor.parse() {
+                Ok(flavor) => self.api_flavor = flavor,
+                Err(e) => tracing::warn!("忽略环境变量 GLM_API_FLAVOR: {}", e),
+            }
+        }
+        if let Some(timeout) = env_u64("GLM_TIMEOUT") {
+            self.timeout = timeout;
+        }
+        if let Ok(value) = std::env::var("GLM_ALLOW_INSECURE_HTTP") {
+            self.allow_insecure_http = matches!(value.as_str(), "1" | "true" | "yes");
+        }
+        if let Some(timeout) = env_u64("GLM_CONNECT_TIMEOUT") {
+            self.connect_timeout = Some(timeout);
+        }
+        if let Some(timeout) = env_u64("GLM_READ_TIMEOUT") {
+            self.read_timeout = Some(timeout);
+        }
+        if let Ok(proxy) = std::env::var("GLM_PROXY") {
+            self.proxy = Some(proxy);
+        }
+        if let Ok(no_proxy) = std::env::var("GLM_NO_PROXY") {
+            self.no_proxy = Some(no_proxy);
+        }
+        if let Ok(path) = std::env::var("GLM_CA_CERT") {
+            self.ca_cert = Some(PathBuf::from(path));
+        }
+        if let Ok(path) = std::env::var("GLM_CLIENT_CERT") {
+            self.client_cert = Some(PathBuf::from(path));
+        }
+        if let Ok(path) = std::env::var("GLM_CLIENT_KEY") {
+            self.client_key = Some(PathBuf::from(path));
+        }
+    }
+
+    /// 展开证书路径开头的 `~`
+    fn expand_paths(&mut self) {
+        let paths = [&mut self.ca_cert, &mut self.client_cert, &mut self.client_key];
+        for path in paths.into_iter().flatten() {
+            *path = expand_home(path);
+        }
     }
 
     /// 验证配置
     pub fn validate(&self) -> Result<()> {
         // 验证 API 密钥格式
         if self.api_key.len() < 10 {
-            return Err(anyhow::anyhow!(
-                "API 密钥长度不足\n\n原因: API 密钥长度必须至少 10 个字符\n\n建议: 请确认您的 API 密钥完整"
-            ));
+            return Err(config_err!("API 密钥长度不足\n\n原因: API 密钥长度必须至少 10 个字符")
+                .suggest(["请确认您的 API 密钥完整"]));
         }
 
         // 验证 URL 格式
-        if !self.api_url.starts_with("https://") {
-            return Err(anyhow::anyhow!(
-                "API URL 必须使用 HTTPS\n\n原因: 当前 URL: {}\n\n建议: 请使用 HTTPS 协议的 URL",
-                self.api_url
-            ));
+        let is_http = self.api_url.starts_with("http://");
+        if !self.api_url.starts_with("https://")
+            && !(is_http && (self.allow_insecure_http || self.is_loopback_url()))
+        {
+            return Err(config_err!("API URL 必须使用 HTTPS\n\n原因: 当前 URL: {}", self.api_url).suggest([
+                "请使用 HTTPS 协议的 URL；如需连接内部 HTTP 网关，请设置 allow_insecure_http: true",
+            ]));
         }
 
         // 验证超时范围
         if self.timeout == 0 || self.timeout > 300 {
-            return Err(anyhow::anyhow!(
-                "超时时间必须在 1-300 秒之间\n\n原因: 当前设置: {} 秒\n\n建议: 设置为 30 秒",
-                self.timeout
-            ));
+            return Err(config_err!("超时时间必须在 1-300 秒之间\n\n原因: 当前设置: {} 秒", self.timeout)
+                .suggest(["设置为 30 秒"]));
+        }
+
+        for (name, value) in [
+            ("connect_timeout", self.connect_timeout),
+            ("read_timeout", self.read_timeout),
+        ] {
+            if let Some(secs) = value {
+                if secs == 0 || secs > 300 {
+                    return Err(config_err!("{} 必须在 1-300 秒之间\n\n原因: 当前设置: {} 秒", name, secs)
+                        .suggest(["删除该项或设置为 10 秒"]));
+                }
+            }
+        }
+
+        // 验证代理地址
+        if let Some(proxy) = &self.proxy {
+            if !proxy.starts_with("http://") && !proxy.starts_with("https://") {
+                return Err(config_err!("代理地址格式错误\n\n原因: 当前代理: {}", proxy)
+                    .suggest(["请使用 http:// 或 https:// 开头的代理地址"]));
+            }
+        }
+
+        // 验证证书文件
+        if self.client_cert.is_some() != self.client_key.is_some() {
+            return Err(config_err!("客户端证书配置不完整\n\n原因: client_cert 和 client_key 必须同时设置")
+                .suggest(["请同时配置客户端证书与私钥路径"]));
+        }
+        for path in [&self.ca_cert, &self.client_cert, &self.client_key]
+            .into_iter()
+            .flatten()
+        {
+            check_file_exists(path)?;
+        }
+
+        // 验证显示时区
+        if let Err(e) = self.display_zone() {
+            return Err(config_err!("{}", e).suggest(["请检查 timezone 设置"]));
         }
 
         Ok(())
     }
 
+    /// 使用明文 HTTP 时的警告信息
+    ///
+    /// API URL 为 `http://` 且已被允许时返回警告文本，否则返回 `None`。
+    pub fn insecure_http_warning(&self) -> Option<String> {
+        if !self.api_url.starts_with("http://") {
+            return None;
+        }
+        let reason = if self.is_loopback_url() {
+            "目标为本机回环地址"
+        } else {
+            "已启用 allow_insecure_http"
+        };
+        Some(format!(
+            "警告: 正在通过明文 HTTP 访问 {}（{}），API 密钥将以明文传输，请勿在生产环境中使用",
+            self.api_url, reason
+        ))
+    }
+
+    /// API URL 的主机是否为回环地址
+    fn is_loopback_url(&self) -> bool {
+        let Ok(url) = reqwest::Url::parse(&self.api_url) else {
+            return false;
+        };
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        host.eq_ignore_ascii_case("localhost")
+            || host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<std::net::IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback())
+    }
+
+    /// 生成网络相关配置的诊断报告
+    ///
+    /// 每项为 `(名称, 当前值)`，API 密钥以掩码形式显示。
+    pub fn network_report(&self) -> Vec<(String, String)> {
+        let unset = || "未设置".to_string();
+        let secs = |v: Option<u64>| v.map(|s| format!("{} 秒", s)).unwrap_or_else(unset);
+        let path = |p: &Option<PathBuf>| {
+            p.as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(unset)
+        };
+
+        let proxy = match &self.proxy {
+            Some(proxy) => proxy.clone(),
+            None => ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]
+                .iter()
+                .find_map(|name| std::env::var(name).ok().map(|v| format!("{}（来自 {}）", v, name)))
+                .unwrap_or_else(|| "未设置（直连）".to_string()),
+        };
+
+        vec![
+            ("API 密钥".to_string(), self.masked_key()),
+            ("区域".to_string(), self.region.as_str().to_string()),
+            ("API URL".to_string(), self.api_url.clone()),
+            ("接口形式".to_string(), match self.api_flavor {
+                ApiFlavor::Auto => format!("auto（{}）", self.flavor().as_str()),
+                flavor => flavor.as_str().to_string(),
+            }),
+            ("总超时".to_string(), format!("{} 秒", self.timeout)),
+            ("连接超时".to_string(), secs(self.connect_timeout)),
+            ("读取超时".to_string(), secs(self.read_timeout)),
+            ("代理".to_string(), proxy),
+            ("不走代理".to_string(), self.no_proxy.clone().unwrap_or_else(unset)),
+            ("CA 证书".to_string(), path(&self.ca_cert)),
+            ("客户端证书".to_string(), path(&self.client_cert)),
+            ("客户端私钥".to_string(), path(&self.client_key)),
+        ]
+    }
+
+    /// 切换服务区域
+    ///
+    /// `api_url` 仍为原区域的默认地址时一并切换为新区域的地址；
+    /// 显式配置的 `api_url` 保持不变。
+    pub fn set_region(&mut self, region: Region) {
+        if !self.api_url_explicit && self.api_url.trim_end_matches('/') == self.region.default_api_url() {
+            self.api_url = region.default_api_url().to_string();
+        }
+        self.region = region;
+    }
+
+    /// 实际使用的显示语言
+    pub fn display_language(&self) -> Language {
+        self.language.unwrap_or_else(|| self.region.default_language())
+    }
+
+    /// 显示时间所用的时区（未设置时为本地时区）
+    pub fn display_zone(&self) -> std::result::Result<DisplayZone, String> {
+        match &self.timezone {
+            Some(timezone) => timezone.parse(),
+            None => Ok(DisplayZone::Local),
+        }
+    }
+
+    /// 实际使用的接口形式
+    pub fn flavor(&self) -> ApiFlavor {
+        self.api_flavor.resolve(&self.api_url)
+    }
+
+    /// 额度查询端点的完整 URL
+    pub fn endpoint_url(&self) -> String {
+        format!(
+            "{}{}",
+            self.api_url.trim_end_matches('/'),
+            self.flavor().endpoint_path()
+        )
+    }
+
+    /// 掩码后的 API 密钥（显示前 5 个和后 4 个字符）
+    pub fn masked_key(&self) -> String {
+        crate::logging::mask_secret(&self.api_key)
+    }
+
     /// 获取配置文件路径
     pub fn config_file_path() -> Result<PathBuf> {
         Ok(dirs::home_dir()
-            .ok_or_else(|| anyhow::anyhow!("无法确定主目录"))?
+            .ok_or_else(|| config_err!("无法确定主目录"))?
             .join(".glm/config.yaml"))
     }
+
+    /// 获取额外配置所在目录（`glm tui` 为其中每份配置显示一个标签页）
+    pub fn profiles_dir() -> Result<PathBuf> {
+        Ok(dirs::home_dir()
+            .ok_or_else(|| config_err!("无法确定主目录"))?
+            .join(".glm/profiles"))
+    }
 }
 
 #[cfg(test)]
@@ -131,6 +710,7 @@ mod tests {
             api_key: "short".to_string(),
             api_url: default_api_url(),
             timeout: default_timeout(),
+            ..Config::default()
         };
         assert!(config.validate().is_err());
     }
@@ -141,6 +721,7 @@ mod tests {
             api_key: "my_valid_api_key_12345".to_string(),
             api_url: default_api_url(),
             timeout: 30,
+            ..Config::default()
         };
         assert!(config.validate().is_ok());
     }
@@ -151,16 +732,55 @@ mod tests {
             api_key: "valid_api_key".to_string(),
             api_url: "http://insecure.com".to_string(),
             timeout: default_timeout(),
+            ..Config::default()
         };
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_config_validate_http_loopback() {
+        for url in ["http://127.0.0.1:8080/api", "http://localhost:3000", "http://[::1]:9000"] {
+            let config = Config {
+                api_key: "valid_api_key".to_string(),
+                api_url: url.to_string(),
+                ..Config::default()
+            };
+            assert!(config.validate().is_ok(), "{} 应被允许", url);
+            assert!(config.insecure_http_warning().is_some());
+        }
+    }
+
+    #[test]
+    fn test_config_validate_allow_insecure_http() {
+        let config = Config {
+            api_key: "valid_api_key".to_string(),
+            api_url: "http://gateway.internal/api".to_string(),
+            allow_insecure_http: true,
+            ..Config::default()
+        };
+        assert!(config.validate().is_ok());
+        assert!(config
+            .insecure_http_warning()
+            .unwrap()
+            .contains("allow_insecure_http"));
+    }
+
+    #[test]
+    fn test_https_has_no_insecure_warning() {
+        let config = Config {
+            api_key: "valid_api_key".to_string(),
+            ..Config::default()
+        };
+        assert!(config.insecure_http_warning().is_none());
+    }
+
     #[test]
     fn test_config_validate_invalid_timeout() {
         let config = Config {
             api_key: "valid_api_key".to_string(),
             api_url: default_api_url(),
             timeout: 0,
+            ..Config::default()
         };
         assert!(config.validate().is_err());
     }
@@ -171,7 +791,169 @@ mod tests {
             api_key: "valid_api_key".to_string(),
             api_url: default_api_url(),
             timeout: 400,
+            ..Config::default()
         };
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_config_validate_connect_timeout() {
+        let config = Config {
+            api_key: "valid_api_key".to_string(),
+            connect_timeout: Some(0),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validate_invalid_proxy() {
+        let config = Config {
+            api_key: "valid_api_key".to_string(),
+            proxy: Some("proxy.corp:8080".to_string()),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validate_client_cert_without_key() {
+        let config = Config {
+            api_key: "valid_api_key".to_string(),
+            client_cert: Some(PathBuf::from("/tmp/client.pem")),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_validate_missing_ca_cert() {
+        let config = Config {
+            api_key: "valid_api_key".to_string(),
+            ca_cert: Some(PathBuf::from("/nonexistent/ca.pem")),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_network_fields_from_yaml() {
+        let yaml = "api_key: valid_api_key\nproxy: http://proxy.corp:8080\nno_proxy: localhost\nconnect_timeout: 5\n";
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.proxy.as_deref(), Some("http://proxy.corp:8080"));
+        assert_eq!(config.no_proxy.as_deref(), Some("localhost"));
+        assert_eq!(config.connect_timeout, Some(5));
+        assert!(config.read_timeout.is_none());
+        assert_eq!(config.timeout, 30);
+    }
+
+    #[test]
+    fn test_api_flavor_auto_detection() {
+        let config = Config {
+            api_url: "https://open.bigmodel.cn/api/paas/v4/".to_string(),
+            ..Config::default()
+        };
+        assert_eq!(config.flavor(), ApiFlavor::Plans);
+        assert_eq!(config.endpoint_url(), "https://open.bigmodel.cn/api/paas/v4/plans");
+
+        let config = Config::default();
+        assert_eq!(config.flavor(), ApiFlavor::Monitor);
+        assert_eq!(config.endpoint_url(), "https://bigmodel.cn/api/monitor/usage/quota/limit");
+    }
+
+    #[test]
+    fn test_api_flavor_explicit() {
+        let yaml = "api_key: valid_api_key\napi_url: https://gateway.corp/glm\napi_flavor: plans\n";
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.flavor(), ApiFlavor::Plans);
+        assert_eq!("Monitor".parse::<ApiFlavor>(), Ok(ApiFlavor::Monitor));
+        assert!("v5".parse::<ApiFlavor>().is_err());
+    }
+
+    #[test]
+    fn test_config_validate_timezone() {
+        let mut config = Config {
+            api_key: "test_api_key_12345".to_string(),
+            timezone: Some("+08:00".to_string()),
+            ..Config::default()
+        };
+        assert!(config.validate().is_ok());
+        assert!(matches!(config.display_zone(), Ok(DisplayZone::Fixed(_))));
+
+        config.timezone = Some("Nowhere/Bad".to_string());
+        let err = config.validate().unwrap_err();
+        assert!(format!("{:#}", err).contains("未知的时区"));
+        assert_eq!(err.exit_code(), crate::error::exit_code::CONFIG);
+    }
+
+    #[test]
+    fn test_region_selects_api_url_and_language() {
+        let yaml = "api_key: valid_api_key\nregion: intl\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.apply_region_default();
+        assert_eq!(config.api_url, "https://api.z.ai/api");
+        assert_eq!(config.display_language(), Language::En);
+
+        config.set_region(Region::Cn);
+        assert_eq!(config.api_url, "https://bigmodel.cn/api");
+        assert_eq!(config.display_language(), Language::Zh);
+
+        config.language = Some(Language::En);
+        assert_eq!(config.display_language(), Language::En);
+    }
+
+    #[test]
+    fn test_region_keeps_explicit_default_api_url() {
+        let yaml = "api_key: valid_api_key\nregion: intl\napi_url: https://bigmodel.cn/api\n";
+        let mut config = Config::from_yaml(yaml).unwrap();
+        assert!(config.api_url_explicit);
+        config.apply_region_default();
+        assert_eq!(config.api_url, "https://bigmodel.cn/api");
+
+        config.set_region(Region::Cn);
+        config.set_region(Region::Intl);
+        assert_eq!(config.api_url, "https://bigmodel.cn/api");
+
+        let mut config = Config::from_yaml("api_key: valid_api_key\nregion: intl\n").unwrap();
+        assert!(!config.api_url_explicit);
+        config.apply_region_default();
+        assert_eq!(config.api_url, "https://api.z.ai/api");
+    }
+
+    #[test]
+    fn test_set_region_keeps_explicit_api_url() {
+        let mut config = Config {
+            api_url: "https://gateway.corp/glm".to_string(),
+            ..Config::default()
+        };
+        config.set_region(Region::Intl);
+        assert_eq!(config.api_url, "https://gateway.corp/glm");
+        assert_eq!(config.region, Region::Intl);
+    }
+
+    #[test]
+    fn test_expand_paths() {
+        let home = dirs::home_dir().unwrap();
+        let mut config = Config {
+            ca_cert: Some(PathBuf::from("~/certs/ca.pem")),
+            client_cert: Some(PathBuf::from("/etc/glm/client.pem")),
+            client_key: Some(PathBuf::from("~user/client.key")),
+            ..Config::default()
+        };
+        config.expand_paths();
+
+        assert_eq!(config.ca_cert, Some(home.join("certs/ca.pem")));
+        assert_eq!(config.client_cert, Some(PathBuf::from("/etc/glm/client.pem")));
+        assert_eq!(config.client_key, Some(PathBuf::from("~user/client.key")));
+    }
+
+    #[test]
+    fn test_masked_key() {
+        let config = Config {
+            api_key: "sk.abcdefghijklmn".to_string(),
+            ..Config::default()
+        };
+        assert_eq!(config.masked_key(), "sk.ab****klmn");
+        assert!(!config.masked_key().contains("defghij"));
+    }
 }
@@ -0,0 +1,307 @@
+//! 后台守护进程
+//!
+//! `glm daemon` 持有一个 `GlmClient`，按间隔刷新使用情况，在内存中保存最新数据与
+//! 近期快照，并通过 Unix 域套接字（默认 `~/.glm/daemon.sock`）应答查询。
+//! 其他 `glm` 命令在守护进程运行时直接读取其缓存，否则回退为直接请求 API。
+//!
+//! 协议为按行分隔的 JSON：客户端每次连接发送一行请求（如 `{"method":"usage"}`），
+//! 守护进程返回一行 `{"ok":true,"result":...}` 或 `{"ok":false,"error":"..."}`。
+
+#[cfg(unix)]
+mod server;
+
+#[cfg(unix)]
+pub use server::serve;
+
+use crate::api::ApiData;
+use crate::config::Config;
+use crate::error::CliError;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 客户端等待守护进程应答的最长时间
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 守护进程配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DaemonConfig {
+    /// 守护进程运行时是否从其读取使用情况（默认 true）
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// 套接字路径（可选，默认 `~/.glm/daemon.sock`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket: Option<PathBuf>,
+
+    /// 刷新间隔（秒，默认 60，至少 10）
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval() -> u64 {
+    60
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        DaemonConfig {
+            enabled: default_enabled(),
+            socket: None,
+            interval: default_interval(),
+        }
+    }
+}
+
+impl DaemonConfig {
+    /// 套接字路径
+    pub fn socket_path(&self) -> Result<PathBuf> {
+        match &self.socket {
+            Some(path) => Ok(path.clone()),
+            None => Ok(dirs::home_dir()
+                .ok_or_else(|| CliError::ConfigError("无法确定主目录".to_string()))?
+                .join(".glm/daemon.sock")),
+        }
+    }
+
+    /// 验证守护进程配置
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if self.interval < 10 {
+            return Err(CliError::ConfigError(format!(
+                "daemon.interval 不能小于 10 秒\n\n原因: 当前设置: {} 秒",
+                self.interval
+            ))
+            .suggest(["设置为 60 秒"]));
+        }
+        Ok(())
+    }
+}
+
+/// 客户端请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "lowercase")]
+pub enum Request {
+    /// 最新的使用情况
+    ///
+    /// `fingerprint` 为请求方的 API 地址与密钥摘要，与守护进程不一致时拒绝应答，
+    /// 避免不同账号或区域之间串用数据。
+    Usage {
+        /// 配置摘要（可选，省略时不校验）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fingerprint: Option<String>,
+    },
+    /// 内存中的近期快照
+    History {
+        /// 只返回该时间之后的快照
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        since: Option<DateTime<Utc>>,
+    },
+    /// 立即刷新并返回最新的使用情况
+    Refresh,
+    /// 守护进程状态
+    Status,
+    /// 停止守护进程
+    Stop,
+}
+
+/// 守护进程应答
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    /// 是否成功
+    pub ok: bool,
+
+    /// 结果（成功时）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+
+    /// 错误说明（失败时）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Response {
+    /// 成功应答
+    pub fn success<T: Serialize>(result: &T) -> Self {
+        match serde_json::to_value(result) {
+            Ok(value) => Response {
+                ok: true,
+                result: Some(value),
+                error: None,
+            },
+            Err(e) => Response::failure(e.to_string()),
+        }
+    }
+
+    /// 失败应答
+    pub fn failure(error: impl Into<String>) -> Self {
+        Response {
+            ok: false,
+            result: None,
+            error: Some(error.into()),
+        }
+    }
+
+    /// 取出结果，失败应答转换为错误
+    pub fn into_result<T: serde::de::DeserializeOwned>(self) -> Result<T> {
+        if !self.ok {
+            return Err(CliError::NetworkError(format!(
+                "守护进程返回错误: {}",
+                self.error.unwrap_or_default()
+            ))
+            .into());
+        }
+        let value = self.result.unwrap_or(serde_json::Value::Null);
+        serde_json::from_value(value)
+            .map_err(|e| CliError::ParseError(format!("解析守护进程应答失败: {}", e)).into())
+    }
+}
+
+/// `usage` / `refresh` 请求的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageReply {
+    /// 使用情况
+    pub data: ApiData,
+
+    /// 数据获取时间
+    pub updated_at: DateTime<Utc>,
+}
+
+/// `status` 请求的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    /// 进程 ID
+    pub pid: u32,
+
+    /// 启动时间
+    pub started_at: DateTime<Utc>,
+
+    /// 最近一次成功刷新的时间
+    pub updated_at: Option<DateTime<Utc>>,
+
+    /// 刷新间隔（秒）
+    pub interval: u64,
+
+    /// 查询的 API 地址
+    pub endpoint: String,
+
+    /// 内存中的快照数
+    pub snapshots: usize,
+
+    /// 最近一次刷新失败的原因（成功后清空）
+    pub last_error: Option<String>,
+}
+
+/// 配置摘要：API 地址与密钥的 SHA-256（十六进制），不泄露密钥本身
+///
+/// 摘要由守护进程与其他 `glm` 进程分别计算，必须与编译器版本和运行时无关。
+pub fn fingerprint(config: &Config) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(config.endpoint_url().as_bytes());
+    hasher.update([0]);
+    hasher.update(config.api_key.as_bytes());
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// 向守护进程发送请求
+#[cfg(unix)]
+pub async fn request(socket: &Path, request: &Request) -> Result<Response> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let exchange = async {
+        let mut stream = tokio::net::UnixStream::connect(socket)
+            .await
+            .map_err(|e| CliError::NetworkError(format!("无法连接守护进程: {}", e)))?;
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        stream.write_all(line.as_bytes()).await?;
+
+        let mut reply = String::new();
+        BufReader::new(stream).read_line(&mut reply).await?;
+        Ok::<_, anyhow::Error>(serde_json::from_str::<Response>(&reply)?)
+    };
+
+    tokio::time::timeout(CLIENT_TIMEOUT, exchange)
+        .await
+        .map_err(|_| CliError::NetworkError("等待守护进程应答超时".to_string()))?
+}
+
+/// 向守护进程发送请求（当前平台不支持 Unix 域套接字）
+#[cfg(not(unix))]
+pub async fn request(_socket: &Path, _request: &Request) -> Result<Response> {
+    Err(CliError::ConfigError("当前平台不支持守护进程".to_string()).into())
+}
+
+/// 从守护进程读取使用情况
+///
+/// 守护进程未运行、配置不一致或刷新失败时返回错误，调用方应回退为直接请求。
+pub async fn query_usage(config: &Config, socket: &Path) -> Result<UsageReply> {
+    let reply: UsageReply = request(
+        socket,
+        &Request::Usage {
+            fingerprint: Some(fingerprint(config)),
+        },
+    )
+    .await?
+    .into_result()?;
+    Ok(reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_protocol() {
+        assert_eq!(
+            serde_json::to_string(&Request::Usage { fingerprint: None }).unwrap(),
+            r#"{"method":"usage"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Request>(r#"{"method":"status"}"#).unwrap(),
+            Request::Status
+        );
+        assert!(serde_json::from_str::<Request>(r#"{"method":"launch"}"#).is_err());
+    }
+
+    #[test]
+    fn test_response_roundtrip() {
+        let reply = UsageReply {
+            data: serde_json::from_value(serde_json::json!({
+                "limits": [{"type": "TOKENS_LIMIT", "usage": 1000, "currentValue": 250}]
+            }))
+            .unwrap(),
+            updated_at: DateTime::from_timestamp(1_760_000_000, 0).unwrap(),
+        };
+        let line = serde_json::to_string(&Response::success(&reply)).unwrap();
+        let parsed: UsageReply = serde_json::from_str::<Response>(&line).unwrap().into_result().unwrap();
+        assert_eq!(parsed.data.limits[0].percentage, 25.0);
+        assert_eq!(parsed.updated_at, reply.updated_at);
+
+        let err = Response::failure("配置不一致").into_result::<UsageReply>().unwrap_err();
+        assert!(format!("{:#}", err).contains("配置不一致"));
+    }
+
+    #[test]
+    fn test_fingerprint_depends_on_key_and_endpoint() {
+        let mut config = Config {
+            api_key: "key-1234567890".to_string(),
+            ..Config::default()
+        };
+        let a = fingerprint(&config);
+        assert_eq!(a, fingerprint(&config));
+        assert_eq!(a.len(), 64);
+        config.api_key = "key-0987654321".to_string();
+        let b = fingerprint(&config);
+        assert_ne!(a, b);
+        config.api_url = "http://127.0.0.1:8080/api".to_string();
+        assert_ne!(b, fingerprint(&config));
+        assert!(!a.contains("key"));
+    }
+}
@@ -0,0 +1,247 @@
+//! 守护进程服务端
+
+use super::{fingerprint, DaemonStatus, Request, Response, UsageReply};
+use crate::api::GlmClient;
+use crate::cli_config::CliConfig;
+use crate::error::CliError;
+use crate::history::Snapshot;
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use std::collections::VecDeque;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::Notify;
+
+/// 内存中保留的快照时长（小时）
+const HISTORY_HOURS: u64 = 24;
+
+/// 单行请求的最大长度
+const MAX_REQUEST_BYTES: u64 = 64 * 1024;
+
+/// 缓存超过刷新间隔的该倍数后视为过期，不再提供给客户端
+const STALE_AFTER_INTERVALS: i64 = 3;
+
+/// 守护进程的可变状态
+struct State {
+    latest: Option<UsageReply>,
+    last_error: Option<String>,
+    history: VecDeque<Snapshot>,
+}
+
+/// 守护进程
+struct Daemon {
+    client: GlmClient,
+    endpoint: String,
+    fingerprint: String,
+    interval: u64,
+    started_at: chrono::DateTime<Utc>,
+    state: Mutex<State>,
+    shutdown: Notify,
+}
+
+impl Daemon {
+    /// 刷新一次使用情况
+    async fn refresh(&self) -> Result<UsageReply> {
+        let result = self.client.fetch_usage().await;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Ok(data) => {
+                let reply = UsageReply {
+                    data,
+                    updated_at: Utc::now(),
+                };
+                state.history.push_back(Snapshot {
+                    taken_at: reply.updated_at,
+                    limits: reply.data.limits.clone(),
+                });
+                let capacity = (HISTORY_HOURS * 3600 / self.interval) as usize;
+                while state.history.len() > capacity {
+                    state.history.pop_front();
+                }
+                state.latest = Some(reply.clone());
+                state.last_error = None;
+                tracing::debug!("守护进程已刷新使用情况");
+                Ok(reply)
+            }
+            Err(e) => {
+                tracing::warn!("守护进程刷新使用情况失败: {:#}", e);
+                state.last_error = Some(format!("{:#}", e));
+                Err(e.into())
+            }
+        }
+    }
+
+    /// 处理一条请求
+    async fn handle(&self, request: Request) -> Response {
+        match request {
+            Request::Usage { fingerprint } => {
+                if fingerprint.is_some_and(|fp| fp != self.fingerprint) {
+                    return Response::failure("守护进程使用的 API 地址或密钥与请求方不一致");
+                }
+                let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                match &state.latest {
+                    Some(reply)
+                        if Utc::now() - reply.updated_at
+                            <= Duration::seconds(self.interval as i64 * STALE_AFTER_INTERVALS) =>
+                    {
+                        Response::success(reply)
+                    }
+                    Some(_) => Response::failure(format!(
+                        "缓存数据已过期: {}",
+                        state.last_error.as_deref().unwrap_or("刷新未完成")
+                    )),
+                    None => Response::failure(
+                        state.last_error.clone().unwrap_or_else(|| "尚未获取到使用情况".to_string()),
+                    ),
+                }
+            }
+            Request::History { since } => {
+                let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                let snapshots: Vec<&Snapshot> = state
+                    .history
+                    .iter()
+                    .filter(|snapshot| since.is_none_or(|since| snapshot.taken_at >= since))
+                    .collect();
+                Response::success(&snapshots)
+            }
+            Request::Refresh => match self.refresh().await {
+                Ok(reply) => Response::success(&reply),
+                Err(e) => Response::failure(format!("{:#}", e)),
+            },
+            Request::Status => {
+                let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                Response::success(&DaemonStatus {
+                    pid: std::process::id(),
+                    started_at: self.started_at,
+                    updated_at: state.latest.as_ref().map(|reply| reply.updated_at),
+                    interval: self.interval,
+                    endpoint: self.endpoint.clone(),
+                    snapshots: state.history.len(),
+                    last_error: state.last_error.clone(),
+                })
+            }
+            Request::Stop => {
+                self.shutdown.notify_one();
+                Response::success(&serde_json::Value::Null)
+            }
+        }
+    }
+
+    /// 处理一个连接：读取一行请求，写回一行应答
+    async fn serve_connection(&self, stream: UnixStream) -> Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut line = String::new();
+        BufReader::new(reader.take(MAX_REQUEST_BYTES))
+            .read_line(&mut line)
+            .await?;
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => self.handle(request).await,
+            Err(e) => Response::failure(format!("无法解析请求: {}", e)),
+        };
+        let mut reply = serde_json::to_string(&response)?;
+        reply.push('\n');
+        writer.write_all(reply.as_bytes()).await?;
+        Ok(())
+    }
+}
+
+/// 准备套接字路径：创建目录，清理上次异常退出遗留的套接字文件
+///
+/// 新建的目录权限为 0700；`private_dir` 为 true 时（默认的 `~/.glm`）已存在的目录
+/// 也会收紧为 0700。自定义套接字路径的已有目录可能是共享目录，不做修改。
+async fn prepare_socket(path: &Path, private_dir: bool) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+        if private_dir {
+            std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+        }
+    }
+    if path.exists() {
+        if UnixStream::connect(path).await.is_ok() {
+            return Err(CliError::ConfigError(format!("守护进程已在运行（{}）", path.display()))
+                .suggest(["使用 glm daemon stop 停止后再启动"])
+                .into());
+        }
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// 以 0600 权限创建并监听套接字
+///
+/// 在收紧的 umask 下绑定，套接字文件创建时即只允许当前用户连接，
+/// 不存在先创建再修改权限之间可被其他用户连接的窗口。
+fn bind_private(path: &Path) -> std::io::Result<UnixListener> {
+    // SAFETY: umask 只修改本进程的文件创建掩码，绑定后立即恢复；此时守护进程尚未启动其他任务
+    let previous = unsafe { libc::umask(0o177) };
+    let listener = UnixListener::bind(path);
+    // SAFETY: 同上，恢复原来的掩码
+    unsafe { libc::umask(previous) };
+    listener
+}
+
+/// 运行守护进程，直到收到 Ctrl-C、SIGTERM 或 `stop` 请求
+///
+/// `interval` 为刷新间隔（秒）。
+pub async fn serve(config: CliConfig, interval: u64) -> Result<()> {
+    let socket = config.daemon.socket_path()?;
+    prepare_socket(&socket, config.daemon.socket.is_none()).await?;
+    let listener = bind_private(&socket).map_err(|e| {
+        CliError::ConfigError(format!("无法监听套接字 {}: {}", socket.display(), e))
+    })?;
+
+    let daemon = Arc::new(Daemon {
+        endpoint: config.endpoint_url(),
+        fingerprint: fingerprint(&config),
+        client: GlmClient::new(config.base)?,
+        interval,
+        started_at: Utc::now(),
+        state: Mutex::new(State {
+            latest: None,
+            last_error: None,
+            history: VecDeque::new(),
+        }),
+        shutdown: Notify::new(),
+    });
+    tracing::info!(socket = %socket.display(), interval, "守护进程已启动");
+
+    let refresher = tokio::spawn({
+        let daemon = Arc::clone(&daemon);
+        async move {
+            loop {
+                let _ = daemon.refresh().await;
+                tokio::time::sleep(std::time::Duration::from_secs(daemon.interval)).await;
+            }
+        }
+    });
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let daemon = Arc::clone(&daemon);
+                    tokio::spawn(async move {
+                        if let Err(e) = daemon.serve_connection(stream).await {
+                            tracing::debug!("处理守护进程请求失败: {:#}", e);
+                        }
+                    });
+                }
+                Err(e) => tracing::warn!("接受连接失败: {}", e),
+            },
+            _ = tokio::signal::ctrl_c() => break,
+            _ = terminate.recv() => break,
+            _ = daemon.shutdown.notified() => break,
+        }
+    }
+
+    refresher.abort();
+    let _ = std::fs::remove_file(&socket);
+    tracing::info!("守护进程已停止");
+    Ok(())
+}
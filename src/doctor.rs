@@ -0,0 +1,759 @@
+//! 诊断命令
+//!
+//! 按顺序逐层检查配置、网络与 API，定位 `glm` 失败的具体环节。
+
+use crate::api::{
+    check_plans_schema, check_usage_schema, http_client_builder, ApiErrorResponse, ApiResponse,
+    GlmClient, PlanResponse,
+};
+use crate::cli_config::CliConfig;
+use crate::config::{ApiFlavor, Config};
+use serde::Serialize;
+use std::time::Duration;
+
+/// 单项检查结果状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// 通过
+    Pass,
+    /// 警告（不阻断后续检查）
+    Warn,
+    /// 失败
+    Fail,
+    /// 因前置检查失败或不适用而跳过
+    Skip,
+}
+
+impl CheckStatus {
+    /// 中文标签
+    pub fn label(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "通过",
+            CheckStatus::Warn => "警告",
+            CheckStatus::Fail => "失败",
+            CheckStatus::Skip => "跳过",
+        }
+    }
+}
+
+/// 单项检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    /// 检查项标识（英文，便于脚本处理）
+    pub id: &'static str,
+    /// 检查项名称
+    pub name: &'static str,
+    /// 检查状态
+    pub status: CheckStatus,
+    /// 检查详情
+    pub detail: String,
+    /// 修复建议
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+/// 诊断报告
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    /// 工具版本
+    pub version: &'static str,
+    /// 全部检查结果（按执行顺序）
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    /// 失败的检查项数量
+    pub fn failures(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Fail)
+            .count()
+    }
+
+    /// 渲染为人类可读的检查清单
+    pub fn render_text(&self) -> String {
+        let mut output = String::from("GLM 诊断报告\n\n");
+        for check in &self.checks {
+            output.push_str(&format!(
+                "[{}] {}：{}\n",
+                check.status.label(),
+                check.name,
+                check.detail
+            ));
+            if let Some(hint) = &check.hint {
+                output.push_str(&format!("       建议：{}\n", hint));
+            }
+        }
+        output.push_str(&format!(
+            "\n共 {} 项检查，{} 项失败",
+            self.checks.len(),
+            self.failures()
+        ));
+        output
+    }
+}
+
+/// 诊断过程中的上下文
+struct Doctor {
+    checks: Vec<CheckResult>,
+}
+
+impl Doctor {
+    fn push(
+        &mut self,
+        id: &'static str,
+        name: &'static str,
+        status: CheckStatus,
+        detail: impl Into<String>,
+        hint: Option<&str>,
+    ) {
+        self.checks.push(CheckResult {
+            id,
+            name,
+            status,
+            detail: detail.into(),
+            hint: hint.map(str::to_string),
+        });
+    }
+
+    fn skip(&mut self, id: &'static str, name: &'static str, reason: &str) {
+        self.push(id, name, CheckStatus::Skip, reason, None);
+    }
+
+    fn finish(self) -> DoctorReport {
+        DoctorReport {
+            version: env!("CARGO_PKG_VERSION"),
+            checks: self.checks,
+        }
+    }
+}
+
+/// 运行全部诊断
+///
+/// 检查顺序：配置来源 → 密钥格式 → 文件权限 → DNS 解析 → TLS 握手 →
+/// 代理 → 端点可达性 → 认证 → 响应解析。前置检查失败时后续依赖项会被跳过。
+///
+/// `loaded` 为调用方加载并应用命令行覆盖项（如 `--region`）之后的配置，
+/// 加载失败时传入错误，由诊断报告为配置检查失败。
+pub async fn run_diagnostics(loaded: crate::error::Result<CliConfig>) -> DoctorReport {
+    let mut doctor = Doctor { checks: Vec::new() };
+
+    let config = check_config(&mut doctor, loaded);
+    match &config {
+        Some(config) => check_key(&mut doctor, config),
+        None => doctor.skip("key_format", "密钥格式", "未加载到配置"),
+    }
+    check_permissions(&mut doctor);
+
+    let Some(config) = config else {
+        for (id, name) in NETWORK_CHECKS {
+            doctor.skip(id, name, "未加载到配置");
+        }
+        return doctor.finish();
+    };
+
+    let network_ok = check_network(&mut doctor, &config).await;
+    if network_ok {
+        check_api(&mut doctor, &config).await;
+    } else {
+        skip_remaining(&mut doctor, "网络检查未通过");
+    }
+
+    doctor.finish()
+}
+
+/// 把尚未执行的网络检查项标记为跳过，保证清单完整
+fn skip_remaining(doctor: &mut Doctor, reason: &str) {
+    for (id, name) in NETWORK_CHECKS {
+        if !doctor.checks.iter().any(|check| check.id == id) {
+            doctor.skip(id, name, reason);
+        }
+    }
+}
+
+/// 依赖配置的检查项（按执行顺序）
+const NETWORK_CHECKS: [(&str, &str); 6] = [
+    ("dns", "DNS 解析"),
+    ("tls", "TLS 握手"),
+    ("proxy", "代理"),
+    ("reachability", "端点可达性"),
+    ("auth", "认证"),
+    ("payload", "响应解析"),
+];
+
+/// 检查配置来源与基本校验
+fn check_config(doctor: &mut Doctor, loaded: crate::error::Result<CliConfig>) -> Option<CliConfig> {
+    let mut sources = Vec::new();
+    if let Ok(path) = Config::config_file_path() {
+        if path.exists() {
+            sources.push(format!("配置文件 {}", path.display()));
+        }
+    }
+    if std::env::var("GLM_API_KEY").is_ok() {
+        sources.push("环境变量 GLM_API_KEY".to_string());
+    }
+
+    let config = match loaded {
+        Ok(config) => config,
+        Err(e) => {
+            doctor.push(
+                "config",
+                "配置来源",
+                CheckStatus::Fail,
+                first_line(&e.to_string()),
+                Some("设置环境变量 GLM_API_KEY 或创建 ~/.glm/config.yaml"),
+            );
+            return None;
+        }
+    };
+
+    if let Err(e) = config.validate() {
+        doctor.push(
+            "config",
+            "配置来源",
+            CheckStatus::Fail,
+            format!("{}；{}", sources.join("，"), first_line(&e.to_string())),
+            Some("运行 `glm config` 查看当前生效的配置并修正对应字段"),
+        );
+        return None;
+    }
+
+    let status = if config.insecure_http_warning().is_some() {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+    doctor.push(
+        "config",
+        "配置来源",
+        status,
+        format!("{}，API URL: {}", sources.join(" + "), config.api_url),
+        (status == CheckStatus::Warn).then_some("正在使用明文 HTTP，仅应用于本地测试"),
+    );
+    Some(config)
+}
+
+/// 检查密钥格式（仅展示掩码后的密钥）
+fn check_key(doctor: &mut Doctor, config: &Config) {
+    let masked = config.masked_key();
+    let key = &config.api_key;
+    if key.trim() != key {
+        doctor.push(
+            "key_format",
+            "密钥格式",
+            CheckStatus::Fail,
+            format!("{}（包含首尾空白字符）", masked),
+            Some("删除密钥首尾的空格或换行"),
+        );
+    } else if !key.contains('.') {
+        doctor.push(
+            "key_format",
+            "密钥格式",
+            CheckStatus::Warn,
+            format!("{}（不符合 `id.secret` 格式）", masked),
+            Some("请确认复制的是智谱 AI 开放平台上的完整 API 密钥"),
+        );
+    } else {
+        doctor.push("key_format", "密钥格式", CheckStatus::Pass, masked, None);
+    }
+}
+
+/// 检查配置文件权限
+fn check_permissions(doctor: &mut Doctor) {
+    let Ok(path) = Config::config_file_path() else {
+        doctor.skip("permissions", "文件权限", "无法确定主目录");
+        return;
+    };
+    if !path.exists() {
+        doctor.skip("permissions", "文件权限", "未使用配置文件");
+        return;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        match std::fs::metadata(&path) {
+            Ok(meta) => {
+                let mode = meta.permissions().mode() & 0o777;
+                if mode & 0o077 != 0 {
+                    doctor.push(
+                        "permissions",
+                        "文件权限",
+                        CheckStatus::Warn,
+                        format!("{} 权限为 {:o}，其他用户可读取", path.display(), mode),
+                        Some(&format!("chmod 600 {}", path.display())),
+                    );
+                } else {
+                    doctor.push(
+                        "permissions",
+                        "文件权限",
+                        CheckStatus::Pass,
+                        format!("{} 权限为 {:o}", path.display(), mode),
+                        None,
+                    );
+                }
+            }
+            Err(e) => doctor.push(
+                "permissions",
+                "文件权限",
+                CheckStatus::Fail,
+                format!("无法读取 {} 的元数据: {}", path.display(), e),
+                Some("请检查配置文件是否可读"),
+            ),
+        }
+    }
+
+    #[cfg(not(unix))]
+    doctor.skip("permissions", "文件权限", "当前平台不检查文件权限");
+}
+
+/// 检查 DNS、TLS 与代理，返回是否可以继续进行 API 检查
+async fn check_network(doctor: &mut Doctor, config: &Config) -> bool {
+    let Ok(url) = reqwest::Url::parse(&config.api_url) else {
+        doctor.push(
+            "dns",
+            "DNS 解析",
+            CheckStatus::Fail,
+            format!("无法解析 API URL: {}", config.api_url),
+            Some("请检查 api_url 配置"),
+        );
+        return false;
+    };
+    let host = url.host_str().unwrap_or_default().to_string();
+    let port = url.port_or_known_default().unwrap_or(443);
+    let proxy = effective_proxy(config, &url);
+    // 走代理时直连失败不代表请求一定失败
+    let direct_fail = if proxy.is_some() {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Fail
+    };
+    let connect_timeout = Duration::from_secs(config.connect_timeout.unwrap_or(config.timeout));
+
+    // DNS 解析
+    let lookup = tokio::time::timeout(
+        connect_timeout,
+        tokio::net::lookup_host((host.trim_matches(['[', ']']), port)),
+    )
+    .await;
+    let dns_ok = match lookup {
+        Ok(Ok(addrs)) => {
+            let addrs: Vec<String> = addrs.map(|a| a.ip().to_string()).collect();
+            doctor.push("dns", "DNS 解析", CheckStatus::Pass, format!("{} → {}", host, addrs.join(", ")), None);
+            true
+        }
+        Ok(Err(e)) => {
+            doctor.push(
+                "dns",
+                "DNS 解析",
+                direct_fail,
+                format!("无法解析 {}: {}", host, e),
+                Some("请检查 DNS 设置或 api_url 中的主机名"),
+            );
+            false
+        }
+        Err(_) => {
+            doctor.push(
+                "dns",
+                "DNS 解析",
+                direct_fail,
+                format!("解析 {} 超时", host),
+                Some("请检查 DNS 服务器是否可用"),
+            );
+            false
+        }
+    };
+
+    // TLS 握手（直连，不经过代理）
+    if !dns_ok {
+        doctor.skip("tls", "TLS 握手", "DNS 解析未通过");
+    } else if url.scheme() != "https" {
+        doctor.skip("tls", "TLS 握手", "明文 HTTP 无需 TLS");
+    } else {
+        check_tls(doctor, config, &url, direct_fail).await;
+    }
+
+    // 代理
+    match &proxy {
+        None => doctor.skip("proxy", "代理", "未使用代理"),
+        Some(proxy_url) => {
+            let target = reqwest::Url::parse(proxy_url).ok().and_then(|u| {
+                Some((u.host_str()?.to_string(), u.port_or_known_default()?))
+            });
+            match target {
+                None => doctor.push(
+                    "proxy",
+                    "代理",
+                    CheckStatus::Fail,
+                    format!("代理地址无效: {}", proxy_url),
+                    Some("代理地址应形如 http://proxy.example.com:8080"),
+                ),
+                Some((proxy_host, proxy_port)) => {
+                    let connect = tokio::time::timeout(
+                        connect_timeout,
+                        tokio::net::TcpStream::connect((proxy_host.as_str(), proxy_port)),
+                    )
+                    .await;
+                    match connect {
+                        Ok(Ok(_)) => doctor.push(
+                            "proxy",
+                            "代理",
+                            CheckStatus::Pass,
+                            format!("{} 可连接", proxy_url),
+                            None,
+                        ),
+                        _ => {
+                            doctor.push(
+                                "proxy",
+                                "代理",
+                                CheckStatus::Fail,
+                                format!("无法连接代理 {}", proxy_url),
+                                Some("请检查 proxy / HTTPS_PROXY 配置，或通过 no_proxy 排除目标主机"),
+                            );
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    dns_ok || proxy.is_some()
+}
+
+/// 直连目标主机完成一次 TLS 握手
+async fn check_tls(doctor: &mut Doctor, config: &Config, url: &reqwest::Url, fail: CheckStatus) {
+    let client = match http_client_builder(config).and_then(|b| Ok(b.no_proxy().build()?)) {
+        Ok(client) => client,
+        Err(e) => {
+            doctor.push(
+                "tls",
+                "TLS 握手",
+                CheckStatus::Fail,
+                first_line(&e.to_string()),
+                Some("请检查 ca_cert / client_cert / client_key 配置"),
+            );
+            return;
+        }
+    };
+
+    let origin = tls_origin(url);
+    // 任何 HTTP 响应（包括 404）都说明 TLS 握手已完成
+    match client.head(&origin).send().await {
+        Ok(_) => doctor.push("tls", "TLS 握手", CheckStatus::Pass, format!("{} 证书校验通过", origin), None),
+        Err(e) => doctor.push(
+            "tls",
+            "TLS 握手",
+            fail,
+            error_chain(&e),
+            Some("企业网络中间人代理需通过 ca_cert 配置额外的 CA 证书；如需 mTLS 请配置 client_cert / client_key"),
+        ),
+    }
+}
+
+/// TLS 检查请求的源地址（保留非默认端口）
+fn tls_origin(url: &reqwest::Url) -> String {
+    format!("{}/", url.origin().ascii_serialization())
+}
+
+/// 检查端点可达性、认证与响应解析
+async fn check_api(doctor: &mut Doctor, config: &Config) {
+    let client = match GlmClient::new(config.clone()) {
+        Ok(client) => client,
+        Err(e) => {
+            doctor.push(
+                "reachability",
+                "端点可达性",
+                CheckStatus::Fail,
+                first_line(&e.to_string()),
+                Some("请检查代理与证书配置"),
+            );
+            doctor.skip("auth", "认证", "端点不可达");
+            doctor.skip("payload", "响应解析", "端点不可达");
+            return;
+        }
+    };
+
+    let (status, body) = match client.fetch_usage_raw().await {
+        Ok(resp) => (resp.status, resp.body),
+        Err(e) => {
+            doctor.push(
+                "reachability",
+                "端点可达性",
+                CheckStatus::Fail,
+                first_line(&e.to_string()),
+                Some("请检查网络、防火墙与 api_url 配置"),
+            );
+            doctor.skip("auth", "认证", "端点不可达");
+            doctor.skip("payload", "响应解析", "端点不可达");
+            return;
+        }
+    };
+
+    if status == 404 {
+        doctor.push(
+            "reachability",
+            "端点可达性",
+            CheckStatus::Fail,
+            format!(
+                "HTTP 404，端点 {} 不存在（接口形式: {}）",
+                config.endpoint_url(),
+                config.flavor().as_str()
+            ),
+            Some("请确认 api_url 为 https://bigmodel.cn/api（监控接口）或 https://open.bigmodel.cn/api/paas/v4（计划接口），必要时设置 api_flavor"),
+        );
+        doctor.skip("auth", "认证", "端点不存在");
+        doctor.skip("payload", "响应解析", "端点不存在");
+        return;
+    }
+    doctor.push(
+        "reachability",
+        "端点可达性",
+        CheckStatus::Pass,
+        format!("HTTP {}（响应 {} 字节）", status, body.len()),
+        None,
+    );
+
+    let business_error = serde_json::from_str::<ApiErrorResponse>(&body)
+        .ok()
+        .filter(ApiErrorResponse::is_business_error);
+
+    match status {
+        200..=299 if business_error.is_some() => {
+            let envelope = business_error.unwrap_or_default();
+            doctor.push(
+                "auth",
+                "认证",
+                CheckStatus::Fail,
+                format!(
+                    "业务错误 {}: {}",
+                    envelope.upstream_code().unwrap_or_else(|| "-".to_string()),
+                    envelope.message().unwrap_or("未知错误")
+                ),
+                Some("请根据上游错误代码确认密钥、账户余额与套餐状态"),
+            );
+            doctor.skip("payload", "响应解析", "未获得成功响应");
+            return;
+        }
+        200..=299 => doctor.push("auth", "认证", CheckStatus::Pass, "API 密钥有效", None),
+        401 | 403 => {
+            doctor.push(
+                "auth",
+                "认证",
+                CheckStatus::Fail,
+                format!("HTTP {}，API 密钥无效、已过期或无权限", status),
+                Some("请访问智谱 AI 开放平台确认密钥状态，必要时重新生成"),
+            );
+            doctor.skip("payload", "响应解析", "认证未通过");
+            return;
+        }
+        _ => {
+            doctor.push(
+                "auth",
+                "认证",
+                CheckStatus::Fail,
+                format!("HTTP {}: {}", status, truncate(&body, 200)),
+                Some("服务端返回错误，请稍后重试"),
+            );
+            doctor.skip("payload", "响应解析", "未获得成功响应");
+            return;
+        }
+    }
+
+    let parsed = match config.flavor() {
+        ApiFlavor::Plans => serde_json::from_str::<PlanResponse>(&body)
+            .map(|resp| format!("订阅计划: {}", resp.data.plan_name)),
+        _ => serde_json::from_str::<ApiResponse>(&body)
+            .map(|resp| format!("共 {} 个额度项", resp.data.limits.len())),
+    };
+
+    match parsed {
+        Ok(summary) => {
+            let issues = serde_json::from_str(&body)
+                .map(|value| match config.flavor() {
+                    ApiFlavor::Plans => check_plans_schema(&value),
+                    _ => check_usage_schema(&value),
+                })
+                .unwrap_or_default();
+            if issues.is_empty() {
+                doctor.push("payload", "响应解析", CheckStatus::Pass, summary, None);
+            } else {
+                doctor.push(
+                    "payload",
+                    "响应解析",
+                    CheckStatus::Warn,
+                    format!("已宽松解析，但有 {} 处结构差异: {}", issues.len(), issues.join("；")),
+                    Some("接口格式可能已变更，请升级 glm 或反馈此报告"),
+                );
+            }
+        }
+        Err(e) => doctor.push(
+            "payload",
+            "响应解析",
+            CheckStatus::Fail,
+            format!("{}；响应片段: {}", e, truncate(&body, 200)),
+            Some("接口返回格式可能已变更，请升级 glm 或反馈此报告"),
+        ),
+    }
+}
+
+/// 当前请求实际使用的代理地址
+fn effective_proxy(config: &Config, url: &reqwest::Url) -> Option<String> {
+    let host = url.host_str().unwrap_or_default();
+    let no_proxy = config
+        .no_proxy
+        .clone()
+        .or_else(|| std::env::var("NO_PROXY").ok())
+        .or_else(|| std::env::var("no_proxy").ok())
+        .unwrap_or_default();
+    let bypass = no_proxy.split(',').map(str::trim).any(|entry| {
+        !entry.is_empty()
+            && (entry == "*" || host == entry.trim_start_matches('.') || host.ends_with(&format!(".{}", entry.trim_start_matches('.'))))
+    });
+    if bypass {
+        return None;
+    }
+
+    let env_names: &[&str] = if url.scheme() == "https" {
+        &["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+    } else {
+        &["HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]
+    };
+    config.proxy.clone().or_else(|| {
+        env_names
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+    })
+}
+
+/// 展开错误链，便于看到底层的证书错误
+fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut parts = vec![err.to_string()];
+    let mut source = err.source();
+    while let Some(e) = source {
+        parts.push(e.to_string());
+        source = e.source();
+    }
+    parts.join(": ")
+}
+
+/// 取多行错误信息的第一行
+fn first_line(s: &str) -> String {
+    s.lines().next().unwrap_or_default().to_string()
+}
+
+/// 按字符截断字符串
+fn truncate(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        s.to_string()
+    } else {
+        format!("{}…", s.chars().take(max_chars).collect::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_key_masks_secret() {
+        let mut doctor = Doctor { checks: Vec::new() };
+        let config = Config {
+            api_key: "abcdef123456.secretsecret".to_string(),
+            ..Config::default()
+        };
+        check_key(&mut doctor, &config);
+        let check = &doctor.checks[0];
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(!check.detail.contains("secretsecret"));
+    }
+
+    #[test]
+    fn test_check_key_without_dot_warns() {
+        let mut doctor = Doctor { checks: Vec::new() };
+        let config = Config {
+            api_key: "abcdef1234567890".to_string(),
+            ..Config::default()
+        };
+        check_key(&mut doctor, &config);
+        assert_eq!(doctor.checks[0].status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn test_check_config_uses_given_config() {
+        let mut doctor = Doctor { checks: Vec::new() };
+        let mut config = Config {
+            api_key: "abcdef123456.secretsecret".to_string(),
+            ..Config::default()
+        };
+        config.set_region(crate::config::Region::Intl);
+        let config = check_config(&mut doctor, Ok(CliConfig::from(config))).unwrap();
+        assert_eq!(config.api_url, "https://api.z.ai/api");
+        assert!(doctor.checks[0].detail.contains("https://api.z.ai/api"));
+
+        let mut doctor = Doctor { checks: Vec::new() };
+        assert!(check_config(&mut doctor, Err(crate::error::CliError::ConfigError("缺少 API 密钥".to_string()))).is_none());
+        assert_eq!(doctor.checks[0].status, CheckStatus::Fail);
+        assert_eq!(doctor.checks[0].detail, "配置错误: 缺少 API 密钥");
+    }
+
+    #[test]
+    fn test_report_render_and_json() {
+        let mut doctor = Doctor { checks: Vec::new() };
+        doctor.push("dns", "DNS 解析", CheckStatus::Fail, "无法解析", Some("检查 DNS"));
+        doctor.skip("tls", "TLS 握手", "DNS 解析未通过");
+        let report = doctor.finish();
+
+        assert_eq!(report.failures(), 1);
+        let text = report.render_text();
+        assert!(text.contains("[失败] DNS 解析：无法解析"));
+        assert!(text.contains("建议：检查 DNS"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["checks"][0]["status"], "fail");
+        assert_eq!(json["checks"][1]["status"], "skip");
+        assert!(json["checks"][1].get("hint").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_url_skips_remaining_network_checks() {
+        let mut doctor = Doctor { checks: Vec::new() };
+        let config = Config {
+            api_url: "not a url".to_string(),
+            ..Config::default()
+        };
+        assert!(!check_network(&mut doctor, &config).await);
+        skip_remaining(&mut doctor, "网络检查未通过");
+
+        let ids: Vec<_> = doctor.checks.iter().map(|check| check.id).collect();
+        assert_eq!(ids, NETWORK_CHECKS.map(|(id, _)| id));
+        assert_eq!(doctor.checks[0].status, CheckStatus::Fail);
+        assert!(doctor.checks[1..].iter().all(|check| check.status == CheckStatus::Skip));
+    }
+
+    #[test]
+    fn test_tls_origin_keeps_port() {
+        let url = reqwest::Url::parse("https://gw.example:8443/api/monitor/usage/quota/limit").unwrap();
+        assert_eq!(tls_origin(&url), "https://gw.example:8443/");
+        let url = reqwest::Url::parse("https://open.bigmodel.cn:443/api/monitor").unwrap();
+        assert_eq!(tls_origin(&url), "https://open.bigmodel.cn/");
+    }
+
+    #[test]
+    fn test_effective_proxy_respects_no_proxy() {
+        let config = Config {
+            proxy: Some("http://proxy.corp:8080".to_string()),
+            no_proxy: Some("localhost,.bigmodel.cn".to_string()),
+            ..Config::default()
+        };
+        let url = reqwest::Url::parse("https://open.bigmodel.cn/api").unwrap();
+        assert!(effective_proxy(&config, &url).is_none());
+
+        let url = reqwest::Url::parse("https://example.com/api").unwrap();
+        assert_eq!(
+            effective_proxy(&config, &url).as_deref(),
+            Some("http://proxy.corp:8080")
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //! 使用 thiserror 定义所有可能的错误类型，
 //! 提供清晰的中文错误消息。
 
+use serde::Serialize;
 use thiserror::Error;
 
 /// CLI 错误类型
@@ -17,13 +18,57 @@ pub enum CliError {
     NetworkError(String),
 
     /// API 错误
-    #[error("API 错误 (代码 {code}): {msg}")]
-    ApiError { code: u16, msg: String },
+    #[error("API 错误 (代码 {code}): {msg}{}", render_suggestions(&api_suggestions(*code)))]
+    ApiError {
+        /// HTTP 状态码
+        code: u16,
+        /// 面向用户的完整错误说明
+        msg: String,
+        /// 上游接口返回的原始错误消息
+        upstream_msg: Option<String>,
+        /// 上游业务错误代码（如 `1113`）
+        upstream_code: Option<String>,
+        /// 上游请求 ID，便于向平台反馈问题
+        request_id: Option<String>,
+    },
+
+    /// 上游业务错误（HTTP 2xx 响应中的错误代码无法归入已知的 HTTP 语义）
+    #[error("业务错误: {msg}{}", render_suggestions(&business_suggestions()))]
+    BusinessError {
+        /// 面向用户的完整错误说明
+        msg: String,
+        /// 上游接口返回的原始错误消息
+        upstream_msg: Option<String>,
+        /// 上游业务错误代码（如 `9999`）
+        upstream_code: Option<String>,
+        /// 上游请求 ID，便于向平台反馈问题
+        request_id: Option<String>,
+    },
+
+    /// 额度不足（如 `glm guard` 拒绝启动命令）
+    #[error("额度不足: {0}")]
+    QuotaExhausted(String),
+
+    /// 操作被用户中断（如等待额度时按下 Ctrl-C）
+    #[error("已中断: {0}")]
+    Interrupted(String),
+
+    /// 等待超时（如 `glm wait` 超过 `--timeout`）
+    #[error("等待超时: {0}")]
+    Timeout(String),
+
+    /// 钩子命令执行失败（无法启动、以非零状态退出或超时）
+    #[error("钩子执行失败: {0}")]
+    HookError(String),
 
     /// 数据验证错误
     #[error("数据验证错误: {0}")]
     ValidationError(String),
 
+    /// API 响应解析错误
+    #[error("响应解析错误: {0}")]
+    ParseError(String),
+
     /// IO 错误
     #[error("IO 错误: {0}")]
     IoError(#[from] std::io::Error),
@@ -39,14 +84,304 @@ pub enum CliError {
     /// HTTP 请求错误
     #[error("HTTP 请求错误: {0}")]
     HttpError(#[from] reqwest::Error),
+
+    /// 附带修复建议的错误（由 [`CliError::suggest`] 生成）
+    ///
+    /// 退出码与错误类别与 `error` 相同；建议在文本输出中列在说明末尾，
+    /// 在 `--error-format json` 中输出到 `suggestions` 字段。
+    #[error("{error}{}", render_suggestions(.suggestions))]
+    WithSuggestions {
+        /// 原始错误
+        error: Box<CliError>,
+        /// 修复建议
+        suggestions: Vec<String>,
+    },
+}
+
+/// 本库公开接口使用的结果类型
+pub type Result<T> = std::result::Result<T, CliError>;
+
+/// 进程退出码
+///
+/// 每类错误对应一个固定的退出码，便于脚本区分失败原因。
+pub mod exit_code {
+    /// 成功
+    pub const SUCCESS: u8 = 0;
+    /// 未归类的一般错误
+    pub const GENERAL: u8 = 1;
+    /// 命令行参数错误（由 clap 使用）
+    pub const USAGE: u8 = 2;
+    /// 配置错误（缺少密钥、配置文件格式错误、配置校验失败等）
+    pub const CONFIG: u8 = 3;
+    /// 网络错误（超时、无法连接、TLS 失败等）
+    pub const NETWORK: u8 = 4;
+    /// 认证失败（HTTP 401 / 403）
+    pub const AUTH: u8 = 5;
+    /// 请求被限流（HTTP 429）
+    pub const RATE_LIMITED: u8 = 6;
+    /// 服务端错误（HTTP 5xx）
+    pub const SERVER: u8 = 7;
+    /// 响应解析或数据验证失败（接口格式可能已变更）
+    pub const PARSE: u8 = 8;
+    /// 其他 API 错误（HTTP 400、404 等）
+    pub const API: u8 = 9;
+    /// 等待超时
+    pub const TIMEOUT: u8 = 10;
+    /// 额度低于下限（`glm guard` 拒绝启动命令）
+    pub const QUOTA: u8 = 11;
+    /// 被用户中断（Ctrl-C，与 shell 的 128 + SIGINT 约定一致）
+    pub const INTERRUPTED: u8 = 130;
+}
+
+impl CliError {
+    /// 附加修复建议
+    pub fn suggest<I>(self, suggestions: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let added = suggestions.into_iter().map(Into::into);
+        match self {
+            CliError::WithSuggestions { error, mut suggestions } => {
+                suggestions.extend(added);
+                CliError::WithSuggestions { error, suggestions }
+            }
+            error => CliError::WithSuggestions {
+                error: Box::new(error),
+                suggestions: added.collect(),
+            },
+        }
+    }
+
+    /// 修复建议（API 错误按状态码给出通用建议）
+    pub fn suggestions(&self) -> Vec<String> {
+        match self {
+            CliError::WithSuggestions { suggestions, .. } => suggestions.clone(),
+            CliError::ApiError { code, .. } => api_suggestions(*code),
+            CliError::BusinessError { .. } => business_suggestions(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// 去掉修复建议后的原始错误
+    pub fn inner(&self) -> &CliError {
+        match self {
+            CliError::WithSuggestions { error, .. } => error.inner(),
+            error => error,
+        }
+    }
+
+    /// 该错误对应的进程退出码
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::ConfigError(_) | CliError::YamlError(_) => exit_code::CONFIG,
+            CliError::NetworkError(_) | CliError::HttpError(_) => exit_code::NETWORK,
+            CliError::ApiError { code: 401 | 403, .. } => exit_code::AUTH,
+            CliError::ApiError { code: 429, .. } => exit_code::RATE_LIMITED,
+            CliError::QuotaExhausted(_) => exit_code::QUOTA,
+            CliError::ApiError { code: 500..=599, .. } => exit_code::SERVER,
+            CliError::ApiError { .. } | CliError::BusinessError { .. } => exit_code::API,
+            CliError::ValidationError(_)
+            | CliError::ParseError(_)
+            | CliError::SerializationError(_) => exit_code::PARSE,
+            CliError::Timeout(_) => exit_code::TIMEOUT,
+            CliError::Interrupted(_) => exit_code::INTERRUPTED,
+            CliError::IoError(_) | CliError::HookError(_) => exit_code::GENERAL,
+            CliError::WithSuggestions { error, .. } => error.exit_code(),
+        }
+    }
+
+    /// 从 `anyhow::Error` 的错误链中找出 `CliError` 并返回对应的退出码
+    pub fn exit_code_of(err: &anyhow::Error) -> u8 {
+        find_cli_error(err)
+            .map(CliError::exit_code)
+            .unwrap_or(exit_code::GENERAL)
+    }
+
+    /// 错误类别标识（英文，供机器读取）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CliError::ConfigError(_) | CliError::YamlError(_) => "config",
+            CliError::NetworkError(_) | CliError::HttpError(_) => "network",
+            CliError::ApiError { code: 401 | 403, .. } => "auth",
+            CliError::ApiError { code: 429, .. } => "rate_limit",
+            CliError::QuotaExhausted(_) => "quota",
+            CliError::ApiError { code: 500..=599, .. } => "server",
+            CliError::ApiError { .. } => "api",
+            CliError::BusinessError { .. } => "business",
+            CliError::ValidationError(_) => "validation",
+            CliError::ParseError(_) | CliError::SerializationError(_) => "parse",
+            CliError::Timeout(_) => "timeout",
+            CliError::Interrupted(_) => "interrupted",
+            CliError::IoError(_) => "io",
+            CliError::HookError(_) => "hook",
+            CliError::WithSuggestions { error, .. } => error.kind(),
+        }
+    }
+
+    /// 稍后重试是否可能成功
+    pub fn retryable(&self) -> bool {
+        matches!(
+            self.inner(),
+            CliError::NetworkError(_)
+                | CliError::HttpError(_)
+                | CliError::ApiError { code: 429 | 500..=599, .. }
+                | CliError::QuotaExhausted(_)
+        )
+    }
+}
+
+/// API 错误按状态码给出的通用建议
+fn api_suggestions(code: u16) -> Vec<String> {
+    let suggestions: &[&str] = match code {
+        400 => &["请检查请求格式，确保所有参数正确"],
+        401 => &[
+            "请检查 API 密钥是否正确配置",
+            "请确认 API 密钥未过期",
+            "请访问智谱 AI 平台重新生成密钥",
+        ],
+        403 => &["请确认您的 API 密钥有访问权限", "如问题持续，请联系客服"],
+        404 => &["请检查 API URL 配置是否正确（可运行 glm config 查看）"],
+        429 => &["请稍后再试（建议等待 1-2 秒）"],
+        502..=504 => &["请稍后重试"],
+        _ => &["请稍后重试", "如问题持续，请联系支持团队"],
+    };
+    suggestions.iter().map(|s| s.to_string()).collect()
+}
+
+/// 上游业务错误的通用建议
+fn business_suggestions() -> Vec<String> {
+    vec![
+        "请根据上游错误信息处理".to_string(),
+        "如问题持续，请携带错误代码与请求 ID 联系支持团队".to_string(),
+    ]
+}
+
+/// 把修复建议渲染为错误说明末尾的“建议”段落
+///
+/// 单条建议写在同一行，多条建议逐行编号列出。
+fn render_suggestions(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [only] => format!("\n\n建议: {}", only),
+        _ => {
+            let mut text = "\n\n建议:".to_string();
+            for (i, suggestion) in suggestions.iter().enumerate() {
+                text.push_str(&format!("\n{}. {}", i + 1, suggestion));
+            }
+            text
+        }
+    }
+}
+
+/// 在错误链中查找 `CliError`
+fn find_cli_error(err: &anyhow::Error) -> Option<&CliError> {
+    err.chain().find_map(|e| e.downcast_ref::<CliError>())
+}
+
+/// 机器可读的错误文档
+///
+/// 序列化后形如 `{"error":{"kind":"auth","http_status":401,...}}`，
+/// 通过 `--error-format json` 输出到标准错误。
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorDocument {
+    /// 错误详情
+    pub error: ErrorDetail,
 }
 
-/// API 错误响应类型
-#[derive(Debug, Clone)]
-pub struct ApiErrorResponse {
-    pub code: u16,
-    pub msg: String,
-    pub error: String,
+/// 错误详情
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorDetail {
+    /// 错误类别（usage / config / network / auth / rate_limit / quota / server / api / business / validation / parse / timeout / interrupted / io / internal）
+    pub kind: &'static str,
+    /// 进程退出码
+    pub exit_code: u8,
+    /// HTTP 状态码（仅 API 错误）
+    pub http_status: Option<u16>,
+    /// 错误摘要（人类可读文本的第一行）
+    pub message: String,
+    /// 上游接口返回的原始错误消息
+    pub upstream_msg: Option<String>,
+    /// 上游业务错误代码
+    pub upstream_code: Option<String>,
+    /// 上游请求 ID
+    pub request_id: Option<String>,
+    /// 修复建议
+    pub suggestions: Vec<String>,
+    /// 稍后重试是否可能成功
+    pub retryable: bool,
+}
+
+impl ErrorDocument {
+    /// 从 `anyhow::Error` 生成错误文档
+    pub fn from_error(err: &anyhow::Error) -> Self {
+        let text = format!("{:#}", err);
+        let message = text.lines().next().unwrap_or_default().to_string();
+        let cli_error = find_cli_error(err);
+
+        let (http_status, upstream_msg, upstream_code, request_id) = match cli_error.map(CliError::inner) {
+            Some(CliError::ApiError {
+                code,
+                upstream_msg,
+                upstream_code,
+                request_id,
+                ..
+            }) => (
+                Some(*code),
+                upstream_msg.clone(),
+                upstream_code.clone(),
+                request_id.clone(),
+            ),
+            Some(CliError::BusinessError {
+                upstream_msg,
+                upstream_code,
+                request_id,
+                ..
+            }) => (None, upstream_msg.clone(), upstream_code.clone(), request_id.clone()),
+            _ => (None, None, None, None),
+        };
+
+        ErrorDocument {
+            error: ErrorDetail {
+                kind: cli_error.map(CliError::kind).unwrap_or("internal"),
+                exit_code: CliError::exit_code_of(err),
+                http_status,
+                message,
+                upstream_msg,
+                upstream_code,
+                request_id,
+                suggestions: cli_error.map(CliError::suggestions).unwrap_or_default(),
+                retryable: cli_error.is_some_and(CliError::retryable),
+            },
+        }
+    }
+
+    /// 命令行参数错误（clap 解析失败）的错误文档
+    ///
+    /// `message` 为 clap 渲染的错误文本，其中的 `tip:` 行作为修复建议。
+    pub fn usage(message: &str) -> Self {
+        let lines: Vec<&str> = message.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+        let summary = lines.first().copied().unwrap_or_default();
+        let mut suggestions: Vec<String> = lines
+            .iter()
+            .filter_map(|line| line.strip_prefix("tip: "))
+            .map(str::to_string)
+            .collect();
+        suggestions.push("运行 glm --help 查看用法".to_string());
+        ErrorDocument {
+            error: ErrorDetail {
+                kind: "usage",
+                exit_code: exit_code::USAGE,
+                http_status: None,
+                message: summary.trim_start_matches("error: ").to_string(),
+                upstream_msg: None,
+                upstream_code: None,
+                request_id: None,
+                suggestions,
+                retryable: false,
+            },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -64,8 +399,154 @@ mod tests {
         let err = CliError::ApiError {
             code: 401,
             msg: "未授权".to_string(),
+            upstream_msg: None,
+            upstream_code: None,
+            request_id: None,
         };
         assert!(format!("{}", err).contains("401"));
         assert!(format!("{}", err).contains("未授权"));
     }
+
+    #[test]
+    fn test_exit_codes() {
+        let api = |code| CliError::ApiError {
+            code,
+            msg: String::new(),
+            upstream_msg: None,
+            upstream_code: None,
+            request_id: None,
+        };
+        assert_eq!(CliError::ConfigError(String::new()).exit_code(), exit_code::CONFIG);
+        assert_eq!(CliError::NetworkError(String::new()).exit_code(), exit_code::NETWORK);
+        assert_eq!(api(401).exit_code(), exit_code::AUTH);
+        assert_eq!(api(403).exit_code(), exit_code::AUTH);
+        assert_eq!(api(429).exit_code(), exit_code::RATE_LIMITED);
+        assert_eq!(CliError::QuotaExhausted(String::new()).exit_code(), exit_code::QUOTA);
+        assert_eq!(CliError::Interrupted(String::new()).exit_code(), exit_code::INTERRUPTED);
+        assert_eq!(CliError::Interrupted(String::new()).kind(), "interrupted");
+        assert!(!CliError::Interrupted(String::new()).retryable());
+        assert_eq!(CliError::Timeout(String::new()).exit_code(), exit_code::TIMEOUT);
+        assert_eq!(api(502).exit_code(), exit_code::SERVER);
+        assert_eq!(api(404).exit_code(), exit_code::API);
+        let business = CliError::BusinessError {
+            msg: String::new(),
+            upstream_msg: None,
+            upstream_code: Some("9999".to_string()),
+            request_id: None,
+        };
+        assert_eq!(business.exit_code(), exit_code::API);
+        assert_eq!(business.kind(), "business");
+        assert!(!business.retryable());
+        assert_eq!(CliError::ParseError(String::new()).exit_code(), exit_code::PARSE);
+        assert_eq!(CliError::ValidationError(String::new()).exit_code(), exit_code::PARSE);
+        assert_eq!(CliError::HookError(String::new()).exit_code(), exit_code::GENERAL);
+        assert_eq!(CliError::HookError(String::new()).kind(), "hook");
+    }
+
+    #[test]
+    fn test_exit_code_of_context_chain() {
+        let err = anyhow::Error::from(CliError::ApiError {
+            code: 429,
+            msg: "限流".to_string(),
+            upstream_msg: None,
+            upstream_code: None,
+            request_id: None,
+        })
+        .context("获取使用情况失败");
+        assert_eq!(CliError::exit_code_of(&err), exit_code::RATE_LIMITED);
+
+        let err = anyhow::anyhow!("未知错误");
+        assert_eq!(CliError::exit_code_of(&err), exit_code::GENERAL);
+    }
+
+    #[test]
+    fn test_error_document_for_api_error() {
+        let err = anyhow::Error::from(CliError::ApiError {
+            code: 401,
+            msg: "认证失败\n\n原因: 令牌已过期".to_string(),
+            upstream_msg: Some("令牌已过期".to_string()),
+            upstream_code: Some("1001".to_string()),
+            request_id: Some("req-42".to_string()),
+        })
+        .context("获取使用情况失败");
+
+        let doc = ErrorDocument::from_error(&err);
+        assert_eq!(doc.error.kind, "auth");
+        assert_eq!(doc.error.exit_code, exit_code::AUTH);
+        assert_eq!(doc.error.http_status, Some(401));
+        assert_eq!(doc.error.upstream_msg.as_deref(), Some("令牌已过期"));
+        assert_eq!(doc.error.upstream_code.as_deref(), Some("1001"));
+        assert_eq!(doc.error.request_id.as_deref(), Some("req-42"));
+        assert_eq!(
+            doc.error.suggestions,
+            vec!["请检查 API 密钥是否正确配置", "请确认 API 密钥未过期", "请访问智谱 AI 平台重新生成密钥"]
+        );
+        assert!(!doc.error.retryable);
+
+        let json = serde_json::to_value(&doc).unwrap();
+        assert_eq!(json["error"]["kind"], "auth");
+        assert_eq!(json["error"]["http_status"], 401);
+    }
+
+    #[test]
+    fn test_error_document_for_config_error() {
+        let err = anyhow::Error::from(
+            CliError::ConfigError("API 密钥长度不足\n\n原因: 长度不足".to_string())
+                .suggest(["请确认您的 API 密钥完整"]),
+        );
+        let doc = ErrorDocument::from_error(&err);
+        assert_eq!(doc.error.kind, "config");
+        assert_eq!(doc.error.exit_code, exit_code::CONFIG);
+        assert!(doc.error.http_status.is_none());
+        assert_eq!(doc.error.suggestions, vec!["请确认您的 API 密钥完整"]);
+        assert_eq!(
+            err.to_string(),
+            "配置错误: API 密钥长度不足\n\n原因: 长度不足\n\n建议: 请确认您的 API 密钥完整"
+        );
+    }
+
+    #[test]
+    fn test_suggestions_render_as_numbered_list() {
+        let err = CliError::NetworkError("无法连接到服务器".to_string())
+            .suggest(["请检查网络连接"])
+            .suggest(["请检查防火墙设置"]);
+        assert_eq!(err.kind(), "network");
+        assert!(err.retryable());
+        assert_eq!(err.suggestions(), ["请检查网络连接", "请检查防火墙设置"]);
+        assert!(matches!(err.inner(), CliError::NetworkError(_)));
+        assert_eq!(
+            err.to_string(),
+            "网络错误: 无法连接到服务器\n\n建议:\n1. 请检查网络连接\n2. 请检查防火墙设置"
+        );
+    }
+
+    #[test]
+    fn test_error_document_for_usage_error() {
+        let doc = ErrorDocument::usage(
+            "error: unexpected argument '--bogus' found\n\n  tip: to pass '--bogus' as a value, use '-- --bogus'\n\nUsage: glm [OPTIONS]\n",
+        );
+        assert_eq!(doc.error.kind, "usage");
+        assert_eq!(doc.error.exit_code, exit_code::USAGE);
+        assert_eq!(doc.error.message, "unexpected argument '--bogus' found");
+        assert_eq!(
+            doc.error.suggestions,
+            ["to pass '--bogus' as a value, use '-- --bogus'", "运行 glm --help 查看用法"]
+        );
+    }
+
+    #[test]
+    fn test_error_document_retryable_and_internal() {
+        let err = anyhow::Error::from(CliError::ApiError {
+            code: 503,
+            msg: String::new(),
+            upstream_msg: None,
+            upstream_code: None,
+            request_id: None,
+        });
+        assert!(ErrorDocument::from_error(&err).error.retryable);
+
+        let doc = ErrorDocument::from_error(&anyhow::anyhow!("未知"));
+        assert_eq!(doc.error.kind, "internal");
+        assert_eq!(doc.error.exit_code, exit_code::GENERAL);
+    }
 }
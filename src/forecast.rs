@@ -0,0 +1,227 @@
+//! 额度耗尽预测
+//!
+//! 根据当前窗口内的快照（或窗口开始以来的平均速度）估算 token 消耗速度，
+//! 推算额度会在何时耗尽、是否早于窗口重置。
+
+use crate::api::LimitItem;
+use crate::history::Snapshot;
+use crate::quota::next_reset_at;
+use chrono::{DateTime, Duration, FixedOffset, Months, Utc};
+use serde::Serialize;
+
+/// Token 额度窗口长度（小时）
+pub const WINDOW_HOURS: i64 = 5;
+
+/// 用快照估算速度时回看的时长
+const LOOKBACK: Duration = Duration::hours(1);
+
+/// 估算速度所需的最短时间跨度
+const MIN_SPAN: Duration = Duration::minutes(10);
+
+/// 额度项当前窗口的开始时间
+///
+/// `TIME_LIMIT` 按月重置，窗口为重置前一个自然月（按 `offset` 时区）；
+/// 其余额度为重置前 [`WINDOW_HOURS`] 小时。
+fn window_start(item: &LimitItem, reset_at: DateTime<Utc>, offset: FixedOffset) -> Option<DateTime<Utc>> {
+    if item.limit_type == "TIME_LIMIT" {
+        return reset_at
+            .with_timezone(&offset)
+            .checked_sub_months(Months::new(1))
+            .map(|start| start.with_timezone(&Utc));
+    }
+    Some(reset_at - Duration::hours(WINDOW_HOURS))
+}
+
+/// 速度估算依据
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastBasis {
+    /// 最近一小时内的快照
+    History,
+    /// 窗口开始以来的平均速度
+    WindowAverage,
+    /// 数据不足，无法估算
+    Insufficient,
+}
+
+/// 额度耗尽预测
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Forecast {
+    /// 额度类型
+    pub limit_type: String,
+
+    /// 当前使用百分比
+    pub percentage: f64,
+
+    /// 剩余额度
+    pub remaining: u64,
+
+    /// 窗口重置时间
+    pub reset_at: Option<DateTime<Utc>>,
+
+    /// 估算的消耗速度（每小时）
+    pub per_hour: Option<f64>,
+
+    /// 按当前速度耗尽的时间（速度为 0 或无法估算时为 None）
+    pub exhausts_at: Option<DateTime<Utc>>,
+
+    /// 是否会在窗口重置前耗尽
+    pub exhausts_before_reset: bool,
+
+    /// 速度估算依据
+    pub basis: ForecastBasis,
+}
+
+/// 预测额度项的耗尽时间
+///
+/// `snapshots` 为近期快照（按时间升序），只使用同一窗口内最近一小时的数据；
+/// 跨度不足时退回到窗口开始以来的平均速度。重置时间与窗口长度按额度类型确定，
+/// `offset` 为按月重置额度所依据的区域时区，见 [`next_reset_at`]。
+pub fn forecast(item: &LimitItem, snapshots: &[Snapshot], now: DateTime<Utc>, offset: FixedOffset) -> Forecast {
+    let reset_at = next_reset_at(item, now, offset);
+
+    let from_history = snapshots
+        .iter()
+        .filter(|s| s.taken_at >= now - LOOKBACK && s.taken_at <= now - MIN_SPAN)
+        .filter_map(|s| Some((s.taken_at, s.limit(&item.limit_type)?)))
+        .find(|(_, past)| past.next_reset_time == item.next_reset_time && past.current_value <= item.current_value)
+        .map(|(taken_at, past)| {
+            (item.current_value - past.current_value) as f64 / hours(now - taken_at)
+        });
+
+    let window_average = reset_at
+        .and_then(|reset| window_start(item, reset, offset))
+        .filter(|start| now - *start >= MIN_SPAN)
+        .map(|start| item.current_value as f64 / hours(now - start));
+
+    let (per_hour, basis) = match (from_history, window_average) {
+        (Some(rate), _) => (Some(rate), ForecastBasis::History),
+        (None, Some(rate)) => (Some(rate), ForecastBasis::WindowAverage),
+        (None, None) => (None, ForecastBasis::Insufficient),
+    };
+
+    let exhausts_at = match per_hour {
+        _ if item.remaining == 0 && item.usage > 0 => Some(now),
+        // 速率极低时耗尽时间可能超出可表示的范围，视为无法预测
+        Some(rate) if rate > 0.0 => Duration::try_seconds((item.remaining as f64 / rate * 3600.0) as i64)
+            .and_then(|left| now.checked_add_signed(left)),
+        _ => None,
+    };
+
+    Forecast {
+        limit_type: item.limit_type.clone(),
+        percentage: item.percentage,
+        remaining: item.remaining,
+        reset_at,
+        per_hour,
+        exhausts_at,
+        exhausts_before_reset: match (exhausts_at, reset_at) {
+            (Some(exhausts), Some(reset)) => exhausts < reset,
+            (Some(_), None) => true,
+            _ => false,
+        },
+        basis,
+    }
+}
+
+fn hours(duration: Duration) -> f64 {
+    duration.num_seconds() as f64 / 3600.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn item(limit_type: &str, current_value: u64, reset: Option<DateTime<Utc>>) -> LimitItem {
+        serde_json::from_value(serde_json::json!({
+            "type": limit_type,
+            "usage": 1000,
+            "currentValue": current_value,
+            "nextResetTime": reset.map(|t| t.timestamp_millis())
+        }))
+        .unwrap()
+    }
+
+    fn utc() -> FixedOffset {
+        FixedOffset::east_opt(0).unwrap()
+    }
+
+    #[test]
+    fn test_forecast_from_window_average() {
+        let now = Utc.with_ymd_and_hms(2025, 10, 1, 2, 0, 0).unwrap();
+        // 窗口开始 2 小时，已用 400：每小时 200，剩余 600 需 3 小时，恰好在重置时耗尽
+        let forecast = forecast(&item("TOKENS_LIMIT", 400, Some(now + Duration::hours(3))), &[], now, utc());
+        assert_eq!(forecast.basis, ForecastBasis::WindowAverage);
+        assert_eq!(forecast.per_hour, Some(200.0));
+        assert_eq!(forecast.exhausts_at, Some(now + Duration::hours(3)));
+        assert!(!forecast.exhausts_before_reset);
+    }
+
+    #[test]
+    fn test_forecast_from_history() {
+        let now = Utc.with_ymd_and_hms(2025, 10, 1, 2, 0, 0).unwrap();
+        let reset = now + Duration::hours(3);
+        let snapshot = |minutes_ago: i64, value: u64| Snapshot {
+            taken_at: now - Duration::minutes(minutes_ago),
+            limits: vec![item("TOKENS_LIMIT", value, Some(reset))],
+        };
+        // 最近 30 分钟从 400 增加到 700：每小时 600，剩余 300 只够 30 分钟
+        let snapshots = [snapshot(90, 100), snapshot(30, 400), snapshot(5, 690)];
+        let forecast = forecast(&item("TOKENS_LIMIT", 700, Some(reset)), &snapshots, now, utc());
+        assert_eq!(forecast.basis, ForecastBasis::History);
+        assert_eq!(forecast.per_hour, Some(600.0));
+        assert_eq!(forecast.exhausts_at, Some(now + Duration::minutes(30)));
+        assert!(forecast.exhausts_before_reset);
+    }
+
+    #[test]
+    fn test_forecast_time_limit_monthly_window() {
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        // 北京时间 2025-10-11 00:00，本月已过 10 天，已用 100：每天 10 次
+        let now = Utc.with_ymd_and_hms(2025, 10, 10, 16, 0, 0).unwrap();
+        let reset = Utc.with_ymd_and_hms(2025, 10, 31, 16, 0, 0).unwrap();
+        let forecast = forecast(&item("TIME_LIMIT", 100, None), &[], now, offset);
+        assert_eq!(forecast.reset_at, Some(reset));
+        assert_eq!(forecast.basis, ForecastBasis::WindowAverage);
+        assert!((forecast.per_hour.unwrap() - 10.0 / 24.0).abs() < 1e-9);
+        // 剩余 900 需 90 天，月底重置前不会耗尽
+        assert!(!forecast.exhausts_before_reset);
+
+        // 已用 800：每天 80 次，剩余 200 只够 2.5 天
+        let forecast = super::forecast(&item("TIME_LIMIT", 800, None), &[], now, offset);
+        assert!(forecast.exhausts_before_reset);
+        assert_eq!(forecast.exhausts_at, Some(now + Duration::minutes(60 * 60)));
+    }
+
+    #[test]
+    fn test_forecast_far_future_exhaustion() {
+        let now = Utc.with_ymd_and_hms(2025, 10, 1, 2, 0, 0).unwrap();
+        // 窗口开始 4 小时只用了 1：剩余 10 亿需约 45 万年，超出可表示的时间范围
+        let far = |remaining: u64| -> LimitItem {
+            serde_json::from_value(serde_json::json!({
+                "type": "TOKENS_LIMIT",
+                "usage": remaining,
+                "currentValue": 1,
+                "remaining": remaining,
+                "nextResetTime": (now + Duration::hours(1)).timestamp_millis()
+            }))
+            .unwrap()
+        };
+        for remaining in [1_000_000_000, u64::MAX] {
+            let forecast = forecast(&far(remaining), &[], now, utc());
+            assert_eq!(forecast.per_hour, Some(0.25));
+            assert_eq!(forecast.exhausts_at, None);
+            assert!(!forecast.exhausts_before_reset);
+        }
+    }
+
+    #[test]
+    fn test_forecast_insufficient_data() {
+        let now = Utc::now();
+        let forecast = forecast(&item("TOKENS_LIMIT", 10, None), &[], now, utc());
+        assert_eq!(forecast.basis, ForecastBasis::Insufficient);
+        assert_eq!(forecast.exhausts_at, None);
+        assert!(!forecast.exhausts_before_reset);
+    }
+}
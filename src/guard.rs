@@ -0,0 +1,352 @@
+//! 额度守卫
+//!
+//! `glm guard -- <命令>` 在启动命令前检查 Token 额度：剩余低于下限时拒绝启动
+//! （或使用 `--wait` 等待窗口重置），运行期间可按间隔复查，剩余低于硬限制时
+//! 向子进程发送 SIGTERM / SIGINT。子进程的退出码原样传递。
+
+use crate::api::{ApiData, GlmClient, LimitItem, PLAN_QUOTA};
+use crate::error::CliError;
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// 窗口重置后再等待的时间，避免刚好在重置前后查询到旧数据
+const RESET_GRACE: Duration = Duration::from_secs(15);
+
+/// 剩余额度下限
+///
+/// 以 `%` 结尾时按剩余百分比比较（如 `10%`），否则按剩余 token 数比较（如 `2000000`）。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Floor {
+    /// 剩余百分比（0-100）
+    Percent(f64),
+    /// 剩余 token 数
+    Tokens(u64),
+}
+
+impl Floor {
+    /// 额度项的剩余量是否低于下限
+    pub fn is_crossed(&self, item: &LimitItem) -> bool {
+        match *self {
+            Floor::Percent(percent) => 100.0 - item.percentage < percent,
+            Floor::Tokens(tokens) => item.remaining < tokens,
+        }
+    }
+}
+
+impl fmt::Display for Floor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Floor::Percent(percent) => write!(f, "{}%", percent),
+            Floor::Tokens(tokens) => write!(f, "{} tokens", tokens),
+        }
+    }
+}
+
+impl FromStr for Floor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.strip_suffix('%') {
+            Some(percent) => match percent.trim().parse::<f64>() {
+                Ok(p) if (0.0..=100.0).contains(&p) => Ok(Floor::Percent(p)),
+                _ => Err(format!("无效的百分比: {}（应在 0%-100% 之间）", s)),
+            },
+            None => s
+                .parse::<u64>()
+                .map(Floor::Tokens)
+                .map_err(|_| format!("无效的额度下限: {}（示例: 10% 或 2000000）", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Floor {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Tokens(u64),
+            Text(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Tokens(tokens) => Ok(Floor::Tokens(tokens)),
+            Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl Serialize for Floor {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Floor::Percent(percent) => serializer.serialize_str(&format!("{}%", percent)),
+            Floor::Tokens(tokens) => serializer.serialize_u64(*tokens),
+        }
+    }
+}
+
+/// 终止子进程时发送的信号
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum GuardSignal {
+    /// SIGTERM
+    #[default]
+    Term,
+    /// SIGINT（相当于 Ctrl-C，部分工具会借此保存进度）
+    Int,
+}
+
+/// 守卫配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GuardConfig {
+    /// 启动命令所需的最低剩余额度（默认 10%）
+    #[serde(default = "default_min_remaining")]
+    pub min_remaining: Floor,
+
+    /// 运行期间剩余额度低于该值时终止命令（可选，未设置时不复查）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hard_limit: Option<Floor>,
+
+    /// 运行期间复查的间隔（秒，默认 60）
+    #[serde(default = "default_check_interval")]
+    pub check_interval: u64,
+
+    /// 终止命令时发送的信号（默认 term）
+    #[serde(default)]
+    pub signal: GuardSignal,
+}
+
+fn default_min_remaining() -> Floor {
+    Floor::Percent(10.0)
+}
+
+fn default_check_interval() -> u64 {
+    60
+}
+
+impl Default for GuardConfig {
+    fn default() -> Self {
+        GuardConfig {
+            min_remaining: default_min_remaining(),
+            hard_limit: None,
+            check_interval: default_check_interval(),
+            signal: GuardSignal::Term,
+        }
+    }
+}
+
+impl GuardConfig {
+    /// 验证守卫配置
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if self.check_interval < 10 {
+            return Err(CliError::ConfigError(format!(
+                "guard.check_interval 不能小于 10 秒\n\n原因: 当前设置: {} 秒",
+                self.check_interval
+            ))
+            .suggest(["设置为 60 秒"]));
+        }
+        Ok(())
+    }
+}
+
+/// 守卫检查的额度项：TOKENS_LIMIT，计划接口下为 PLAN_QUOTA
+pub fn guarded_limit(data: &ApiData) -> Option<&LimitItem> {
+    data.limits
+        .iter()
+        .find(|item| item.limit_type == "TOKENS_LIMIT")
+        .or_else(|| data.limits.iter().find(|item| item.limit_type == PLAN_QUOTA))
+}
+
+/// 剩余额度低于下限时返回对应的额度项
+pub fn below_floor<'a>(data: &'a ApiData, floor: &Floor) -> Option<&'a LimitItem> {
+    guarded_limit(data).filter(|item| floor.is_crossed(item))
+}
+
+/// 额度状况的简短描述
+fn describe(item: &LimitItem, floor: &Floor) -> String {
+    let reset = item
+        .next_reset_time
+        .and_then(DateTime::<Utc>::from_timestamp_millis)
+        .map(|t| format!("，将于 {} 重置", t.with_timezone(&Local).format("%Y-%m-%d %H:%M")))
+        .unwrap_or_default();
+    format!(
+        "{} 剩余 {:.1}%（{} tokens），低于下限 {}{}",
+        item.limit_type,
+        100.0 - item.percentage,
+        item.remaining,
+        floor,
+        reset
+    )
+}
+
+/// 距离窗口重置还需等待的时间（未知时返回 `None`）
+fn until_reset(item: &LimitItem, now: DateTime<Utc>) -> Option<Duration> {
+    let reset = DateTime::<Utc>::from_timestamp_millis(item.next_reset_time?)?;
+    Some((reset - now).to_std().unwrap_or_default() + RESET_GRACE)
+}
+
+/// 在额度充足时运行命令，返回子进程的退出码
+///
+/// 剩余额度低于 `min_remaining` 时：`wait` 为 false 则返回额度不足错误，
+/// 否则等待窗口重置后重新检查。命令运行期间若配置了 `hard_limit`，
+/// 每隔 `check_interval` 秒复查一次，低于硬限制时发送终止信号。
+pub async fn run(client: &GlmClient, config: &GuardConfig, wait: bool, command: &[String]) -> Result<u8> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(CliError::ConfigError("缺少要运行的命令".to_string())
+            .suggest(["glm guard -- <命令> [参数...]"])
+            .into());
+    };
+
+    loop {
+        let data = client.fetch_usage().await?;
+        let Some(item) = below_floor(&data, &config.min_remaining) else {
+            if guarded_limit(&data).is_none() {
+                tracing::warn!("响应中没有 Token 额度信息，跳过额度检查");
+            }
+            break;
+        };
+
+        let reason = describe(item, &config.min_remaining);
+        if !wait {
+            return Err(CliError::QuotaExhausted(reason)
+                .suggest(["使用 --wait 等待窗口重置后自动启动，或通过 --min-remaining 调低下限"])
+                .into());
+        }
+
+        let delay = until_reset(item, Utc::now())
+            .unwrap_or_else(|| Duration::from_secs(config.check_interval));
+        tracing::warn!("{}，等待 {} 秒后重新检查", reason, delay.as_secs());
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = tokio::signal::ctrl_c() => {
+                return Err(CliError::Interrupted("等待额度时被中断".to_string()).into());
+            }
+        }
+    }
+
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .spawn()
+        .map_err(|e| CliError::ConfigError(format!("无法启动命令 `{}`: {}", program, e)))?;
+
+    let interval = Duration::from_secs(config.check_interval);
+    let mut signalled = false;
+    let status = loop {
+        tokio::select! {
+            status = child.wait() => break status?,
+            _ = tokio::time::sleep(interval), if config.hard_limit.is_some() && !signalled => {
+                let Some(hard_limit) = config.hard_limit else { continue };
+                match client.fetch_usage().await {
+                    Ok(data) => {
+                        if let Some(item) = below_floor(&data, &hard_limit) {
+                            tracing::warn!("{}，正在终止命令", describe(item, &hard_limit));
+                            terminate(&mut child, config.signal)?;
+                            signalled = true;
+                        }
+                    }
+                    Err(e) => tracing::warn!("复查使用情况失败: {:#}", e),
+                }
+            }
+            // 终端的 Ctrl-C 同时发给子进程，这里只需等待子进程退出
+            _ = tokio::signal::ctrl_c() => {}
+        }
+    };
+
+    Ok(exit_code_of(status))
+}
+
+/// 将子进程退出状态转换为退出码（被信号终止时为 128 + 信号值）
+fn exit_code_of(status: std::process::ExitStatus) -> u8 {
+    if let Some(code) = status.code() {
+        return code as u8;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return (128 + signal) as u8;
+        }
+    }
+    1
+}
+
+#[cfg(unix)]
+fn terminate(child: &mut tokio::process::Child, signal: GuardSignal) -> Result<()> {
+    let Some(pid) = child.id() else {
+        return Ok(());
+    };
+    let signal = match signal {
+        GuardSignal::Term => libc::SIGTERM,
+        GuardSignal::Int => libc::SIGINT,
+    };
+    // SAFETY: 向仍在运行的子进程发送信号，pid 来自 tokio 的子进程句柄
+    if unsafe { libc::kill(pid as libc::pid_t, signal) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn terminate(child: &mut tokio::process::Child, _signal: GuardSignal) -> Result<()> {
+    child.start_kill()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(current_value: u64) -> ApiData {
+        serde_json::from_value(serde_json::json!({
+            "limits": [
+                {"type": "TIME_LIMIT", "usage": 1000, "currentValue": 999},
+                {"type": "TOKENS_LIMIT", "usage": 1000, "currentValue": current_value}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_floor() {
+        assert_eq!("10%".parse::<Floor>(), Ok(Floor::Percent(10.0)));
+        assert_eq!(" 2.5 % ".parse::<Floor>(), Ok(Floor::Percent(2.5)));
+        assert_eq!("2000000".parse::<Floor>(), Ok(Floor::Tokens(2_000_000)));
+        assert!("150%".parse::<Floor>().is_err());
+        assert!("lots".parse::<Floor>().is_err());
+
+        let config: GuardConfig = serde_yaml::from_str("min_remaining: 5%\nhard_limit: 100\n").unwrap();
+        assert_eq!(config.min_remaining, Floor::Percent(5.0));
+        assert_eq!(config.hard_limit, Some(Floor::Tokens(100)));
+        assert_eq!(config.check_interval, 60);
+        assert_eq!(serde_yaml::from_str::<GuardConfig>("{}").unwrap().min_remaining, Floor::Percent(10.0));
+    }
+
+    #[test]
+    fn test_below_floor() {
+        // TIME_LIMIT 几乎用尽不影响守卫，只看 TOKENS_LIMIT
+        assert!(below_floor(&usage(850), &Floor::Percent(10.0)).is_none());
+        let data = usage(950);
+        let item = below_floor(&data, &Floor::Percent(10.0)).unwrap();
+        assert_eq!(item.limit_type, "TOKENS_LIMIT");
+        assert!(below_floor(&usage(950), &Floor::Tokens(40)).is_none());
+        assert!(below_floor(&usage(970), &Floor::Tokens(40)).is_some());
+
+        let empty: ApiData = serde_json::from_value(serde_json::json!({"limits": []})).unwrap();
+        assert!(below_floor(&empty, &Floor::Percent(100.0)).is_none());
+    }
+
+    #[test]
+    fn test_until_reset() {
+        let now = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+        let mut item = usage(0).limits[1].clone();
+        assert_eq!(until_reset(&item, now), None);
+        item.next_reset_time = Some((now + chrono::Duration::minutes(5)).timestamp_millis());
+        assert_eq!(until_reset(&item, now), Some(Duration::from_secs(300) + RESET_GRACE));
+        item.next_reset_time = Some((now - chrono::Duration::minutes(5)).timestamp_millis());
+        assert_eq!(until_reset(&item, now), Some(RESET_GRACE));
+    }
+}
@@ -0,0 +1,265 @@
+//! 使用情况快照历史
+//!
+//! 每次成功查询后将额度快照追加写入 `~/.glm/history.jsonl`（每行一个 JSON 对象），
+//! 供 `glm report` 等命令统计一段时间内的使用情况。
+
+use crate::api::{ApiData, LimitItem};
+use crate::error::CliError;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 历史文件超过该大小时清理快照
+const PRUNE_THRESHOLD_BYTES: u64 = 1024 * 1024;
+
+/// 清理后文件大小的上限（低于清理阈值，避免之后每次追加都重写文件）
+const PRUNE_TARGET_BYTES: usize = 512 * 1024;
+
+/// 清理时保留的天数
+const RETENTION_DAYS: i64 = 92;
+
+/// 单次查询的额度快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// 查询时间
+    pub taken_at: DateTime<Utc>,
+
+    /// 额度限制列表
+    pub limits: Vec<LimitItem>,
+}
+
+impl Snapshot {
+    /// 按类型查找额度项
+    pub fn limit(&self, limit_type: &str) -> Option<&LimitItem> {
+        self.limits.iter().find(|item| item.limit_type == limit_type)
+    }
+}
+
+/// 默认的历史文件路径
+pub fn history_file_path() -> Result<PathBuf> {
+    Ok(dirs::home_dir()
+        .ok_or_else(|| CliError::ConfigError("无法确定主目录".to_string()))?
+        .join(".glm/history.jsonl"))
+}
+
+/// 追加一条快照
+///
+/// 文件超过 1 MiB 时顺带清理：删除 92 天前的快照，并只保留最新的不超过 512 KiB 的快照。
+pub fn record(path: &Path, data: &ApiData, taken_at: DateTime<Utc>) -> Result<()> {
+    let snapshot = Snapshot {
+        taken_at,
+        limits: data.limits.clone(),
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(&snapshot)?)?;
+    tracing::debug!(path = %path.display(), "已记录使用情况快照");
+
+    if file.metadata()?.len() > PRUNE_THRESHOLD_BYTES {
+        prune(path, taken_at - Duration::days(RETENTION_DAYS), PRUNE_TARGET_BYTES)?;
+    }
+    Ok(())
+}
+
+/// 读取指定时间之后的快照（按时间升序）
+///
+/// 文件不存在时返回空列表；无法解析的行会被跳过。
+pub fn load(path: &Path, since: DateTime<Utc>) -> Result<Vec<Snapshot>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut snapshots: Vec<Snapshot> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str::<Snapshot>(line) {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                tracing::debug!(error = %e, "跳过无法解析的历史记录");
+                None
+            }
+        })
+        .filter(|snapshot| snapshot.taken_at >= since)
+        .collect();
+    snapshots.sort_by_key(|snapshot| snapshot.taken_at);
+    Ok(snapshots)
+}
+
+/// 删除指定时间之前的快照，并从最新的快照起保留不超过 `max_bytes` 的内容
+///
+/// 没有可删除的快照时不改写文件。新内容先写入同目录的临时文件再重命名，
+/// 重命名前会补上读取之后其他进程（watch、守护进程等）追加的快照。
+fn prune(path: &Path, before: DateTime<Utc>, max_bytes: usize) -> Result<()> {
+    let content = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = content.lines().filter(|line| !line.trim().is_empty()).collect();
+
+    let mut kept: Vec<(DateTime<Utc>, &str)> = lines
+        .iter()
+        .filter_map(|line| {
+            serde_json::from_str::<Snapshot>(line)
+                .ok()
+                .map(|snapshot| (snapshot.taken_at, *line))
+        })
+        .filter(|(taken_at, _)| *taken_at >= before)
+        .collect();
+    kept.sort_by_key(|(taken_at, _)| *taken_at);
+
+    let mut size = 0;
+    let mut first = kept.len();
+    while first > 0 {
+        let line_size = kept[first - 1].1.len() + 1;
+        if size + line_size > max_bytes {
+            break;
+        }
+        first -= 1;
+        size += line_size;
+    }
+    let kept = &kept[first..];
+    if kept.len() == lines.len() {
+        return Ok(());
+    }
+
+    let mut new_content = String::with_capacity(size);
+    for (_, line) in kept {
+        new_content.push_str(line);
+        new_content.push('\n');
+    }
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("history.jsonl");
+    let tmp = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let result = (|| -> Result<()> {
+        std::fs::write(&tmp, &new_content)?;
+        let current = std::fs::read(path)?;
+        if current.len() > content.len() && current.starts_with(content.as_bytes()) {
+            std::fs::OpenOptions::new()
+                .append(true)
+                .open(&tmp)?
+                .write_all(&current[content.len()..])?;
+        }
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result?;
+    tracing::debug!(kept = kept.len(), dropped = lines.len() - kept.len(), "已清理历史快照");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_data(current_value: u64) -> ApiData {
+        serde_json::from_value(serde_json::json!({
+            "limits": [{
+                "type": "TOKENS_LIMIT",
+                "unit": 3,
+                "number": 5,
+                "usage": 1000,
+                "currentValue": current_value,
+                "nextResetTime": 1768328328345i64
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_record_and_load() {
+        let path = std::env::temp_dir().join(format!("glm-history-test-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let t0 = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+
+        record(&path, &create_test_data(100), t0).unwrap();
+        record(&path, &create_test_data(200), t0 + Duration::hours(1)).unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        let all = load(&path, t0).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[1].limit("TOKENS_LIMIT").unwrap().current_value, 200);
+        assert_eq!(all[1].limit("TOKENS_LIMIT").unwrap().percentage, 20.0);
+
+        let recent = load(&path, t0 + Duration::minutes(30)).unwrap();
+        assert_eq!(recent.len(), 1);
+
+        prune(&path, t0 + Duration::minutes(30), PRUNE_TARGET_BYTES).unwrap();
+        assert_eq!(load(&path, t0).unwrap().len(), 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_prune_oversized_file_without_old_entries() {
+        let dir = std::env::temp_dir().join(format!("glm-history-prune-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("history.jsonl");
+
+        // 全部快照都在保留期内，但文件超过清理阈值
+        let t0 = Utc::now() - Duration::days(1);
+        let mut content = String::new();
+        let mut count = 0;
+        while content.len() as u64 <= PRUNE_THRESHOLD_BYTES {
+            let snapshot = Snapshot {
+                taken_at: t0 + Duration::seconds(count),
+                limits: create_test_data(count as u64).limits,
+            };
+            content.push_str(&serde_json::to_string(&snapshot).unwrap());
+            content.push('\n');
+            count += 1;
+        }
+        std::fs::write(&path, &content).unwrap();
+
+        let latest = t0 + Duration::seconds(count);
+        record(&path, &create_test_data(999), latest).unwrap();
+        let size = std::fs::metadata(&path).unwrap().len();
+        assert!(size <= PRUNE_TARGET_BYTES as u64);
+        let kept = load(&path, t0).unwrap();
+        assert!(kept.len() < count as usize);
+        assert_eq!(kept.last().unwrap().taken_at, latest);
+
+        // 清理后的文件低于阈值，下一次追加不再重写
+        record(&path, &create_test_data(1000), latest + Duration::seconds(1)).unwrap();
+        assert_eq!(load(&path, t0).unwrap().len(), kept.len() + 1);
+        assert!(std::fs::metadata(&path).unwrap().len() > size);
+
+        // 没有残留的临时文件
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_prune_keeps_file_when_nothing_to_drop() {
+        let path = std::env::temp_dir().join(format!("glm-history-keep-{}.jsonl", std::process::id()));
+        let t0 = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+        let _ = std::fs::remove_file(&path);
+        record(&path, &create_test_data(100), t0).unwrap();
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+
+        prune(&path, t0 - Duration::days(1), PRUNE_TARGET_BYTES).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().modified().unwrap(), modified);
+        assert_eq!(load(&path, t0).unwrap().len(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let path = std::env::temp_dir().join("glm-history-missing.jsonl");
+        assert!(load(&path, Utc::now()).unwrap().is_empty());
+    }
+}
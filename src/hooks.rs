@@ -0,0 +1,300 @@
+//! 事件钩子
+//!
+//! 额度事件发生时执行 `config.yaml` 中 `hooks:` 配置的 shell 命令，
+//! 事件数据通过环境变量（`GLM_EVENT`、`GLM_LIMIT_TYPE`、`GLM_PERCENTAGE`、`GLM_RESET_AT` 等）
+//! 和标准输入（JSON）传给命令。适合暂停任务队列、切换配置等本地自动化。
+
+use crate::alert::Alert;
+use crate::error::{CliError, ErrorDetail, ErrorDocument};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// 钩子配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HookConfig {
+    /// 告警阈值规则触发时执行
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_threshold: Option<String>,
+
+    /// 额度窗口重置时执行
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_reset: Option<String>,
+
+    /// 获取使用情况失败时执行
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_error: Option<String>,
+
+    /// 单个钩子命令的超时时间（秒，默认 30）
+    #[serde(default = "default_hook_timeout")]
+    pub timeout: u64,
+}
+
+fn default_hook_timeout() -> u64 {
+    30
+}
+
+impl Default for HookConfig {
+    fn default() -> Self {
+        HookConfig {
+            on_threshold: None,
+            on_reset: None,
+            on_error: None,
+            timeout: default_hook_timeout(),
+        }
+    }
+}
+
+impl HookConfig {
+    /// 事件对应的命令
+    pub fn command_for(&self, event: &HookEvent) -> Option<&str> {
+        match event {
+            HookEvent::Threshold(_) => self.on_threshold.as_deref(),
+            HookEvent::Reset(_) => self.on_reset.as_deref(),
+            HookEvent::Error(_) => self.on_error.as_deref(),
+        }
+        .filter(|command| !command.trim().is_empty())
+    }
+
+    /// 是否配置了由告警规则触发的钩子（`on_threshold` / `on_reset`）
+    pub fn watches_alerts(&self) -> bool {
+        [&self.on_threshold, &self.on_reset]
+            .into_iter()
+            .flatten()
+            .any(|command| !command.trim().is_empty())
+    }
+
+    /// 验证钩子配置
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if self.timeout == 0 || self.timeout > 300 {
+            return Err(CliError::ConfigError(format!(
+                "钩子超时时间必须在 1-300 秒之间\n\n原因: 当前设置: {} 秒",
+                self.timeout
+            ))
+            .suggest(["设置为 30 秒"]));
+        }
+        Ok(())
+    }
+}
+
+/// 钩子事件
+///
+/// 序列化后作为命令的标准输入，`event` 字段为事件名称。
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum HookEvent {
+    /// 告警阈值规则触发
+    Threshold(Alert),
+    /// 额度窗口重置
+    Reset(Alert),
+    /// 获取使用情况失败
+    Error(ErrorDetail),
+}
+
+impl HookEvent {
+    /// 由告警生成事件（重置通知对应 `reset`，其余对应 `threshold`）
+    pub fn from_alert(alert: &Alert) -> Self {
+        if alert.threshold.is_none() {
+            HookEvent::Reset(alert.clone())
+        } else {
+            HookEvent::Threshold(alert.clone())
+        }
+    }
+
+    /// 由错误生成事件
+    pub fn from_error(err: &anyhow::Error) -> Self {
+        HookEvent::Error(ErrorDocument::from_error(err).error)
+    }
+
+    /// 事件名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::Threshold(_) => "threshold",
+            HookEvent::Reset(_) => "reset",
+            HookEvent::Error(_) => "error",
+        }
+    }
+
+    /// 传给命令的环境变量
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![("GLM_EVENT", self.name().to_string())];
+        match self {
+            HookEvent::Threshold(alert) | HookEvent::Reset(alert) => {
+                env.push(("GLM_LIMIT_TYPE", alert.limit_type.clone()));
+                env.push(("GLM_PERCENTAGE", format!("{:.1}", alert.percentage)));
+                env.push((
+                    "GLM_RESET_AT",
+                    alert.window_reset_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                ));
+                env.push(("GLM_RULE", alert.rule.clone()));
+                env.push(("GLM_MESSAGE", alert.message.clone()));
+            }
+            HookEvent::Error(detail) => {
+                env.push(("GLM_ERROR_KIND", detail.kind.to_string()));
+                env.push(("GLM_MESSAGE", detail.message.clone()));
+            }
+        }
+        env
+    }
+}
+
+/// 执行事件对应的钩子命令
+///
+/// 未配置该事件的钩子时返回 `Ok(false)`。命令以非零状态退出或超时视为失败，
+/// 命令的输出只记录到调试日志，不会混入 `glm` 自身的输出。
+pub async fn run(config: &HookConfig, event: &HookEvent) -> Result<bool> {
+    let Some(command) = config.command_for(event) else {
+        return Ok(false);
+    };
+
+    let mut child = shell(command)
+        .envs(event.env())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| CliError::HookError(format!("无法执行钩子命令 `{}`: {}", command, e)))?;
+
+    // 命令可能不读取标准输入，写入失败（如管道已关闭）不算错误
+    if let Some(mut stdin) = child.stdin.take() {
+        let input = serde_json::to_vec(event)?;
+        let _ = stdin.write_all(&input).await;
+    }
+
+    let output = tokio::time::timeout(Duration::from_secs(config.timeout), child.wait_with_output())
+        .await
+        .map_err(|_| {
+            CliError::HookError(format!(
+                "钩子命令 `{}` 超时（{} 秒）",
+                command, config.timeout
+            ))
+        })??;
+
+    tracing::debug!(
+        event = event.name(),
+        stdout = %String::from_utf8_lossy(&output.stdout).trim(),
+        stderr = %String::from_utf8_lossy(&output.stderr).trim(),
+        "钩子命令输出"
+    );
+
+    if !output.status.success() {
+        return Err(CliError::HookError(format!(
+            "钩子命令 `{}` 执行失败（{}）",
+            command, output.status
+        ))
+        .into());
+    }
+
+    tracing::info!(event = event.name(), "已执行钩子命令");
+    Ok(true)
+}
+
+/// 执行钩子，失败时只输出警告
+///
+/// 返回命令是否执行成功（未配置时为 `false`）。
+pub async fn notify(config: &HookConfig, event: &HookEvent) -> bool {
+    match run(config, event).await {
+        Ok(ran) => ran,
+        Err(e) => {
+            tracing::warn!(event = event.name(), "钩子执行失败: {:#}", e);
+            false
+        }
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::exit_code;
+    use chrono::DateTime;
+
+    fn alert(threshold: Option<f64>) -> Alert {
+        Alert {
+            rule: "TOKENS_LIMIT:percentage>=80".to_string(),
+            limit_type: "TOKENS_LIMIT".to_string(),
+            field: threshold.map(|_| "percentage".to_string()),
+            value: 85.0,
+            percentage: 85.0,
+            threshold,
+            window_reset_at: DateTime::from_timestamp(1_760_000_000, 0),
+            message: "TOKENS_LIMIT 已使用 85.0%（阈值 >= 80%）".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_event_env_and_payload() {
+        let event = HookEvent::from_alert(&alert(Some(80.0)));
+        assert_eq!(event.name(), "threshold");
+        let env = event.env();
+        assert!(env.contains(&("GLM_LIMIT_TYPE", "TOKENS_LIMIT".to_string())));
+        assert!(env.contains(&("GLM_PERCENTAGE", "85.0".to_string())));
+        assert!(env.contains(&("GLM_RESET_AT", "2025-10-09T08:53:20+00:00".to_string())));
+
+        let payload = serde_json::to_value(&event).unwrap();
+        assert_eq!(payload["event"], "threshold");
+        assert_eq!(payload["limit_type"], "TOKENS_LIMIT");
+        assert_eq!(payload["threshold"], 80.0);
+
+        assert_eq!(HookEvent::from_alert(&alert(None)).name(), "reset");
+
+        let err = anyhow::Error::from(CliError::NetworkError("连接超时".to_string()));
+        let payload = serde_json::to_value(HookEvent::from_error(&err)).unwrap();
+        assert_eq!(payload["event"], "error");
+        assert_eq!(payload["kind"], "network");
+    }
+
+    #[test]
+    fn test_command_for_event() {
+        let config: HookConfig = serde_yaml::from_str("on_threshold: ./pause.sh\non_reset: \"\"\n").unwrap();
+        assert_eq!(config.timeout, 30);
+        assert_eq!(config.command_for(&HookEvent::from_alert(&alert(Some(80.0)))), Some("./pause.sh"));
+        assert_eq!(config.command_for(&HookEvent::from_alert(&alert(None))), None);
+        assert!(config.validate().is_ok());
+        assert!(HookConfig { timeout: 0, ..config }.validate().is_err());
+        assert!(!HookConfig::default().watches_alerts());
+        assert!(HookConfig { on_reset: Some("true".to_string()), ..HookConfig::default() }.watches_alerts());
+        assert!(!HookConfig { on_threshold: Some(" ".to_string()), ..HookConfig::default() }.watches_alerts());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_hook_command() {
+        let out = std::env::temp_dir().join(format!("glm-hook-test-{}.txt", std::process::id()));
+        let config = HookConfig {
+            on_threshold: Some(format!(
+                "printf '%s ' \"$GLM_EVENT\" \"$GLM_PERCENTAGE\" > '{0}'; cat >> '{0}'",
+                out.display()
+            )),
+            on_error: Some("exit 3".to_string()),
+            ..HookConfig::default()
+        };
+
+        assert!(run(&config, &HookEvent::from_alert(&alert(Some(80.0)))).await.unwrap());
+        let written = std::fs::read_to_string(&out).unwrap();
+        assert!(written.starts_with("threshold 85.0 {\"event\":\"threshold\""));
+        std::fs::remove_file(&out).unwrap();
+
+        assert!(!run(&config, &HookEvent::from_alert(&alert(None))).await.unwrap());
+        let err = anyhow::Error::from(CliError::NetworkError("x".to_string()));
+        let failed = run(&config, &HookEvent::from_error(&err)).await.unwrap_err();
+        assert_eq!(CliError::exit_code_of(&failed), exit_code::GENERAL);
+        assert_eq!(failed.downcast_ref::<CliError>().unwrap().kind(), "hook");
+    }
+}
@@ -1,8 +1,106 @@
 //! GLM API 计划查询命令行工具库
 //!
 //! 提供查询智谱 AI GLM API 计划使用情况的核心功能。
+//!
+//! # 嵌入使用
+//!
+//! 其他 Rust 程序可以直接用 [`GlmClient::builder`] 创建客户端查询额度，
+//! 所有公开接口返回 [`Result`]（错误类型为 [`CliError`]，可通过
+//! [`CliError::kind`] 与 [`CliError::retryable`] 判断失败原因）：
+//!
+//! ```no_run
+//! use glm::{GlmClient, QuotaKind};
+//!
+//! # async fn example() -> glm::Result<()> {
+//! let client = GlmClient::builder()
+//!     .api_key(std::env::var("GLM_API_KEY").unwrap_or_default())
+//!     .build()?;
+//!
+//! let quota = client.fetch_quota().await?;
+//! if let Some(tokens) = quota.tokens() {
+//!     println!("Token 剩余 {:.1}%，重置时间 {:?}", tokens.remaining_percentage(), tokens.resets_at);
+//! }
+//! if let Some(mcp) = quota.get(&QuotaKind::McpCalls) {
+//!     println!("MCP 本月已用 {}/{}", mcp.used, mcp.total);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! 只嵌入库时可关闭默认的 `cli` feature，不引入命令行相关依赖（`clap`、tokio 的
+//! 网络 / 子进程 / 信号支持等）：
+//!
+//! ```toml
+//! glm = { version = "1", default-features = false }
+//! ```
+//!
+//! 库的稳定接口为客户端（[`GlmClient`]、[`GlmClientBuilder`]）、配置（[`Config`]）、
+//! 额度类型（[`quota`]）、指标模型（[`metrics`]）、渲染（[`output`]，如
+//! [`output::render_table`]）与错误类型（[`error`]）；守护进程、告警、钩子等模块及其
+//! 配置（`cli_config`）只在 `cli` feature 下编译，仅供 `glm` 命令行程序使用。
+//!
+//! 没有异步运行时的程序可启用 `blocking` feature，使用同步的 `GlmBlockingClient`
+//! （由 `GlmClient::builder().build_blocking()` 创建）。
+//!
+//! 启用 `otel` feature 后可通过 `otlp::OtlpExporter` 以 OTLP/HTTP 推送额度指标。
 
 pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod config;
 pub mod error;
+pub mod metrics;
+#[cfg(feature = "otel")]
+pub mod otlp;
 pub mod output;
+pub mod quota;
+
+// 以下模块仅供 glm 命令行程序使用，不属于库的稳定接口
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod alert;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod cli_config;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod daemon;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod doctor;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod forecast;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod guard;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod history;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod hooks;
+#[doc(hidden)]
+pub mod logging;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod mcp;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod report;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod statsd;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod tui;
+#[cfg(feature = "cli")]
+#[doc(hidden)]
+pub mod wait;
+
+pub use api::{GlmClient, GlmClientBuilder};
+#[cfg(feature = "blocking")]
+pub use blocking::GlmBlockingClient;
+pub use config::{ApiFlavor, Config, Region};
+pub use error::{CliError, Result};
+pub use quota::{Quota, QuotaKind, QuotaLimit};
@@ -2,11 +2,35 @@
 //!
 //! 用于查询智谱 AI GLM API 的计划使用情况。
 
-use anyhow::Result;
-use clap::Parser;
-use glm::api::GlmClient;
-use glm::config::Config;
-use glm::output::render_table;
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use clap::{ArgAction, CommandFactory, Parser, Subcommand, ValueEnum};
+use glm::alert;
+use glm::api::{ApiData, GlmClient, UsageDimension};
+use glm::cli_config::CliConfig;
+use glm::config::{Config, Language, Region};
+use glm::daemon::{self, DaemonStatus};
+use glm::doctor::run_diagnostics;
+use glm::error::{CliError, ErrorDocument};
+use glm::guard::{self, Floor, GuardSignal};
+use glm::history;
+use glm::hooks::{self, HookEvent};
+use glm::logging;
+use glm::mcp::McpServer;
+use glm::metrics::{FetchStats, QuotaMetrics};
+#[cfg(feature = "otel")]
+use glm::otlp::OtlpExporter;
+use glm::output::{
+    render, render_compact, render_plan_as, render_report_as, render_usage_breakdown_as,
+    DisplayOptions, DisplayZone, OutputFormat,
+};
+use glm::report::{ReportPeriod, UsageReport};
+use glm::statsd::StatsdExporter;
+use glm::tui;
+use glm::wait::{self, WaitTarget};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
 
 /// GLM API 计划查询工具
 #[derive(Parser, Debug)]
@@ -15,35 +39,803 @@ use glm::output::render_table;
 #[command(version)]
 struct Args {
     /// 输出格式
-    #[arg(short = 'f', long = "format", default_value = "table")]
+    #[arg(short = 'f', long = "format", default_value = "table", value_parser = OutputFormat::NAMES.to_vec(), global = true)]
     format: String,
+
+    /// 数据一致性检查发现问题时报错（默认仅输出警告）
+    #[arg(long, global = true)]
+    strict: bool,
+
+    /// 服务区域（cn: bigmodel.cn，intl: z.ai），覆盖配置中的 region
+    #[arg(long, value_enum, global = true)]
+    region: Option<Region>,
+
+    /// 显示时间所用的时区（如 Asia/Shanghai、+08:00、local），覆盖配置中的 timezone
+    #[arg(long = "tz", value_name = "ZONE", value_parser = parse_zone, global = true)]
+    tz: Option<DisplayZone>,
+
+    /// 以 UTC 显示时间
+    #[arg(long, conflicts_with = "tz", global = true)]
+    utc: bool,
+
+    /// 输出更详细的日志（-v 显示请求与耗时，-vv 显示请求头与配置来源）
+    #[arg(short = 'v', long = "verbose", action = ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// 输出全部调试日志（包括原始响应体）
+    #[arg(long, global = true)]
+    debug: bool,
+
+    /// 将日志写入指定文件而不是标准错误输出
+    #[arg(long = "log-file", value_name = "PATH", global = true)]
+    log_file: Option<PathBuf>,
+
+    /// 错误输出格式（json 便于自动化脚本解析）
+    #[arg(long = "error-format", value_enum, default_value_t = ErrorFormat::Text, global = true)]
+    error_format: ErrorFormat,
+
+    /// 严格校验 API 响应结构，报告每一处与预期不一致的字段
+    #[arg(long = "strict-schema", global = true)]
+    strict_schema: bool,
+
+    /// 不读取守护进程的缓存，直接请求 API
+    #[arg(long = "no-daemon", global = true)]
+    no_daemon: bool,
+
+    /// 子命令（省略时查询使用情况）
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+/// 错误输出格式
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ErrorFormat {
+    /// 人类可读的中文说明
+    Text,
+    /// 结构化 JSON 文档
+    Json,
+}
+
+/// 子命令
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// 显示当前生效的配置（代理、证书、超时等）
+    Config,
+
+    /// 显示订阅计划名称与有效期
+    Plan,
+
+    /// 按模型或工具统计一段时间内的用量
+    Usage {
+        /// 统计维度（model: 按模型统计 token，tool: 按 MCP 工具统计调用次数）
+        #[arg(long, value_enum, default_value_t = UsageDimension::Model)]
+        by: UsageDimension,
+
+        /// 开始日期（YYYY-MM-DD，默认为 6 天前）
+        #[arg(long, value_name = "DATE")]
+        from: Option<NaiveDate>,
+
+        /// 结束日期（YYYY-MM-DD，含当天，默认为今天）
+        #[arg(long, value_name = "DATE")]
+        to: Option<NaiveDate>,
+    },
+
+    /// 根据本地快照历史生成使用报告（-f csv 或 -f md）
+    Report {
+        /// 报告周期
+        #[arg(long, value_enum, default_value_t = ReportPeriod::Week)]
+        period: ReportPeriod,
+    },
+
+    /// 检查一次告警规则并推送新触发的告警（适合 cron 定时运行）
+    Alert {
+        /// 只显示会触发的告警，不推送也不记录去重状态
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// 持续监控使用情况，按间隔刷新并检查告警
+    Watch {
+        /// 刷新间隔（秒，至少 10）
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(10..))]
+        interval: u64,
+
+        /// 不检查告警
+        #[arg(long)]
+        no_alerts: bool,
+    },
+
+    /// 将额度与请求指标推送到监控系统
+    Export {
+        /// 导出目标
+        #[command(subcommand)]
+        target: ExportTarget,
+    },
+
+    /// 全屏终端仪表盘：额度进度条、重置倒计时与近期走势
+    Tui {
+        /// 自动刷新间隔（秒，至少 10）
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(10..))]
+        interval: u64,
+    },
+
+    /// 额度充足时运行命令，不足时拒绝启动或等待窗口重置
+    Guard {
+        /// 启动所需的最低剩余额度（如 10% 或 2000000，覆盖配置中的 guard.min_remaining）
+        #[arg(long, value_name = "FLOOR")]
+        min_remaining: Option<Floor>,
+
+        /// 额度不足时等待窗口重置后再启动
+        #[arg(long)]
+        wait: bool,
+
+        /// 运行期间剩余额度低于该值时终止命令（覆盖配置中的 guard.hard_limit）
+        #[arg(long, value_name = "FLOOR")]
+        hard_limit: Option<Floor>,
+
+        /// 运行期间复查额度的间隔（秒，至少 10）
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(10..))]
+        check_interval: Option<u64>,
+
+        /// 终止命令时发送的信号
+        #[arg(long, value_enum)]
+        signal: Option<GuardSignal>,
+
+        /// 要运行的命令及参数（写在 -- 之后）
+        #[arg(last = true, required = true, value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+
+    /// 等待额度窗口重置或使用率回落（适合批处理脚本）
+    Wait {
+        /// 等到当前 Token 额度窗口重置
+        #[arg(long, conflicts_with = "until_below", required_unless_present = "until_below")]
+        until_reset: bool,
+
+        /// 等到 Token 额度使用率低于指定百分比（如 50%）
+        #[arg(long, value_name = "PERCENT", value_parser = wait::parse_percentage)]
+        until_below: Option<f64>,
+
+        /// 最长等待时间（如 90s、30m、2h），超时后以退出码 10 结束
+        #[arg(long, value_name = "DURATION", value_parser = wait::parse_duration)]
+        timeout: Option<Duration>,
+
+        /// 窗口重置后额外等待的秒数
+        #[arg(long, value_name = "SECS", default_value_t = 15)]
+        margin: u64,
+    },
+
+    /// 在后台定时刷新使用情况，通过 Unix 套接字为其他 glm 命令提供缓存
+    Daemon {
+        /// 管理运行中的守护进程（省略时在前台启动守护进程）
+        #[command(subcommand)]
+        action: Option<DaemonAction>,
+
+        /// 刷新间隔（秒，至少 10，覆盖配置中的 daemon.interval）
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(10..))]
+        interval: Option<u64>,
+    },
+
+    /// 在标准输入输出上运行 MCP 服务，供编程智能体查询额度
+    Mcp,
+
+    /// 逐层诊断配置、网络与 API 问题
+    Doctor {
+        /// 以 JSON 格式输出诊断报告
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// 指标导出目标
+#[derive(Subcommand, Debug)]
+enum ExportTarget {
+    /// 通过 OTLP/HTTP 推送到 OpenTelemetry Collector（需启用 otel feature）
+    #[cfg(feature = "otel")]
+    Otlp {
+        /// Collector 地址（如 http://localhost:4318，默认读取环境变量 OTEL_EXPORTER_OTLP_ENDPOINT）
+        #[arg(long, value_name = "URL")]
+        endpoint: Option<String>,
+
+        /// 推送时附加的请求头（可重复，如 Authorization=Bearer xxx）
+        #[arg(long = "header", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        headers: Vec<(String, String)>,
+
+        /// 追加的资源属性（可重复，如 deployment.environment=prod）
+        #[arg(long = "attribute", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        attributes: Vec<(String, String)>,
+
+        /// 按间隔持续推送（秒，至少 10），省略时只推送一次
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(10..))]
+        interval: Option<u64>,
+    },
+
+    /// 通过 UDP 发送到 StatsD 兼容的代理（Telegraf、Datadog Agent、statsd 等）
+    Statsd {
+        /// 代理地址
+        #[arg(long, value_name = "HOST:PORT", default_value = "127.0.0.1:8125")]
+        addr: String,
+
+        /// 指标名前缀
+        #[arg(long, default_value = "glm")]
+        prefix: String,
+
+        /// 不附带 DogStatsD 标签，额度类型写入指标名（适用于 Graphite）
+        #[arg(long)]
+        no_tags: bool,
+
+        /// 按间隔持续发送（秒，至少 10），省略时只发送一次
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(10..))]
+        interval: Option<u64>,
+    },
+}
+
+/// 指标导出器
+enum Exporter {
+    #[cfg(feature = "otel")]
+    Otlp(OtlpExporter),
+    Statsd(StatsdExporter),
+}
+
+impl Exporter {
+    /// 导出目标（用于提示）
+    fn target(&self) -> &str {
+        match self {
+            #[cfg(feature = "otel")]
+            Exporter::Otlp(exporter) => exporter.url(),
+            Exporter::Statsd(exporter) => exporter.addr(),
+        }
+    }
+
+    /// 导出一次指标
+    ///
+    /// `now` 只用于 OTLP 数据点的时间戳；StatsD 代理按接收时间记录，不需要时间戳。
+    #[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+    async fn export(&self, quota: &[QuotaMetrics], stats: &FetchStats, now: DateTime<Utc>) -> Result<()> {
+        match self {
+            #[cfg(feature = "otel")]
+            Exporter::Otlp(exporter) => exporter.export(quota, stats, now).await,
+            Exporter::Statsd(exporter) => exporter.export(quota, stats).await,
+        }
+    }
+}
+
+/// 守护进程管理操作
+#[derive(Subcommand, Debug)]
+enum DaemonAction {
+    /// 显示运行中的守护进程状态
+    Status,
+    /// 停止运行中的守护进程
+    Stop,
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let _args = Args::parse();
+async fn main() -> ExitCode {
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(err) => return usage_error(err),
+    };
+    let error_format = args.error_format;
+
+    match run(args).await {
+        Ok(code) => code,
+        Err(err) => {
+            match error_format {
+                ErrorFormat::Text => eprintln!("错误: {:#}", err),
+                ErrorFormat::Json => eprintln!(
+                    "{}",
+                    serde_json::to_string(&ErrorDocument::from_error(&err))
+                        .unwrap_or_else(|_| r#"{"error":{"kind":"internal"}}"#.to_string())
+                ),
+            }
+            // 保留错误链中的 CliError，映射为对应的退出码
+            ExitCode::from(CliError::exit_code_of(&err))
+        }
+    }
+}
+
+/// 处理命令行参数错误
+///
+/// 指定了 `--error-format json` 时输出错误文档（退出码 2），否则由 clap 输出帮助或错误说明。
+fn usage_error(err: clap::Error) -> ExitCode {
+    use clap::error::ErrorKind;
+    if matches!(err.kind(), ErrorKind::DisplayHelp | ErrorKind::DisplayVersion) || !json_errors_requested() {
+        err.exit();
+    }
+    eprintln!(
+        "{}",
+        serde_json::to_string(&ErrorDocument::usage(&err.render().to_string()))
+            .unwrap_or_else(|_| r#"{"error":{"kind":"usage"}}"#.to_string())
+    );
+    ExitCode::from(glm::error::exit_code::USAGE)
+}
+
+/// 参数解析失败时，从原始参数判断是否要求 JSON 错误输出
+fn json_errors_requested() -> bool {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter().enumerate().any(|(i, arg)| {
+        arg == "--error-format=json" || (arg == "--error-format" && args.get(i + 1).is_some_and(|value| value == "json"))
+    })
+}
+
+/// 执行命令，返回进程退出码
+async fn run(args: Args) -> Result<ExitCode> {
+    logging::init(args.verbose, args.debug, args.log_file.as_deref())?;
+
+    // 诊断命令自行处理配置加载失败的情况
+    if let Some(Commands::Doctor { json }) = args.command {
+        let loaded = CliConfig::load().map(|mut config| {
+            apply_overrides(&mut config, &args);
+            config
+        });
+        let report = run_diagnostics(loaded).await;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            println!("{}", report.render_text());
+        }
+        if report.failures() > 0 {
+            return Err(anyhow::anyhow!("诊断未通过：{} 项检查失败", report.failures()));
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let format: OutputFormat = args.format.parse().map_err(CliError::ConfigError)?;
+    if format.is_report_only() && !matches!(args.command, Some(Commands::Report { .. })) {
+        Args::command()
+            .error(
+                clap::error::ErrorKind::InvalidValue,
+                format!("输出格式 {} 仅适用于 glm report", args.format),
+            )
+            .exit();
+    }
+
+    // 报告只读取本地快照，不需要 API 密钥
+    if let Some(Commands::Report { period }) = args.command {
+        let now = Utc::now();
+        let snapshots = history::load(&history::history_file_path()?, now - period.duration())?;
+        if snapshots.is_empty() {
+            tracing::warn!("统计区间内没有使用情况快照");
+        }
+        let report = UsageReport::build(period, &snapshots, now);
+        let mut display = match Config::load() {
+            Ok(mut config) => {
+                apply_overrides(&mut config, &args);
+                DisplayOptions::from_config(&config)
+            }
+            Err(_) => DisplayOptions::default(),
+        };
+        apply_display_zone(&mut display, &args);
+        println!("{}", render_report_as(&report, format, &display));
+        return Ok(ExitCode::SUCCESS);
+    }
 
     // 加载配置
-    let config = Config::load().map_err(|e| anyhow::anyhow!("配置加载失败: {}", e))?;
+    let mut config = CliConfig::load().context("配置加载失败")?;
+    apply_overrides(&mut config, &args);
+
+    if let Some(Commands::Config) = args.command {
+        for (name, value) in config.network_report() {
+            println!("{}：{}", name, value);
+        }
+        if let Some(warning) = config.insecure_http_warning() {
+            eprintln!("{}", warning);
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
 
     // 验证配置
-    config
-        .validate()
-        .map_err(|e| anyhow::anyhow!("配置验证失败: {}", e))?;
+    config.validate().context("配置验证失败")?;
+
+    if let Some(warning) = config.insecure_http_warning() {
+        eprintln!("{}", warning);
+    }
+
+    if let Some(Commands::Daemon { action, interval }) = args.command {
+        return daemon_command(&config, action, interval, format).await;
+    }
 
     // 创建 API 客户端
-    let client = GlmClient::new(config.clone())
-        .map_err(|e| anyhow::anyhow!("创建 API 客户端失败: {}", e))?;
+    let client = GlmClient::new(config.base.clone()).context("创建 API 客户端失败")?;
+    let client = if args.no_daemon { client } else { client.with_daemon(&config.daemon) };
+
+    let mut display = DisplayOptions::from_config(&config);
+    apply_display_zone(&mut display, &args);
+
+    if let Some(Commands::Plan) = args.command {
+        let plan = client.fetch_plan().await.context("获取订阅计划失败")?;
+        println!("{}", render_plan_as(&plan, format, &display));
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(Commands::Usage { by, from, to }) = args.command {
+        let (start, end) = usage_range(from, to, config.region.reset_offset())?;
+        let breakdown = client
+            .fetch_usage_breakdown(by, start, end)
+            .await
+            .context("获取用量明细失败")?;
+        println!("{}", render_usage_breakdown_as(&breakdown, format, &display));
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(Commands::Alert { dry_run }) = args.command {
+        config.alerts.validate().context("告警配置验证失败")?;
+        let usage_data = fetch_usage(&client, &config).await?;
+        record_snapshot(&config, &usage_data);
+        let alerts = alert::run(&config, &usage_data, display.language, dry_run).await?;
+        if format == OutputFormat::Json {
+            println!("{}", serde_json::to_string_pretty(&alerts)?);
+        } else if alerts.is_empty() {
+            println!("{}", display.language.pick("未触发告警", "No alerts"));
+        } else {
+            for alert in &alerts {
+                println!("{}", alert.message);
+            }
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(Commands::Watch { interval, no_alerts }) = args.command {
+        if !no_alerts {
+            config.alerts.validate().context("告警配置验证失败")?;
+        }
+        watch(&client, &config, &display, interval, no_alerts).await?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(Commands::Export { target }) = args.command {
+        let (exporter, interval) = match target {
+            #[cfg(feature = "otel")]
+            ExportTarget::Otlp {
+                endpoint,
+                headers,
+                attributes,
+                interval,
+            } => {
+                let endpoint = endpoint
+                    .or_else(|| std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok())
+                    .ok_or_else(|| {
+                        CliError::ConfigError("未指定 OTLP 地址".to_string()).suggest([
+                            "使用 --endpoint http://localhost:4318 或设置环境变量 OTEL_EXPORTER_OTLP_ENDPOINT",
+                        ])
+                    })?;
+                let exporter = OtlpExporter::new(&config, &endpoint, headers, attributes)?;
+                (Exporter::Otlp(exporter), interval)
+            }
+            ExportTarget::Statsd {
+                addr,
+                prefix,
+                no_tags,
+                interval,
+            } => {
+                let exporter = StatsdExporter::new(&config, &addr, &prefix, !no_tags).await?;
+                (Exporter::Statsd(exporter), interval)
+            }
+        };
+        export_metrics(&client, &config, &exporter, interval).await?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(Commands::Tui { interval }) = args.command {
+        // 当前配置在前，其后为 ~/.glm/profiles/ 下的每份额外配置
+        let mut tabs = vec![tui::Tab {
+            name: tab_name(&config),
+            client,
+            config: config.clone(),
+        }];
+        for profile in CliConfig::load_profiles()? {
+            tabs.push(tui::Tab {
+                name: tab_name(&profile),
+                client: GlmClient::new(profile.base.clone()).context("创建 API 客户端失败")?,
+                config: profile,
+            });
+        }
+        tui::run(tabs, display, interval).await?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(Commands::Mcp) = args.command {
+        let server = McpServer::new(client, config);
+        let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+        server.serve(stdin, tokio::io::stdout()).await?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(Commands::Wait {
+        until_reset,
+        until_below,
+        timeout,
+        margin,
+    }) = args.command
+    {
+        let target = match until_below {
+            Some(percentage) if !until_reset => WaitTarget::Below(percentage),
+            _ => WaitTarget::Reset,
+        };
+        let mut progress = |message: String| {
+            eprintln!("[{}] {}", chrono::Local::now().format("%H:%M:%S"), message)
+        };
+        wait::run(&client, target, Duration::from_secs(margin), timeout, &mut progress).await?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(Commands::Guard {
+        min_remaining,
+        wait,
+        hard_limit,
+        check_interval,
+        signal,
+        command,
+    }) = args.command
+    {
+        let mut guard_config = config.guard.clone();
+        guard_config.min_remaining = min_remaining.unwrap_or(guard_config.min_remaining);
+        guard_config.hard_limit = hard_limit.or(guard_config.hard_limit);
+        guard_config.check_interval = check_interval.unwrap_or(guard_config.check_interval);
+        guard_config.signal = signal.unwrap_or(guard_config.signal);
+        let code = guard::run(&client, &guard_config, wait, &command).await?;
+        return Ok(ExitCode::from(code));
+    }
 
     // 获取 API 使用情况
-    let usage_data = client
-        .fetch_usage()
-        .await
-        .map_err(|e| anyhow::anyhow!("获取使用情况失败: {}", e))?;
+    let usage_data = fetch_usage(&client, &config).await?;
+
+    // 记录快照供 glm report 统计
+    record_snapshot(&config, &usage_data);
+    check_alert_hooks(&config, &usage_data, display.language).await;
+
+    // 一致性检查已在查询时完成（严格模式下不一致会直接报错），此处仅用于输出
+    let warnings = usage_data.consistency_warnings(Utc::now());
 
     // 格式化输出
-    let output = render_table(&usage_data);
+    let output = render(&usage_data, &warnings, format, &display);
     println!("{}", output);
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+/// 把全局命令行选项覆盖到配置
+fn apply_overrides(config: &mut Config, args: &Args) {
+    config.strict_schema |= args.strict_schema;
+    config.strict |= args.strict;
+    if let Some(region) = args.region {
+        config.set_region(region);
+    }
+}
+
+/// 运行或管理守护进程
+async fn daemon_command(
+    config: &CliConfig,
+    action: Option<DaemonAction>,
+    interval: Option<u64>,
+    format: OutputFormat,
+) -> Result<ExitCode> {
+    let socket = config.daemon.socket_path()?;
+    match action {
+        None => {
+            #[cfg(unix)]
+            daemon::serve(config.clone(), interval.unwrap_or(config.daemon.interval)).await?;
+            #[cfg(not(unix))]
+            {
+                let _ = interval;
+                return Err(CliError::ConfigError("守护进程仅支持 Unix 系统".to_string()).into());
+            }
+        }
+        Some(DaemonAction::Status) => {
+            let status: DaemonStatus = daemon::request(&socket, &daemon::Request::Status)
+                .await
+                .with_context(|| format!("守护进程未运行（{}）", socket.display()))?
+                .into_result()?;
+            if format == OutputFormat::Json {
+                println!("{}", serde_json::to_string_pretty(&status)?);
+            } else {
+                let time = |t: DateTime<Utc>| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string();
+                println!("进程 ID：{}", status.pid);
+                println!("套接字：{}", socket.display());
+                println!("接口地址：{}", status.endpoint);
+                println!("启动时间：{}", time(status.started_at));
+                println!("刷新间隔：{} 秒", status.interval);
+                println!("最近刷新：{}", status.updated_at.map(time).unwrap_or_else(|| "-".to_string()));
+                println!("内存快照：{}", status.snapshots);
+                if let Some(error) = status.last_error {
+                    println!("最近错误：{}", error);
+                }
+            }
+        }
+        Some(DaemonAction::Stop) => {
+            daemon::request(&socket, &daemon::Request::Stop)
+                .await
+                .with_context(|| format!("守护进程未运行（{}）", socket.display()))?
+                .into_result::<serde_json::Value>()?;
+            println!("守护进程已停止");
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// 解析 `--tz` 参数
+fn parse_zone(s: &str) -> std::result::Result<DisplayZone, String> {
+    s.parse()
+}
+
+/// 解析 `KEY=VALUE` 形式的参数
+#[cfg(feature = "otel")]
+fn parse_key_value(s: &str) -> std::result::Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.trim().to_string())),
+        _ => Err(format!("格式应为 KEY=VALUE: {}", s)),
+    }
+}
+
+/// 按 `--utc` / `--tz` 覆盖配置中的显示时区
+fn apply_display_zone(display: &mut DisplayOptions, args: &Args) {
+    if args.utc {
+        display.timezone = DisplayZone::Utc;
+    } else if let Some(zone) = &args.tz {
+        display.timezone = zone.clone();
+    }
+}
+
+/// 获取使用情况，失败时执行 `hooks.on_error` 钩子
+async fn fetch_usage(client: &GlmClient, config: &CliConfig) -> Result<ApiData> {
+    match client.fetch_usage().await.context("获取使用情况失败") {
+        Ok(data) => Ok(data),
+        Err(e) => {
+            hooks::notify(&config.hooks, &HookEvent::from_error(&e)).await;
+            Err(e)
+        }
+    }
+}
+
+/// 记录使用情况快照（失败时仅输出警告）
+fn record_snapshot(config: &CliConfig, data: &ApiData) {
+    if !config.history {
+        return;
+    }
+    if let Err(e) = history::history_file_path()
+        .and_then(|path| history::record(&path, data, Utc::now()))
+    {
+        tracing::warn!("记录使用情况快照失败: {:#}", e);
+    }
+}
+
+/// TUI 标签页名称：配置的 `profile`，未设置时为区域
+fn tab_name(config: &CliConfig) -> String {
+    config
+        .profile
+        .clone()
+        .unwrap_or_else(|| config.region.as_str().to_string())
+}
+
+/// 配置了 `on_threshold` / `on_reset` 钩子时按告警规则检查使用情况（失败时仅输出警告）
+///
+/// 与 `glm alert` 共用规则与去重状态：新触发的告警同样推送到配置的目标，
+/// 之后的 `glm alert` / `glm watch` 不会重复触发。
+async fn check_alert_hooks(config: &CliConfig, data: &ApiData, language: Language) {
+    if !config.hooks.watches_alerts() {
+        return;
+    }
+    let result = async {
+        config.alerts.validate().context("告警配置验证失败")?;
+        alert::run(config, data, language, false).await
+    }
+    .await;
+    if let Err(e) = result {
+        tracing::warn!("检查告警失败: {:#}", e);
+    }
+}
+
+/// 持续监控：按间隔查询使用情况、记录快照并检查告警，Ctrl-C 退出
+///
+/// 单次查询失败只输出警告，不会中断监控。
+async fn watch(
+    client: &GlmClient,
+    config: &CliConfig,
+    display: &DisplayOptions,
+    interval: u64,
+    no_alerts: bool,
+) -> Result<()> {
+    loop {
+        let now = chrono::Local::now().format("%H:%M:%S");
+        match fetch_usage(client, config).await {
+            Ok(data) => {
+                record_snapshot(config, &data);
+                println!("[{}] {}", now, render_compact(&data, display));
+                if !no_alerts {
+                    match alert::run(config, &data, display.language, false).await {
+                        Ok(alerts) => {
+                            for alert in alerts {
+                                println!("[{}] ⚠ {}", now, alert.message);
+                            }
+                        }
+                        Err(e) => tracing::warn!("检查告警失败: {:#}", e),
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("{:#}", e),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_secs(interval)) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// 导出指标：省略 `interval` 时导出一次，否则按间隔持续导出，Ctrl-C 退出
+///
+/// 查询失败时仍导出请求统计，便于在监控中发现失败；持续导出时单次查询或导出失败
+/// 只输出警告，不会中断。
+async fn export_metrics(
+    client: &GlmClient,
+    config: &CliConfig,
+    exporter: &Exporter,
+    interval: Option<u64>,
+) -> Result<()> {
+    let language = DisplayOptions::from_config(config).language;
+    let mut stats = FetchStats::new(Utc::now());
+    loop {
+        let started = std::time::Instant::now();
+        let result = fetch_usage(client, config).await;
+        stats.record(
+            started.elapsed(),
+            result.as_ref().err().and_then(|e| e.downcast_ref::<CliError>()),
+        );
+        let now = Utc::now();
+        let quota = match &result {
+            Ok(data) => {
+                record_snapshot(config, data);
+                check_alert_hooks(config, data, language).await;
+                QuotaMetrics::collect(data, now, config.region.reset_offset())
+            }
+            Err(_) => Vec::new(),
+        };
+        let pushed = exporter.export(&quota, &stats, now).await;
+
+        let Some(interval) = interval else {
+            let _ = result?;
+            pushed?;
+            println!("已推送 {} 项额度指标到 {}", quota.len(), exporter.target());
+            return Ok(());
+        };
+        for e in [result.err(), pushed.err()].into_iter().flatten() {
+            tracing::warn!("{:#}", e);
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_secs(interval)) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// 计算用量明细的时间范围
+///
+/// 日期按区域时区理解：开始日期从 00:00:00 起，结束日期到 23:59:59 为止，
+/// 结束日期为今天时截止到当前时间。
+fn usage_range(
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    offset: FixedOffset,
+) -> Result<(DateTime<FixedOffset>, DateTime<FixedOffset>)> {
+    let now = Utc::now().with_timezone(&offset);
+    let today = now.date_naive();
+    let to = to.unwrap_or(today);
+    let from = from.unwrap_or_else(|| to - chrono::Days::new(6));
+
+    if from > to {
+        return Err(CliError::ValidationError(format!(
+            "开始日期 ({}) 晚于结束日期 ({})",
+            from, to
+        ))
+        .into());
+    }
+
+    let at = |date: NaiveDate, h, m, s| {
+        date.and_hms_opt(h, m, s)
+            .and_then(|dt| dt.and_local_timezone(offset).single())
+            .expect("固定时区的日期时间总是唯一的")
+    };
+    let end = if to >= today { now } else { at(to, 23, 59, 59) };
+    Ok((at(from, 0, 0, 0), end))
 }
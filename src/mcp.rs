@@ -0,0 +1,351 @@
+//! MCP（Model Context Protocol）服务
+//!
+//! `glm mcp` 在标准输入输出上运行 JSON-RPC 2.0 服务（每行一条消息），
+//! 向编程智能体提供额度查询工具：`get_quota`、`time_until_reset`、`forecast_exhaustion`。
+//! 标准输出只用于协议消息，日志写到标准错误。
+
+use crate::api::{ApiData, GlmClient, LimitItem};
+use crate::cli_config::CliConfig;
+use crate::forecast::forecast;
+use crate::history;
+use crate::output::format_duration;
+use crate::quota::next_reset_at;
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+/// 支持的协议版本（从新到旧）
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// JSON-RPC 错误码
+mod rpc_error {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+}
+
+/// 未指定时查询的额度类型
+const DEFAULT_LIMIT: &str = "TOKENS_LIMIT";
+
+/// 预测耗尽时读取的历史时长
+const FORECAST_HISTORY: Duration = Duration::hours(6);
+
+/// MCP 服务
+pub struct McpServer {
+    client: GlmClient,
+    config: CliConfig,
+}
+
+/// 工具调用失败（作为工具结果返回给智能体，而不是协议错误）
+struct ToolError(String);
+
+impl McpServer {
+    /// 创建 MCP 服务
+    pub fn new(client: GlmClient, config: CliConfig) -> Self {
+        McpServer { client, config }
+    }
+
+    /// 逐行读取请求并写回应答，直到输入结束
+    pub async fn serve<R, W>(&self, reader: R, mut writer: W) -> Result<()>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut lines = reader.lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_message(&line).await {
+                let mut output = serde_json::to_string(&response)?;
+                output.push('\n');
+                writer.write_all(output.as_bytes()).await?;
+                writer.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 处理一条消息，通知类消息返回 `None`
+    pub async fn handle_message(&self, line: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => return Some(error_response(Value::Null, rpc_error::PARSE_ERROR, format!("无法解析 JSON: {}", e))),
+        };
+
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            let id = message.get("id").cloned().unwrap_or(Value::Null);
+            return Some(error_response(id, rpc_error::INVALID_REQUEST, "缺少 method 字段".to_string()));
+        };
+        // 没有 id 的是通知（如 notifications/initialized），不需要应答
+        let id = message.get("id")?.clone();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        tracing::debug!(method, "收到 MCP 请求");
+
+        let result = match method {
+            "initialize" => Ok(self.initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": self.tools() })),
+            "tools/call" => self.call_tool(&params).await,
+            _ => Err((rpc_error::METHOD_NOT_FOUND, format!("不支持的方法: {}", method))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, message),
+        })
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params.get("protocolVersion").and_then(Value::as_str);
+        let version = requested
+            .filter(|v| PROTOCOL_VERSIONS.contains(v))
+            .unwrap_or(PROTOCOL_VERSIONS[0]);
+        json!({
+            "protocolVersion": version,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "glm", "version": env!("CARGO_PKG_VERSION") },
+        })
+    }
+
+    fn tools(&self) -> Value {
+        let lang = self.config.display_language();
+        let limit_param = json!({
+            "type": "object",
+            "properties": {
+                "limit": {
+                    "type": "string",
+                    "description": lang.pick(
+                        "额度类型：TOKENS_LIMIT（5 小时 Token 额度，默认）或 TIME_LIMIT（每月 MCP 调用次数）",
+                        "Limit type: TOKENS_LIMIT (5-hour token quota, default) or TIME_LIMIT (monthly MCP calls)"
+                    ),
+                }
+            },
+        });
+        json!([
+            {
+                "name": "get_quota",
+                "description": lang.pick(
+                    "查询 GLM 编程套餐当前的全部额度：总额度、已使用、剩余、使用百分比与重置时间",
+                    "Get all current GLM coding plan quotas: total, used, remaining, percentage and reset time"
+                ),
+                "inputSchema": { "type": "object", "properties": {} },
+            },
+            {
+                "name": "time_until_reset",
+                "description": lang.pick(
+                    "查询额度窗口距离下次重置还有多久",
+                    "Get how long until the quota window resets"
+                ),
+                "inputSchema": limit_param,
+            },
+            {
+                "name": "forecast_exhaustion",
+                "description": lang.pick(
+                    "按最近的消耗速度预测额度何时耗尽，以及是否会早于窗口重置",
+                    "Forecast when the quota runs out at the recent burn rate, and whether that is before the window resets"
+                ),
+                "inputSchema": limit_param,
+            },
+        ])
+    }
+
+    async fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((rpc_error::INVALID_PARAMS, "缺少工具名称 name".to_string()))?;
+        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+        let limit = arguments
+            .get("limit")
+            .and_then(Value::as_str)
+            .unwrap_or(DEFAULT_LIMIT)
+            .to_string();
+
+        let outcome = match name {
+            "get_quota" => self.get_quota().await,
+            "time_until_reset" => self.time_until_reset(&limit).await,
+            "forecast_exhaustion" => self.forecast_exhaustion(&limit).await,
+            _ => return Err((rpc_error::INVALID_PARAMS, format!("未知的工具: {}", name))),
+        };
+
+        Ok(match outcome {
+            Ok(value) => json!({
+                "content": [{
+                    "type": "text",
+                    "text": serde_json::to_string_pretty(&value).unwrap_or_default(),
+                }],
+                "structuredContent": value,
+                "isError": false,
+            }),
+            Err(ToolError(message)) => json!({
+                "content": [{ "type": "text", "text": message }],
+                "isError": true,
+            }),
+        })
+    }
+
+    /// 获取使用情况并记录快照
+    async fn fetch(&self) -> Result<ApiData, ToolError> {
+        let data = self
+            .client
+            .fetch_usage()
+            .await
+            .map_err(|e| ToolError(format!("获取使用情况失败: {:#}", e)))?;
+        if self.config.history {
+            if let Err(e) = history::history_file_path().and_then(|path| history::record(&path, &data, Utc::now())) {
+                tracing::warn!("记录使用情况快照失败: {:#}", e);
+            }
+        }
+        Ok(data)
+    }
+
+    fn find_limit<'a>(&self, data: &'a ApiData, limit: &str) -> Result<&'a LimitItem, ToolError> {
+        data.limits
+            .iter()
+            .find(|item| item.limit_type.eq_ignore_ascii_case(limit))
+            .ok_or_else(|| {
+                let available: Vec<&str> = data.limits.iter().map(|item| item.limit_type.as_str()).collect();
+                ToolError(format!("没有额度类型 {}（可用: {}）", limit, available.join(", ")))
+            })
+    }
+
+    async fn get_quota(&self) -> Result<Value, ToolError> {
+        let data = self.fetch().await?;
+        let now = Utc::now();
+        let offset = self.config.region.reset_offset();
+        let limits: Vec<Value> = data
+            .limits
+            .iter()
+            .map(|item| {
+                json!({
+                    "type": item.limit_type,
+                    "total": item.usage,
+                    "used": item.current_value,
+                    "remaining": item.remaining,
+                    "percentage": item.percentage,
+                    "reset_at": next_reset_at(item, now, offset),
+                })
+            })
+            .collect();
+        Ok(json!({ "limits": limits, "fetched_at": now }))
+    }
+
+    async fn time_until_reset(&self, limit: &str) -> Result<Value, ToolError> {
+        let data = self.fetch().await?;
+        let item = self.find_limit(&data, limit)?;
+        let now = Utc::now();
+        let reset_at = next_reset_at(item, now, self.config.region.reset_offset())
+            .ok_or_else(|| ToolError(format!("{} 没有重置时间", item.limit_type)))?;
+        let until = (reset_at - now).to_std().unwrap_or_default();
+        Ok(json!({
+            "limit_type": item.limit_type,
+            "reset_at": reset_at,
+            "seconds_until_reset": until.as_secs(),
+            "human": format_duration(until),
+            "percentage": item.percentage,
+            "remaining": item.remaining,
+        }))
+    }
+
+    async fn forecast_exhaustion(&self, limit: &str) -> Result<Value, ToolError> {
+        let now = Utc::now();
+        // 先读历史再查询，避免把本次快照当作基准
+        let snapshots = history::history_file_path()
+            .and_then(|path| history::load(&path, now - FORECAST_HISTORY))
+            .unwrap_or_default();
+        let data = self.fetch().await?;
+        let item = self.find_limit(&data, limit)?;
+        serde_json::to_value(forecast(item, &snapshots, now, self.config.region.reset_offset())).map_err(|e| ToolError(e.to_string()))
+    }
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Language};
+
+    fn server() -> McpServer {
+        let config = CliConfig {
+            history: false,
+            ..CliConfig::from(Config {
+                api_key: "test_api_key_12345".to_string(),
+                api_url: "http://127.0.0.1:9/api".to_string(),
+                language: Some(Language::En),
+                ..Config::default()
+            })
+        };
+        McpServer::new(GlmClient::new(config.base.clone()).unwrap(), config)
+    }
+
+    #[tokio::test]
+    async fn test_initialize_and_list_tools() {
+        let server = server();
+        let response = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(response["result"]["serverInfo"]["name"], "glm");
+
+        assert!(server
+            .handle_message(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+            .await
+            .is_none());
+
+        let response = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":"a","method":"tools/list"}"#)
+            .await
+            .unwrap();
+        let names: Vec<&str> = response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["get_quota", "time_until_reset", "forecast_exhaustion"]);
+    }
+
+    #[tokio::test]
+    async fn test_protocol_errors() {
+        let server = server();
+        let response = server.handle_message("not json").await.unwrap();
+        assert_eq!(response["error"]["code"], rpc_error::PARSE_ERROR);
+
+        let response = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":2,"method":"resources/list"}"#)
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], rpc_error::METHOD_NOT_FOUND);
+
+        let response = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"launch"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], rpc_error::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_tool_failure_is_reported_as_result() {
+        let response = server()
+            .handle_message(r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"get_quota"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(response["result"]["isError"], true);
+        assert!(response["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("获取使用情况失败"));
+    }
+}
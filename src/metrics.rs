@@ -0,0 +1,358 @@
+//! 指标模型
+//!
+//! 把额度项（[`LimitItem`]）转换为与导出协议无关的指标：每个额度项一组仪表值
+//! （已使用量、总额度、剩余量、使用百分比、距重置秒数），以及导出进程内累计的
+//! 请求次数、失败次数与请求耗时分布。数据来源（区域与配置名称）由 [`Resource`]
+//! 描述。各导出方式（OTLP、InfluxDB 行协议、StatsD）只负责把这些指标编码为自己的协议。
+
+use crate::api::{ApiData, LimitItem};
+use crate::config::Config;
+use crate::error::CliError;
+use crate::quota::next_reset_at;
+use chrono::{DateTime, FixedOffset, Utc};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// 请求耗时直方图的桶边界（秒）
+pub const DURATION_BOUNDS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// 指标值
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    /// 整数
+    Int(i64),
+    /// 浮点数
+    Float(f64),
+}
+
+/// 指标的数据来源，各导出方式据此附加标签或资源属性
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resource {
+    /// 服务区域（如 `cn`）
+    pub region: String,
+
+    /// 配置名称（未设置时为 None）
+    pub profile: Option<String>,
+}
+
+impl Resource {
+    /// 由配置中的区域与配置名称生成
+    pub fn from_config(config: &Config) -> Self {
+        Resource {
+            region: config.region.as_str().to_string(),
+            profile: config.profile.clone(),
+        }
+    }
+
+    /// 标签键值对：`region` 在前，设置了配置名称时附加 `profile`
+    pub fn tags(&self) -> Vec<(&'static str, &str)> {
+        let mut tags = vec![("region", self.region.as_str())];
+        if let Some(profile) = &self.profile {
+            tags.push(("profile", profile.as_str()));
+        }
+        tags
+    }
+}
+
+/// 额度项的一个仪表值
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gauge {
+    /// 字段名（如 `used`），各导出方式在此基础上加前缀
+    pub name: &'static str,
+
+    /// 单位（UCUM 记法，如 `1`、`%`、`s`）
+    pub unit: &'static str,
+
+    /// 说明
+    pub description: &'static str,
+
+    /// 当前值
+    pub value: Value,
+}
+
+/// 单个额度项的指标
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotaMetrics {
+    /// 额度类型（如 `TOKENS_LIMIT`）
+    pub limit_type: String,
+
+    /// 总额度
+    pub total: u64,
+
+    /// 已使用量
+    pub used: u64,
+
+    /// 剩余量
+    pub remaining: u64,
+
+    /// 使用百分比（0-100）
+    pub percentage: f64,
+
+    /// 距下次重置的秒数（无法确定时为 None）
+    pub reset_seconds: Option<i64>,
+}
+
+impl QuotaMetrics {
+    /// 由额度项生成指标
+    ///
+    /// `offset` 为按月重置额度所依据的区域时区，见 [`next_reset_at`]。
+    pub fn from_limit(limit: &LimitItem, now: DateTime<Utc>, offset: FixedOffset) -> Self {
+        QuotaMetrics {
+            limit_type: limit.limit_type.clone(),
+            total: limit.usage,
+            used: limit.current_value,
+            remaining: limit.remaining,
+            percentage: limit.percentage,
+            reset_seconds: next_reset_at(limit, now, offset).map(|at| (at - now).num_seconds().max(0)),
+        }
+    }
+
+    /// 全部额度项的指标
+    pub fn collect(data: &ApiData, now: DateTime<Utc>, offset: FixedOffset) -> Vec<Self> {
+        data.limits
+            .iter()
+            .map(|limit| QuotaMetrics::from_limit(limit, now, offset))
+            .collect()
+    }
+
+    /// 仪表值（缺少重置时间时不含 `reset_seconds`）
+    pub fn gauges(&self) -> Vec<Gauge> {
+        let int = |value: u64| Value::Int(i64::try_from(value).unwrap_or(i64::MAX));
+        let mut gauges = vec![
+            Gauge {
+                name: "used",
+                unit: "1",
+                description: "当前窗口已使用量",
+                value: int(self.used),
+            },
+            Gauge {
+                name: "total",
+                unit: "1",
+                description: "当前窗口总额度",
+                value: int(self.total),
+            },
+            Gauge {
+                name: "remaining",
+                unit: "1",
+                description: "当前窗口剩余量",
+                value: int(self.remaining),
+            },
+            Gauge {
+                name: "pct",
+                unit: "%",
+                description: "使用百分比",
+                value: Value::Float(self.percentage),
+            },
+        ];
+        if let Some(seconds) = self.reset_seconds {
+            gauges.push(Gauge {
+                name: "reset_seconds",
+                unit: "s",
+                description: "距下次重置的秒数",
+                value: Value::Int(seconds),
+            });
+        }
+        gauges
+    }
+}
+
+/// 累计直方图
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// 桶上边界（含），最后一个桶为 +Inf
+    pub bounds: &'static [f64],
+
+    /// 各桶计数（比 `bounds` 多一个）
+    pub counts: Vec<u64>,
+
+    /// 样本数
+    pub count: u64,
+
+    /// 样本总和
+    pub sum: f64,
+
+    /// 最小值（没有样本时为 None）
+    pub min: Option<f64>,
+
+    /// 最大值（没有样本时为 None）
+    pub max: Option<f64>,
+}
+
+impl Histogram {
+    /// 创建空直方图
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Histogram {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            count: 0,
+            sum: 0.0,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// 记录一个样本
+    pub fn record(&mut self, value: f64) {
+        let bucket = self.bounds.iter().position(|&bound| value <= bound).unwrap_or(self.bounds.len());
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.sum += value;
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+    }
+}
+
+/// 导出进程内累计的请求统计
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchStats {
+    /// 开始统计的时间
+    pub started_at: DateTime<Utc>,
+
+    /// 请求次数
+    pub requests: u64,
+
+    /// 按错误类型（[`CliError::kind`]）统计的失败次数
+    pub errors: BTreeMap<&'static str, u64>,
+
+    /// 请求耗时（秒）
+    pub duration: Histogram,
+
+    /// 最近一次请求的耗时（尚无请求时为 None）
+    pub last_duration: Option<Duration>,
+
+    /// 最近一次请求失败的错误类型（成功时为 None）
+    pub last_error: Option<&'static str>,
+}
+
+impl FetchStats {
+    /// 创建空统计
+    pub fn new(started_at: DateTime<Utc>) -> Self {
+        FetchStats {
+            started_at,
+            requests: 0,
+            errors: BTreeMap::new(),
+            duration: Histogram::new(&DURATION_BOUNDS),
+            last_duration: None,
+            last_error: None,
+        }
+    }
+
+    /// 记录一次请求
+    pub fn record(&mut self, elapsed: Duration, error: Option<&CliError>) {
+        self.requests += 1;
+        self.duration.record(elapsed.as_secs_f64());
+        self.last_duration = Some(elapsed);
+        self.last_error = error.map(CliError::kind);
+        if let Some(kind) = self.last_error {
+            *self.errors.entry(kind).or_default() += 1;
+        }
+    }
+
+    /// 失败总次数
+    pub fn error_count(&self) -> u64 {
+        self.errors.values().sum()
+    }
+}
+
+/// 本机主机名（用作资源属性 `host.name`）
+pub fn host_name() -> Option<String> {
+    #[cfg(unix)]
+    {
+        let mut buf = [0u8; 256];
+        // SAFETY: 缓冲区长度与传入的长度一致，gethostname 不会越界写入
+        if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } == 0 {
+            let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+            let name = String::from_utf8_lossy(&buf[..end]).into_owned();
+            if !name.is_empty() {
+                return Some(name);
+            }
+        }
+    }
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn data() -> ApiData {
+        serde_json::from_value(serde_json::json!({
+            "limits": [
+                {"type": "TIME_LIMIT", "usage": 1000, "currentValue": 164},
+                {"type": "TOKENS_LIMIT", "usage": 200000, "currentValue": 50000, "nextResetTime": 1776585600000i64}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_collect_quota_metrics() {
+        let now = Utc.with_ymd_and_hms(2026, 4, 19, 6, 0, 0).unwrap();
+        let metrics = QuotaMetrics::collect(&data(), now, FixedOffset::east_opt(8 * 3600).unwrap());
+        assert_eq!(metrics.len(), 2);
+
+        let mcp = &metrics[0];
+        assert_eq!((mcp.total, mcp.used, mcp.remaining), (1000, 164, 836));
+        // 中国大陆站按 UTC+8 的下月 1 号 00:00 重置
+        let reset = Utc.with_ymd_and_hms(2026, 4, 30, 16, 0, 0).unwrap();
+        assert_eq!(mcp.reset_seconds, Some((reset - now).num_seconds()));
+
+        let tokens = &metrics[1];
+        assert_eq!(tokens.percentage, 25.0);
+        assert_eq!(tokens.reset_seconds, Some(2 * 3600));
+        let gauges = tokens.gauges();
+        let names: Vec<_> = gauges.iter().map(|gauge| gauge.name).collect();
+        assert_eq!(names, ["used", "total", "remaining", "pct", "reset_seconds"]);
+        assert_eq!(gauges[0].value, Value::Int(50000));
+        assert_eq!(gauges[3].value, Value::Float(25.0));
+    }
+
+    #[test]
+    fn test_gauges_without_reset_time() {
+        let data: ApiData = serde_json::from_value(serde_json::json!({
+            "limits": [{"type": "SEARCH_LIMIT", "usage": 10, "currentValue": 1}]
+        }))
+        .unwrap();
+        let metrics = QuotaMetrics::collect(&data, Utc::now(), FixedOffset::east_opt(0).unwrap());
+        assert_eq!(metrics[0].reset_seconds, None);
+        assert_eq!(metrics[0].gauges().len(), 4);
+    }
+
+    #[test]
+    fn test_resource_tags() {
+        let mut config = Config::default();
+        assert_eq!(Resource::from_config(&config).tags(), [("region", "cn")]);
+
+        config.region = crate::config::Region::Intl;
+        config.profile = Some("team".to_string());
+        assert_eq!(Resource::from_config(&config).tags(), [("region", "intl"), ("profile", "team")]);
+    }
+
+    #[test]
+    fn test_fetch_stats() {
+        let mut stats = FetchStats::new(Utc::now());
+        stats.record(Duration::from_millis(80), None);
+        stats.record(Duration::from_millis(300), Some(&CliError::NetworkError("连接失败".to_string())));
+        stats.record(Duration::from_secs(60), Some(&CliError::Timeout("超时".to_string())));
+
+        assert_eq!(stats.requests, 3);
+        assert_eq!(stats.error_count(), 2);
+        assert_eq!(stats.errors.get("network"), Some(&1));
+        assert_eq!(stats.errors.get("timeout"), Some(&1));
+        assert_eq!(stats.last_duration, Some(Duration::from_secs(60)));
+        assert_eq!(stats.last_error, Some("timeout"));
+
+        let histogram = &stats.duration;
+        assert_eq!(histogram.count, 3);
+        assert_eq!(histogram.counts.len(), DURATION_BOUNDS.len() + 1);
+        assert_eq!(histogram.counts[1], 1);
+        assert_eq!(histogram.counts[3], 1);
+        assert_eq!(histogram.counts[DURATION_BOUNDS.len()], 1);
+        assert_eq!(histogram.min, Some(0.08));
+        assert_eq!(histogram.max, Some(60.0));
+    }
+}
@@ -0,0 +1,356 @@
+//! OpenTelemetry 指标导出（需启用 `otel` feature）
+//!
+//! 以 OTLP/HTTP 的 JSON 编码把 [`crate::metrics`] 中的指标推送到 Collector
+//! （`POST {endpoint}/v1/metrics`），复用已有的 HTTP 客户端，不引入 gRPC / protobuf 依赖。
+//!
+//! 导出的指标：
+//!
+//! - `glm.quota.used` / `total` / `remaining` / `pct` / `reset_seconds`：仪表值，属性 `type` 为额度类型
+//! - `glm.fetch.requests`：累计请求次数
+//! - `glm.fetch.errors`：累计失败次数，属性 `error.type` 为错误类型
+//! - `glm.fetch.duration`：请求耗时直方图（秒）
+//!
+//! 资源属性包括 `service.name`、`service.version`、`host.name`、`glm.region`
+//! 与 `glm.profile`（设置了配置名称时），以及调用方追加的属性。
+
+use crate::api::http_client_builder;
+use crate::config::Config;
+use crate::error::CliError;
+use crate::metrics::{FetchStats, Histogram, QuotaMetrics, Resource, Value as MetricValue};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+/// 指标接收路径
+const METRICS_PATH: &str = "/v1/metrics";
+
+/// 累计（cumulative）聚合
+const AGGREGATION_CUMULATIVE: u8 = 2;
+
+/// OTLP 指标导出器
+pub struct OtlpExporter {
+    client: reqwest::Client,
+    url: String,
+    headers: Vec<(String, String)>,
+    resource: Vec<(String, String)>,
+}
+
+impl OtlpExporter {
+    /// 创建导出器
+    ///
+    /// `endpoint` 为 Collector 的 OTLP/HTTP 地址（如 `http://localhost:4318`），未以
+    /// `/v1/metrics` 结尾时自动补全；`headers` 随每次推送发送（如鉴权头），
+    /// `attributes` 追加到资源属性中，同名时覆盖默认值。
+    pub fn new(
+        config: &Config,
+        endpoint: &str,
+        headers: Vec<(String, String)>,
+        attributes: Vec<(String, String)>,
+    ) -> Result<Self> {
+        let client = http_client_builder(config)?
+            .build()
+            .map_err(|e| CliError::NetworkError(format!("创建 HTTP 客户端失败: {}", e)))?;
+        Ok(OtlpExporter {
+            client,
+            url: metrics_url(endpoint)?,
+            headers,
+            resource: resource_attributes(config, attributes),
+        })
+    }
+
+    /// 指标推送地址
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// 生成 `ExportMetricsServiceRequest` 请求体
+    pub fn payload(&self, quota: &[QuotaMetrics], stats: &FetchStats, now: DateTime<Utc>) -> Value {
+        let time = nanos(now);
+        let start = nanos(stats.started_at);
+
+        // 各额度项的仪表值按字段合并为一个指标，每个额度项一个数据点
+        let mut metrics: Vec<Value> = Vec::new();
+        for item in quota {
+            for gauge in item.gauges() {
+                let mut point = number_point(gauge.value, &time);
+                point["attributes"] = attributes([("type", item.limit_type.as_str())]);
+                let name = format!("glm.quota.{}", gauge.name);
+                match metrics.iter_mut().find(|metric| metric["name"] == name.as_str()) {
+                    Some(metric) => {
+                        if let Some(points) = metric["gauge"]["dataPoints"].as_array_mut() {
+                            points.push(point);
+                        }
+                    }
+                    None => metrics.push(json!({
+                        "name": name,
+                        "unit": gauge.unit,
+                        "description": gauge.description,
+                        "gauge": { "dataPoints": [point] },
+                    })),
+                }
+            }
+        }
+
+        metrics.push(json!({
+            "name": "glm.fetch.requests",
+            "unit": "{request}",
+            "description": "查询使用情况的请求次数",
+            "sum": {
+                "aggregationTemporality": AGGREGATION_CUMULATIVE,
+                "isMonotonic": true,
+                "dataPoints": [{
+                    "startTimeUnixNano": start,
+                    "timeUnixNano": time,
+                    "asInt": stats.requests.to_string(),
+                }],
+            },
+        }));
+        let error_points: Vec<Value> = stats
+            .errors
+            .iter()
+            .map(|(kind, count)| {
+                json!({
+                    "attributes": attributes([("error.type", *kind)]),
+                    "startTimeUnixNano": start,
+                    "timeUnixNano": time,
+                    "asInt": count.to_string(),
+                })
+            })
+            .collect();
+        metrics.push(json!({
+            "name": "glm.fetch.errors",
+            "unit": "{request}",
+            "description": "查询使用情况失败的次数",
+            "sum": {
+                "aggregationTemporality": AGGREGATION_CUMULATIVE,
+                "isMonotonic": true,
+                "dataPoints": error_points,
+            },
+        }));
+        metrics.push(json!({
+            "name": "glm.fetch.duration",
+            "unit": "s",
+            "description": "查询使用情况的请求耗时",
+            "histogram": {
+                "aggregationTemporality": AGGREGATION_CUMULATIVE,
+                "dataPoints": [histogram_point(&stats.duration, &start, &time)],
+            },
+        }));
+
+        json!({
+            "resourceMetrics": [{
+                "resource": {
+                    "attributes": attributes(self.resource.iter().map(|(k, v)| (k.as_str(), v.as_str()))),
+                },
+                "scopeMetrics": [{
+                    "scope": { "name": "glm", "version": env!("CARGO_PKG_VERSION") },
+                    "metrics": metrics,
+                }],
+            }],
+        })
+    }
+
+    /// 推送一次指标
+    pub async fn export(&self, quota: &[QuotaMetrics], stats: &FetchStats, now: DateTime<Utc>) -> Result<()> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("User-Agent", format!("glm-cli/{}", env!("CARGO_PKG_VERSION")))
+            .json(&self.payload(quota, stats, now));
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| CliError::NetworkError(format!("推送 OTLP 指标失败: {}\n\n地址: {}", e, self.url)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(CliError::NetworkError(format!(
+                "OTLP 接收端返回 HTTP {}\n\n地址: {}\n响应: {}",
+                status.as_u16(),
+                self.url,
+                body.chars().take(200).collect::<String>()
+            ))
+            .into());
+        }
+        tracing::info!(url = %self.url, limits = quota.len(), "已推送 OTLP 指标");
+        Ok(())
+    }
+}
+
+/// 由 Collector 地址得到指标推送地址
+pub fn metrics_url(endpoint: &str) -> Result<String> {
+    let endpoint = endpoint.trim().trim_end_matches('/');
+    match reqwest::Url::parse(endpoint) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+        _ => {
+            return Err(CliError::ConfigError(format!("OTLP 地址无效: {}", endpoint))
+                .suggest(["请使用 http:// 或 https:// 开头的地址，如 http://localhost:4318"])
+                .into())
+        }
+    }
+    if endpoint.ends_with(METRICS_PATH) {
+        Ok(endpoint.to_string())
+    } else {
+        Ok(format!("{}{}", endpoint, METRICS_PATH))
+    }
+}
+
+/// 资源属性：默认属性在前，`extra` 中的同名属性覆盖默认值
+pub fn resource_attributes(config: &Config, extra: Vec<(String, String)>) -> Vec<(String, String)> {
+    let mut resource = vec![
+        ("service.name".to_string(), "glm".to_string()),
+        ("service.version".to_string(), env!("CARGO_PKG_VERSION").to_string()),
+    ];
+    if let Some(host) = crate::metrics::host_name() {
+        resource.push(("host.name".to_string(), host));
+    }
+    for (key, value) in Resource::from_config(config).tags() {
+        resource.push((format!("glm.{}", key), value.to_string()));
+    }
+
+    for (key, value) in extra {
+        match resource.iter_mut().find(|(k, _)| *k == key) {
+            Some(existing) => existing.1 = value,
+            None => resource.push((key, value)),
+        }
+    }
+    resource
+}
+
+/// 纳秒时间戳（OTLP JSON 中 64 位整数以字符串表示）
+fn nanos(at: DateTime<Utc>) -> String {
+    at.timestamp_nanos_opt().unwrap_or_default().to_string()
+}
+
+/// 字符串属性列表
+fn attributes<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> Value {
+    pairs
+        .into_iter()
+        .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+        .collect()
+}
+
+/// 仪表值数据点
+fn number_point(value: MetricValue, time: &str) -> Value {
+    match value {
+        MetricValue::Int(n) => json!({ "timeUnixNano": time, "asInt": n.to_string() }),
+        MetricValue::Float(x) => json!({ "timeUnixNano": time, "asDouble": x }),
+    }
+}
+
+/// 直方图数据点
+fn histogram_point(histogram: &Histogram, start: &str, time: &str) -> Value {
+    let mut point = json!({
+        "startTimeUnixNano": start,
+        "timeUnixNano": time,
+        "count": histogram.count.to_string(),
+        "sum": histogram.sum,
+        "bucketCounts": histogram.counts.iter().map(u64::to_string).collect::<Vec<_>>(),
+        "explicitBounds": histogram.bounds,
+    });
+    if let (Some(min), Some(max)) = (histogram.min, histogram.max) {
+        point["min"] = json!(min);
+        point["max"] = json!(max);
+    }
+    point
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, TimeZone};
+    use std::time::Duration;
+
+    fn exporter() -> OtlpExporter {
+        let config = Config {
+            api_key: "test_api_key_12345".to_string(),
+            ..Config::default()
+        };
+        OtlpExporter::new(
+            &config,
+            "http://localhost:4318/",
+            Vec::new(),
+            vec![("service.name".to_string(), "quota".to_string()), ("team".to_string(), "infra".to_string())],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_metrics_url() {
+        assert_eq!(metrics_url("http://localhost:4318").unwrap(), "http://localhost:4318/v1/metrics");
+        assert_eq!(metrics_url("https://otel.example.com/v1/metrics/").unwrap(), "https://otel.example.com/v1/metrics");
+        assert_eq!(
+            metrics_url("http://gateway:8080/otlp").unwrap(),
+            "http://gateway:8080/otlp/v1/metrics"
+        );
+        assert!(metrics_url("localhost:4318").is_err());
+    }
+
+    #[test]
+    fn test_resource_attributes() {
+        let exporter = exporter();
+        assert_eq!(exporter.resource[0], ("service.name".to_string(), "quota".to_string()));
+        assert!(exporter.resource.contains(&("glm.region".to_string(), "cn".to_string())));
+        assert_eq!(exporter.resource.last().unwrap(), &("team".to_string(), "infra".to_string()));
+        assert!(!exporter.resource.iter().any(|(key, _)| key == "glm.profile"));
+
+        let config = Config {
+            profile: Some("team".to_string()),
+            ..Config::default()
+        };
+        let resource = resource_attributes(&config, Vec::new());
+        assert!(resource.contains(&("glm.profile".to_string(), "team".to_string())));
+    }
+
+    #[test]
+    fn test_payload() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 19, 6, 0, 0).unwrap();
+        let data = serde_json::from_value(serde_json::json!({
+            "limits": [
+                {"type": "TIME_LIMIT", "usage": 1000, "currentValue": 164},
+                {"type": "TOKENS_LIMIT", "usage": 200000, "currentValue": 50000, "nextResetTime": now.timestamp_millis() + 3_600_000}
+            ]
+        }))
+        .unwrap();
+        let quota = QuotaMetrics::collect(&data, now, FixedOffset::east_opt(8 * 3600).unwrap());
+        let mut stats = FetchStats::new(now - chrono::Duration::minutes(5));
+        stats.record(Duration::from_millis(120), None);
+        stats.record(Duration::from_millis(80), Some(&CliError::NetworkError("连接失败".to_string())));
+
+        let payload = exporter().payload(&quota, &stats, now);
+        let scope = &payload["resourceMetrics"][0]["scopeMetrics"][0];
+        assert_eq!(scope["scope"]["name"], "glm");
+        let metric = |name: &str| {
+            scope["metrics"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|m| m["name"] == name)
+                .cloned()
+                .unwrap_or_else(|| panic!("缺少指标 {}", name))
+        };
+
+        let used = metric("glm.quota.used");
+        let points = used["gauge"]["dataPoints"].as_array().unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1]["asInt"], "50000");
+        assert_eq!(points[1]["attributes"][0]["value"]["stringValue"], "TOKENS_LIMIT");
+        assert_eq!(points[1]["timeUnixNano"], "1792389600000000000");
+        assert_eq!(metric("glm.quota.pct")["gauge"]["dataPoints"][1]["asDouble"], 25.0);
+        assert_eq!(metric("glm.quota.reset_seconds")["gauge"]["dataPoints"][1]["asInt"], "3600");
+
+        assert_eq!(metric("glm.fetch.requests")["sum"]["dataPoints"][0]["asInt"], "2");
+        let errors = metric("glm.fetch.errors");
+        assert_eq!(errors["sum"]["isMonotonic"], true);
+        assert_eq!(errors["sum"]["dataPoints"][0]["attributes"][0]["value"]["stringValue"], "network");
+        let duration = &metric("glm.fetch.duration")["histogram"]["dataPoints"][0];
+        assert_eq!(duration["count"], "2");
+        assert_eq!(duration["bucketCounts"][1], "1");
+        assert_eq!(duration["bucketCounts"][2], "1");
+        assert_eq!(duration["max"], 0.12);
+    }
+}
@@ -0,0 +1,101 @@
+//! InfluxDB 行协议输出
+//!
+//! 每个额度项输出一行
+//! `glm_quota,type=TOKENS_LIMIT,region=cn,profile=team used=...i,total=...i,pct=... <纳秒时间戳>`
+//! （未设置配置名称时不含 `profile` 标签），可直接交给 Telegraf 的 `exec` 输入或写入 InfluxDB。
+
+use super::DisplayOptions;
+use crate::api::ApiData;
+use crate::metrics::{QuotaMetrics, Resource, Value};
+use chrono::{DateTime, Utc};
+
+/// 测量名称
+const MEASUREMENT: &str = "glm_quota";
+
+/// 渲染行协议格式的使用情况
+pub fn render_influx(data: &ApiData, opts: &DisplayOptions) -> String {
+    let now = Utc::now();
+    render_lines(&QuotaMetrics::collect(data, now, opts.reset_offset), &opts.resource, now)
+}
+
+/// 把额度指标编码为行协议，时间戳为 `now`
+fn render_lines(quota: &[QuotaMetrics], resource: &Resource, now: DateTime<Utc>) -> String {
+    let timestamp = now.timestamp_nanos_opt().unwrap_or_default();
+    let resource_tags: String = resource
+        .tags()
+        .iter()
+        .map(|(key, value)| format!(",{}={}", key, escape_tag(value)))
+        .collect();
+    quota
+        .iter()
+        .map(|item| {
+            let fields = item
+                .gauges()
+                .iter()
+                .map(|gauge| match gauge.value {
+                    Value::Int(n) => format!("{}={}i", gauge.name, n),
+                    Value::Float(x) => format!("{}={}", gauge.name, x),
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            format!(
+                "{},type={}{} {} {}",
+                MEASUREMENT,
+                escape_tag(&item.limit_type),
+                resource_tags,
+                fields,
+                timestamp
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 转义标签值中的逗号、等号与空格
+fn escape_tag(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, ',' | '=' | ' ') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, TimeZone};
+
+    #[test]
+    fn test_render_lines() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 19, 6, 0, 0).unwrap();
+        let data: ApiData = serde_json::from_value(serde_json::json!({
+            "limits": [
+                {"type": "TOKENS_LIMIT", "usage": 200000, "currentValue": 50000, "nextResetTime": now.timestamp_millis() + 5_400_000},
+                {"type": "SEARCH LIMIT", "usage": 8, "currentValue": 3}
+            ]
+        }))
+        .unwrap();
+        let quota = QuotaMetrics::collect(&data, now, FixedOffset::east_opt(0).unwrap());
+        let mut resource = Resource {
+            region: "cn".to_string(),
+            profile: None,
+        };
+        let output = render_lines(&quota, &resource, now);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            "glm_quota,type=TOKENS_LIMIT,region=cn used=50000i,total=200000i,remaining=150000i,pct=25,reset_seconds=5400i 1792389600000000000"
+        );
+        assert_eq!(
+            lines[1],
+            "glm_quota,type=SEARCH\\ LIMIT,region=cn used=3i,total=8i,remaining=5i,pct=37.5 1792389600000000000"
+        );
+
+        resource.profile = Some("my team".to_string());
+        let output = render_lines(&quota, &resource, now);
+        assert!(output.starts_with("glm_quota,type=TOKENS_LIMIT,region=cn,profile=my\\ team used=50000i,"));
+    }
+}
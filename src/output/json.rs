@@ -0,0 +1,135 @@
+//! JSON 输出
+//!
+//! 输出机器可读的使用情况文档，额度项字段与上游接口保持一致，
+//! 另附按显示时区换算的重置时间。
+
+use super::DisplayOptions;
+use crate::api::{ApiData, ApiPlan, LimitItem, UsageBreakdown};
+use crate::quota::next_reset_at;
+use chrono::Utc;
+use serde::Serialize;
+
+/// JSON 输出文档
+#[derive(Debug, Serialize)]
+struct JsonOutput<'a> {
+    /// 额度限制列表
+    limits: Vec<JsonLimit<'a>>,
+    /// 数据一致性警告
+    warnings: &'a [String],
+    /// 查询时间（显示时区，RFC 3339）
+    updated_at: String,
+    /// 显示时区
+    timezone: String,
+}
+
+/// 额度项及其重置时间
+#[derive(Debug, Serialize)]
+struct JsonLimit<'a> {
+    /// 上游额度项
+    #[serde(flatten)]
+    item: &'a LimitItem,
+    /// 下次重置时间（显示时区，RFC 3339；按月重置的额度按区域时区推算）
+    #[serde(rename = "resetAt", skip_serializing_if = "Option::is_none")]
+    reset_at: Option<String>,
+    /// 距下次重置的秒数
+    #[serde(rename = "resetsInSeconds", skip_serializing_if = "Option::is_none")]
+    resets_in_seconds: Option<i64>,
+}
+
+/// 渲染 JSON 格式的使用情况
+///
+/// # 参数
+///
+/// * `data`: API 响应数据
+/// * `warnings`: 数据一致性警告
+/// * `opts`: 显示时区设置
+pub fn render_json(data: &ApiData, warnings: &[String], opts: &DisplayOptions) -> String {
+    tracing::debug!(limits = data.limits.len(), "渲染 JSON 输出");
+    let now = Utc::now();
+    let limits = data
+        .limits
+        .iter()
+        .map(|item| {
+            let reset = next_reset_at(item, now, opts.reset_offset);
+            JsonLimit {
+                item,
+                reset_at: reset.map(|reset| opts.timezone.convert(reset).to_rfc3339()),
+                resets_in_seconds: reset.map(|reset| (reset - now).num_seconds().max(0)),
+            }
+        })
+        .collect();
+    let output = JsonOutput {
+        limits,
+        warnings,
+        updated_at: opts.timezone.convert(now).to_rfc3339(),
+        timezone: opts.timezone.label(now),
+    };
+    serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// 渲染 JSON 格式的订阅计划信息
+pub fn render_plan_json(plan: &ApiPlan) -> String {
+    serde_json::to_string_pretty(plan).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// 渲染 JSON 格式的用量明细
+pub fn render_usage_breakdown_json(breakdown: &UsageBreakdown) -> String {
+    serde_json::to_string_pretty(breakdown).unwrap_or_else(|_| "{}".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::LimitItem;
+
+    #[test]
+    fn test_render_json_includes_warnings() {
+        let data = ApiData {
+            limits: vec![LimitItem {
+                limit_type: "TIME_LIMIT".to_string(),
+                unit: 5,
+                number: 1,
+                usage: 1000,
+                current_value: 164,
+                remaining: 836,
+                percentage: 16.0,
+                next_reset_time: None,
+                extra: Default::default(),
+            }],
+        };
+        let warnings = vec!["TIME_LIMIT: 测试警告".to_string()];
+
+        let value: serde_json::Value =
+            serde_json::from_str(&render_json(&data, &warnings, &DisplayOptions::default())).unwrap();
+        assert_eq!(value["limits"][0]["type"], "TIME_LIMIT");
+        assert_eq!(value["limits"][0]["currentValue"], 164);
+        assert_eq!(value["warnings"][0], "TIME_LIMIT: 测试警告");
+        assert!(value["updated_at"].is_string());
+    }
+
+    #[test]
+    fn test_render_json_reset_times() {
+        let reset = Utc::now() + chrono::Duration::hours(2);
+        let data: ApiData = serde_json::from_value(serde_json::json!({"limits": [
+            {"type": "TOKENS_LIMIT", "usage": 100, "currentValue": 66, "nextResetTime": reset.timestamp_millis()},
+            {"type": "TIME_LIMIT", "usage": 1000, "currentValue": 164},
+            {"type": "OTHER", "usage": 10, "currentValue": 1}
+        ]}))
+        .unwrap();
+        let opts = DisplayOptions {
+            timezone: "+08:00".parse().unwrap(),
+            ..DisplayOptions::default()
+        };
+        let value: serde_json::Value = serde_json::from_str(&render_json(&data, &[], &opts)).unwrap();
+
+        assert_eq!(value["timezone"], "UTC+08:00");
+        assert!(value["updated_at"].as_str().unwrap().ends_with("+08:00"));
+        let limit = &value["limits"][0];
+        assert!(limit["resetAt"].as_str().unwrap().ends_with("+08:00"));
+        let secs = limit["resetsInSeconds"].as_i64().unwrap();
+        assert!((7190..=7200).contains(&secs), "{}", secs);
+        // 按月重置：区域时区（UTC+8）下月 1 号 00:00
+        assert!(value["limits"][1]["resetAt"].as_str().unwrap().ends_with("-01T00:00:00+08:00"));
+        assert!(value["limits"][2].get("resetAt").is_none());
+    }
+}
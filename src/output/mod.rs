@@ -2,6 +2,165 @@
 //!
 //! 提供多种输出格式的实现。
 
+pub mod influx;
+pub mod json;
+#[cfg(feature = "cli")]
+pub mod report;
 pub mod table;
+pub mod timezone;
 
-pub use table::render_table;
+pub use influx::render_influx;
+pub use json::{render_json, render_plan_json, render_usage_breakdown_json};
+#[cfg(feature = "cli")]
+pub use report::{render_report_csv, render_report_markdown};
+pub use table::{
+    render_compact, render_plan, render_table, render_usage_breakdown, render_warnings,
+};
+pub use timezone::{format_duration, format_relative, DisplayZone};
+
+use crate::api::{ApiData, ApiPlan, UsageBreakdown};
+use crate::config::{Config, Language, Region};
+use crate::metrics::Resource;
+#[cfg(feature = "cli")]
+use crate::report::UsageReport;
+use chrono::FixedOffset;
+use std::str::FromStr;
+
+/// 显示设置
+#[derive(Debug, Clone)]
+pub struct DisplayOptions {
+    /// 显示语言
+    pub language: Language,
+    /// 额度按月重置所依据的时区
+    pub reset_offset: FixedOffset,
+    /// 显示时间所用的时区
+    pub timezone: DisplayZone,
+    /// 指标的数据来源（行协议输出的标签）
+    pub resource: Resource,
+}
+
+impl DisplayOptions {
+    /// 根据配置中的区域、语言与时区创建显示设置
+    pub fn from_config(config: &Config) -> Self {
+        DisplayOptions {
+            language: config.display_language(),
+            reset_offset: config.region.reset_offset(),
+            timezone: config.display_zone().unwrap_or_default(),
+            resource: Resource::from_config(config),
+        }
+    }
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        DisplayOptions {
+            language: Region::Cn.default_language(),
+            reset_offset: Region::Cn.reset_offset(),
+            timezone: DisplayZone::Local,
+            resource: Resource::from_config(&Config::default()),
+        }
+    }
+}
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// 用户友好的文本表格
+    Table,
+    /// JSON
+    Json,
+    /// InfluxDB 行协议（仅使用情况，其他命令按 JSON 输出）
+    Influx,
+    /// CSV（仅 `glm report`）
+    Csv,
+    /// Markdown 表格（仅 `glm report`）
+    Markdown,
+}
+
+impl OutputFormat {
+    /// 支持的格式名称
+    pub const NAMES: &'static [&'static str] = &["table", "json", "influx", "csv", "md"];
+
+    /// 是否为仅报告支持的格式
+    pub fn is_report_only(self) -> bool {
+        matches!(self, OutputFormat::Csv | OutputFormat::Markdown)
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "influx" => Ok(OutputFormat::Influx),
+            "csv" => Ok(OutputFormat::Csv),
+            "md" | "markdown" => Ok(OutputFormat::Markdown),
+            other => Err(format!(
+                "不支持的输出格式: {}（可选: {}）",
+                other,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// 按指定格式渲染使用情况，并附带数据一致性警告
+///
+/// CSV 与 Markdown 仅用于报告，此处按 JSON 输出；行协议不含一致性警告。
+pub fn render(
+    data: &ApiData,
+    warnings: &[String],
+    format: OutputFormat,
+    opts: &DisplayOptions,
+) -> String {
+    match format {
+        OutputFormat::Table => {
+            let mut output = render_table(data, opts);
+            if !warnings.is_empty() {
+                output.push_str("\n\n");
+                output.push_str(&render_warnings(warnings, opts));
+            }
+            output
+        }
+        OutputFormat::Influx => render_influx(data, opts),
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Markdown => {
+            render_json(data, warnings, opts)
+        }
+    }
+}
+
+/// 按指定格式渲染订阅计划信息
+pub fn render_plan_as(plan: &ApiPlan, format: OutputFormat, opts: &DisplayOptions) -> String {
+    match format {
+        OutputFormat::Table => render_plan(plan, opts),
+        OutputFormat::Json | OutputFormat::Influx | OutputFormat::Csv | OutputFormat::Markdown => {
+            render_plan_json(plan)
+        }
+    }
+}
+
+/// 按指定格式渲染用量明细
+pub fn render_usage_breakdown_as(
+    breakdown: &UsageBreakdown,
+    format: OutputFormat,
+    opts: &DisplayOptions,
+) -> String {
+    match format {
+        OutputFormat::Table => render_usage_breakdown(breakdown, opts),
+        OutputFormat::Json | OutputFormat::Influx | OutputFormat::Csv | OutputFormat::Markdown => {
+            render_usage_breakdown_json(breakdown)
+        }
+    }
+}
+
+/// 按指定格式渲染使用报告（表格格式即 Markdown，仅 `cli` feature）
+#[cfg(feature = "cli")]
+pub fn render_report_as(report: &UsageReport, format: OutputFormat, opts: &DisplayOptions) -> String {
+    match format {
+        OutputFormat::Csv => render_report_csv(report, opts),
+        OutputFormat::Table | OutputFormat::Markdown => render_report_markdown(report, opts),
+        OutputFormat::Json | OutputFormat::Influx => serde_json::to_string_pretty(report).unwrap_or_else(|_| "{}".to_string()),
+    }
+}
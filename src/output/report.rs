@@ -0,0 +1,195 @@
+//! 使用报告输出
+//!
+//! CSV 便于导入电子表格，Markdown 表格便于粘贴到 Wiki。
+
+use super::DisplayOptions;
+use crate::config::Language;
+use crate::report::{ReportPeriod, UsageReport};
+use chrono::{DateTime, Utc};
+
+/// 渲染 CSV 格式的使用报告
+///
+/// 先输出每个 5 小时窗口一行，空一行后输出 `metric,value` 汇总。
+/// 时间按显示时区输出为 RFC 3339。
+pub fn render_report_csv(report: &UsageReport, opts: &DisplayOptions) -> String {
+    let time = |dt: DateTime<Utc>| opts.timezone.convert(dt).to_rfc3339();
+    let mut output = String::from(
+        "window_reset_at,peak_percentage,peak_tokens,quota_tokens,headroom_percentage,hit_limit,samples\n",
+    );
+    for window in &report.windows {
+        output.push_str(&format!(
+            "{},{:.1},{},{},{:.1},{},{}\n",
+            time(window.reset_at),
+            window.peak_percentage,
+            window.peak_tokens,
+            window.quota_tokens,
+            window.headroom(),
+            window.hit_limit(),
+            window.samples
+        ));
+    }
+
+    let optional = |v: Option<String>| v.unwrap_or_default();
+    output.push('\n');
+    output.push_str("metric,value\n");
+    output.push_str(&format!("period_start,{}\n", time(report.start)));
+    output.push_str(&format!("period_end,{}\n", time(report.end)));
+    output.push_str(&format!("snapshots,{}\n", report.snapshots));
+    output.push_str(&format!("windows,{}\n", report.windows.len()));
+    output.push_str(&format!("windows_at_limit,{}\n", report.windows_at_limit));
+    output.push_str(&format!(
+        "peak_percentage,{}\n",
+        optional(report.peak_percentage.map(|p| format!("{:.1}", p)))
+    ));
+    output.push_str(&format!(
+        "average_headroom_percentage,{}\n",
+        optional(report.average_headroom.map(|h| format!("{:.1}", h)))
+    ));
+    output.push_str(&format!("mcp_calls_this_month,{}\n", optional(report.mcp_calls.map(|c| c.to_string()))));
+    output.push_str(&format!("mcp_quota,{}\n", optional(report.mcp_quota.map(|c| c.to_string()))));
+
+    output
+}
+
+/// 渲染 Markdown 格式的使用报告
+pub fn render_report_markdown(report: &UsageReport, opts: &DisplayOptions) -> String {
+    let lang = opts.language;
+    let time = |dt: DateTime<Utc>| opts.timezone.convert(dt).format("%Y-%m-%d %H:%M").to_string();
+    let percent = |v: Option<f64>| v.map(|p| format!("{:.1}%", p)).unwrap_or_else(|| "-".to_string());
+
+    let period = match (lang, report.period) {
+        (Language::Zh, ReportPeriod::Day) => "近 24 小时",
+        (Language::Zh, ReportPeriod::Week) => "近 7 天",
+        (Language::Zh, ReportPeriod::Month) => "近 30 天",
+        (Language::En, ReportPeriod::Day) => "last 24 hours",
+        (Language::En, ReportPeriod::Week) => "last 7 days",
+        (Language::En, ReportPeriod::Month) => "last 30 days",
+    };
+
+    let mut output = String::new();
+    match lang {
+        Language::Zh => {
+            output.push_str(&format!("## GLM 使用报告（{}）\n\n", period));
+            output.push_str(&format!(
+                "统计区间：{} 至 {}（共 {} 个快照）\n\n",
+                opts.timezone.format_datetime(report.start),
+                opts.timezone.format_datetime(report.end),
+                report.snapshots
+            ));
+        }
+        Language::En => {
+            output.push_str(&format!("## GLM usage report ({})\n\n", period));
+            output.push_str(&format!(
+                "Period: {} to {} ({} snapshots)\n\n",
+                opts.timezone.format_datetime(report.start),
+                opts.timezone.format_datetime(report.end),
+                report.snapshots
+            ));
+        }
+    }
+
+    let mcp = match (report.mcp_calls, report.mcp_quota) {
+        (Some(calls), Some(quota)) => format!("{} / {}", calls, quota),
+        _ => "-".to_string(),
+    };
+    output.push_str(lang.pick("| 指标 | 数值 |\n", "| Metric | Value |\n"));
+    output.push_str("|------|------|\n");
+    for (label, value) in [
+        (lang.pick("5 小时窗口数", "5-hour windows"), report.windows.len().to_string()),
+        (lang.pick("达到 100% 的窗口数", "Windows at 100%"), report.windows_at_limit.to_string()),
+        (lang.pick("窗口峰值使用率", "Peak utilisation"), percent(report.peak_percentage)),
+        (lang.pick("平均剩余额度", "Average headroom"), percent(report.average_headroom)),
+        (lang.pick("本月 MCP 调用", "MCP calls this month"), mcp),
+    ] {
+        output.push_str(&format!("| {} | {} |\n", label, value));
+    }
+
+    if report.windows.is_empty() {
+        output.push('\n');
+        output.push_str(lang.pick(
+            "统计区间内没有快照。每次运行 `glm` 查询都会记录一次快照。\n",
+            "No snapshots in this period. Every `glm` query records one.\n",
+        ));
+        return output;
+    }
+
+    output.push('\n');
+    output.push_str(lang.pick("### 各 5 小时窗口\n\n", "### 5-hour windows\n\n"));
+    output.push_str(lang.pick(
+        "| 重置时间 | 峰值使用率 | 峰值 tokens | 剩余额度 | 快照数 |\n",
+        "| Resets at | Peak | Peak tokens | Headroom | Snapshots |\n",
+    ));
+    output.push_str("|----------|------------|-------------|----------|--------|\n");
+    for window in &report.windows {
+        output.push_str(&format!(
+            "| {} | {:.1}%{} | {} / {} | {:.1}% | {} |\n",
+            time(window.reset_at),
+            window.peak_percentage,
+            if window.hit_limit() { " ⚠" } else { "" },
+            window.peak_tokens,
+            window.quota_tokens,
+            window.headroom(),
+            window.samples
+        ));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::WindowStat;
+
+    fn create_test_report() -> UsageReport {
+        let end = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+        UsageReport {
+            period: ReportPeriod::Week,
+            start: end - chrono::Duration::days(7),
+            end,
+            snapshots: 3,
+            windows: vec![WindowStat {
+                reset_at: end,
+                peak_percentage: 100.0,
+                peak_tokens: 1000,
+                quota_tokens: 1000,
+                samples: 3,
+            }],
+            windows_at_limit: 1,
+            peak_percentage: Some(100.0),
+            average_headroom: Some(0.0),
+            mcp_calls: Some(164),
+            mcp_quota: Some(1000),
+        }
+    }
+
+    #[test]
+    fn test_render_report_csv() {
+        let opts = DisplayOptions {
+            timezone: crate::output::DisplayZone::Utc,
+            ..DisplayOptions::default()
+        };
+        let output = render_report_csv(&create_test_report(), &opts);
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].starts_with("window_reset_at,peak_percentage"));
+        assert_eq!(lines[1], "2025-10-09T08:53:20+00:00,100.0,1000,1000,0.0,true,3");
+        assert!(output.contains("\nwindows_at_limit,1\n"));
+        assert!(output.contains("\nmcp_calls_this_month,164\n"));
+
+        let opts = DisplayOptions {
+            timezone: "+08:00".parse().unwrap(),
+            ..DisplayOptions::default()
+        };
+        let output = render_report_csv(&create_test_report(), &opts);
+        assert!(output.contains("\nperiod_end,2025-10-09T16:53:20+08:00\n"));
+    }
+
+    #[test]
+    fn test_render_report_markdown() {
+        let output = render_report_markdown(&create_test_report(), &DisplayOptions::default());
+        assert!(output.starts_with("## GLM 使用报告（近 7 天）"));
+        assert!(output.contains("| 达到 100% 的窗口数 | 1 |"));
+        assert!(output.contains("| 本月 MCP 调用 | 164 / 1000 |"));
+        assert!(output.contains("| 100.0% ⚠ | 1000 / 1000 | 0.0% | 3 |"));
+    }
+}
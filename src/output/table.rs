@@ -2,47 +2,255 @@
 //!
 //! 渲染更加用户友好的 API 使用情况显示。
 
-use crate::api::{ApiData, LimitItem};
-use chrono::{Timelike, Utc};
-use chrono::Local;
+use super::{format_relative, DisplayOptions};
+use crate::api::{ApiData, ApiPlan, LimitItem, UsageBreakdown, UsageDimension, PLAN_QUOTA};
+use crate::config::Language;
+use crate::quota::next_reset_at;
+use chrono::{DateTime, Utc};
 
 /// 渲染 API 使用情况信息
 ///
 /// # 参数
 ///
 /// * `data`: API 响应数据，包含所有限制项
+/// * `opts`: 显示语言与时区设置
 ///
 /// # 返回
 ///
 /// 格式化后的输出字符串
-pub fn render_table(data: &ApiData) -> String {
+pub fn render_table(data: &ApiData, opts: &DisplayOptions) -> String {
+    tracing::debug!(limits = data.limits.len(), "渲染表格输出");
     let mut output = String::new();
 
     // 遍历所有限制项
     for limit in &data.limits {
-        output.push_str(&render_limit_item(limit));
+        output.push_str(&render_limit_item(limit, opts));
         output.push_str("\n\n");
     }
 
     // 添加最近更新时间
-    output.push_str(&format!("最近更新时间：{}", format_now()));
+    output.push_str(&render_updated_at(opts));
 
     output
 }
 
-/// 渲染单个限制项
-fn render_limit_item(limit: &LimitItem) -> String {
-    let title = match limit.limit_type.as_str() {
-        "TIME_LIMIT" => "MCP每月额度",
-        "TOKENS_LIMIT" => "每5小时使用限额",
-        _ => &limit.limit_type,
+/// 渲染单行的使用情况摘要（`glm watch`），如 `每5小时使用限额 66%（2h 13m 后重置） · MCP每月额度 16%`
+pub fn render_compact(data: &ApiData, opts: &DisplayOptions) -> String {
+    let now = Utc::now();
+    data.limits
+        .iter()
+        .map(|limit| {
+            let summary = format!("{} {}%", limit_title(limit, opts.language), limit.percentage as u32);
+            match limit.next_reset_time.and_then(DateTime::from_timestamp_millis) {
+                Some(reset) => {
+                    let relative = format_relative(reset, now, opts.language);
+                    match opts.language {
+                        Language::Zh => format!("{}（{}重置）", summary, relative),
+                        Language::En => format!("{} (resets {})", summary, relative),
+                    }
+                }
+                None => summary,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// 额度项的显示标题
+pub(crate) fn limit_title(limit: &LimitItem, lang: Language) -> String {
+    match limit.limit_type.as_str() {
+        "TIME_LIMIT" => lang.pick("MCP每月额度", "MCP monthly quota").to_string(),
+        "TOKENS_LIMIT" => lang.pick("每5小时使用限额", "5-hour usage limit").to_string(),
+        PLAN_QUOTA => ApiPlan::from_limit_item(limit).plan_name,
+        _ => limit.limit_type.clone(),
+    }
+}
+
+/// 渲染订阅计划信息（`glm plan`）
+pub fn render_plan(plan: &ApiPlan, opts: &DisplayOptions) -> String {
+    let lang = opts.language;
+    let mut output = String::new();
+
+    output.push_str(lang.pick("订阅计划：", "Plan: "));
+    output.push_str(&plan.plan_name);
+    if let Some(plan_id) = &plan.plan_id {
+        match lang {
+            Language::Zh => output.push_str(&format!("（{}）", plan_id)),
+            Language::En => output.push_str(&format!(" ({})", plan_id)),
+        }
+    }
+    output.push('\n');
+
+    let percentage = plan.usage_percentage as u32;
+    output.push_str(&render_progress_bar(percentage));
+    output.push_str(&format!(" {}%\n", percentage));
+
+    if plan.token_type.as_deref() == Some("requests") {
+        output.push_str(&format_number_with_used(plan.used_quota, plan.total_quota, lang));
+    } else {
+        output.push_str(&format_tokens_used(plan.used_quota, plan.total_quota, lang));
+    }
+    output.push('\n');
+
+    output.push_str(&render_validity(plan, Utc::now(), opts));
+    output.push('\n');
+    output.push_str(&render_updated_at(opts));
+
+    output
+}
+
+/// 渲染按模型或工具统计的用量明细（`glm usage`）
+///
+/// 按模型统计时按 token 消耗计算占比，按工具统计时按调用次数计算占比。
+pub fn render_usage_breakdown(breakdown: &UsageBreakdown, opts: &DisplayOptions) -> String {
+    let lang = opts.language;
+    let mut output = String::new();
+
+    let title = match breakdown.by {
+        UsageDimension::Model => lang.pick("按模型统计用量", "Usage by model"),
+        UsageDimension::Tool => lang.pick("按工具统计调用", "Calls by tool"),
     };
+    let range = format!(
+        "{} {} {}",
+        breakdown.start.format("%Y-%m-%d"),
+        lang.pick("至", "to"),
+        breakdown.end.format("%Y-%m-%d")
+    );
+    match lang {
+        Language::Zh => output.push_str(&format!("{}（{}）\n", title, range)),
+        Language::En => output.push_str(&format!("{} ({})\n", title, range)),
+    }
+
+    if breakdown.entries.is_empty() {
+        output.push_str(lang.pick("该时间范围内没有用量记录\n", "No usage in this period\n"));
+        output.push('\n');
+        output.push_str(&render_updated_at(opts));
+        return output;
+    }
+
+    let value_of = |tokens: u64, calls: u64| match breakdown.by {
+        UsageDimension::Model => tokens,
+        UsageDimension::Tool => calls,
+    };
+    let total = value_of(breakdown.total_tokens(), breakdown.total_calls());
+    let width = breakdown
+        .entries
+        .iter()
+        .map(|e| e.name.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    for entry in &breakdown.entries {
+        let value = value_of(entry.tokens, entry.calls);
+        let share = if total == 0 { 0.0 } else { value as f64 / total as f64 * 100.0 };
+        output.push_str(&format!(
+            "{:<width$}  {} {:>5.1}%  ",
+            entry.name,
+            render_progress_bar(share as u32),
+            share,
+            width = width
+        ));
+        match breakdown.by {
+            UsageDimension::Model => output.push_str(&format!(
+                "{} tokens · {} {}\n",
+                format_number(entry.tokens),
+                format_number(entry.calls),
+                lang.pick("次", "calls")
+            )),
+            UsageDimension::Tool => output.push_str(&format!(
+                "{} {}\n",
+                format_number(entry.calls),
+                lang.pick("次", "calls")
+            )),
+        }
+    }
+
+    let total_label = lang.pick("合计", "Total");
+    match breakdown.by {
+        UsageDimension::Model => output.push_str(&format!(
+            "{}：{} tokens · {} {}\n",
+            total_label,
+            format_number(breakdown.total_tokens()),
+            format_number(breakdown.total_calls()),
+            lang.pick("次", "calls")
+        )),
+        UsageDimension::Tool => output.push_str(&format!(
+            "{}：{} {}\n",
+            total_label,
+            format_number(breakdown.total_calls()),
+            lang.pick("次", "calls")
+        )),
+    }
+    output.push('\n');
+    output.push_str(&render_updated_at(opts));
+
+    output
+}
+
+/// 渲染计划有效期
+fn render_validity(plan: &ApiPlan, now: DateTime<Utc>, opts: &DisplayOptions) -> String {
+    let lang = opts.language;
+    let date = |dt: DateTime<Utc>| opts.timezone.convert(dt).format("%Y-%m-%d").to_string();
+    let label = lang.pick("有效期：", "Valid: ");
+    match (plan.start_date, plan.end_date) {
+        (start, Some(end)) => {
+            let start = start.map(date).unwrap_or_else(|| "-".to_string());
+            let days = (end - now).num_days();
+            match (lang, end < now) {
+                (Language::Zh, true) => format!("{}{} 至 {}（已过期）\n", label, start, date(end)),
+                (Language::Zh, false) => {
+                    format!("{}{} 至 {}（剩余 {} 天）\n", label, start, date(end), days)
+                }
+                (Language::En, true) => format!("{}{} to {} (expired)\n", label, start, date(end)),
+                (Language::En, false) => {
+                    format!("{}{} to {} ({} days left)\n", label, start, date(end), days)
+                }
+            }
+        }
+        (Some(start), None) => match lang {
+            Language::Zh => format!("{}自 {} 起\n", label, date(start)),
+            Language::En => format!("{}since {}\n", label, date(start)),
+        },
+        (None, None) => {
+            let missing = lang.pick("接口未提供", "not provided by the API");
+            match plan.next_reset_time {
+                Some(reset) => {
+                    let reset = opts.timezone.format_datetime(reset);
+                    match lang {
+                        Language::Zh => format!("{}{}（下次重置 {}）\n", label, missing, reset),
+                        Language::En => format!("{}{} (next reset {})\n", label, missing, reset),
+                    }
+                }
+                None => format!("{}{}\n", label, missing),
+            }
+        }
+    }
+}
+
+/// 渲染数据一致性警告
+pub fn render_warnings(warnings: &[String], opts: &DisplayOptions) -> String {
+    let mut output = String::from(opts.language.pick("⚠ 数据一致性警告：", "⚠ Data consistency warnings:"));
+    for warning in warnings {
+        output.push_str("\n- ");
+        output.push_str(warning);
+    }
+    output
+}
+
+/// 渲染单个限制项
+fn render_limit_item(limit: &LimitItem, opts: &DisplayOptions) -> String {
+    let lang = opts.language;
+
+    // 计划接口的额度项：显示订阅名称与有效期
+    let plan = (limit.limit_type == PLAN_QUOTA).then(|| ApiPlan::from_limit_item(limit));
+
+    let title = limit_title(limit, lang);
 
     let mut output = String::new();
 
     // 标题
-    output.push_str(title);
-    output.push_str("\n");
+    output.push_str(&title);
+    output.push('\n');
 
     // 百分比（显示在上方，不带 %，单独一行）
     let percentage = limit.percentage as u32;
@@ -50,34 +258,40 @@ fn render_limit_item(limit: &LimitItem) -> String {
 
     // 进度条（在百分比下方）
     output.push_str(&render_progress_bar(percentage));
-    output.push_str(" ");
+    output.push(' ');
     output.push_str(&format!("{}%", percentage));
-    output.push_str("\n");
+    output.push('\n');
 
     // 已使用信息
-    match limit.limit_type.as_str() {
-        "TIME_LIMIT" => {
-            // TIME_LIMIT 显示具体使用次数
-            output.push_str(&format_number_with_used(limit.current_value, limit.usage));
-            output.push_str("\n");
-        }
-        "TOKENS_LIMIT" => {
-            // TOKENS_LIMIT 显示 token 使用量
-            output.push_str(&format_tokens_used(limit.current_value, limit.usage));
-            output.push_str("\n");
-        }
-        _ => {
-            output.push_str(&format_number(limit.current_value));
-            output.push_str("\n");
-        }
-    }
+    output.push_str(&format_limit_used(limit, plan.as_ref(), lang));
+    output.push('\n');
 
-    // 重置时间
-    output.push_str(&render_reset_time(limit));
+    // 重置时间（计划额度显示有效期）
+    match &plan {
+        Some(plan) => output.push_str(&render_validity(plan, Utc::now(), opts)),
+        None => output.push_str(&render_reset_time(limit, opts, Utc::now())),
+    }
 
     output
 }
 
+/// 额度项的已使用量（如 `1.2 万 / 5.0 万 tokens`、`100 / 1,000 次`）
+///
+/// `plan` 为计划接口额度项对应的订阅信息，用于判断按请求次数还是 token 计量。
+pub(crate) fn format_limit_used(limit: &LimitItem, plan: Option<&ApiPlan>, lang: Language) -> String {
+    match limit.limit_type.as_str() {
+        // TIME_LIMIT 显示具体使用次数
+        "TIME_LIMIT" => format_number_with_used(limit.current_value, limit.usage, lang),
+        // TOKENS_LIMIT 显示 token 使用量
+        "TOKENS_LIMIT" => format_tokens_used(limit.current_value, limit.usage, lang),
+        PLAN_QUOTA if plan.and_then(|p| p.token_type.as_deref()) == Some("requests") => {
+            format_number_with_used(limit.current_value, limit.usage, lang)
+        }
+        PLAN_QUOTA => format_tokens_used(limit.current_value, limit.usage, lang),
+        _ => format_number(limit.current_value),
+    }
+}
+
 /// 渲染百分比进度条
 ///
 /// 使用 Unicode 字符创建一个类似这样的进度条：
@@ -94,29 +308,39 @@ fn render_progress_bar(percentage: u32) -> String {
 }
 
 /// 渲染重置时间
-fn render_reset_time(limit: &LimitItem) -> String {
-    match limit.limit_type.as_str() {
-        "TIME_LIMIT" => {
-            // MCP每月额度
-            "重置时间：每月1号00:00重置\n".to_string()
-        }
-        "TOKENS_LIMIT" => {
-            // 每5小时使用限额 - 计算下次重置时间
-            if let Some(reset_ts) = limit.next_reset_time {
-                let reset_time = chrono::DateTime::from_timestamp_millis(reset_ts);
-                if let Some(dt) = reset_time {
-                    let local_dt = dt.with_timezone(&Local);
-                    return format!("重置时间：{:02}:{:02}\n", local_dt.hour(), local_dt.minute());
-                }
+///
+/// 同时显示显示时区下的绝对时间与相对时间。按月重置的额度（`TIME_LIMIT`）
+/// 取区域时区下月 1 号 00:00。
+fn render_reset_time(limit: &LimitItem, opts: &DisplayOptions, now: DateTime<Utc>) -> String {
+    let lang = opts.language;
+    let label = lang.pick("重置时间：", "Resets: ");
+    match next_reset_at(limit, now, opts.reset_offset) {
+        Some(reset) => {
+            let at = opts.timezone.format_datetime(reset);
+            let relative = format_relative(reset, now, lang);
+            match lang {
+                Language::Zh => format!("{}{}（{}）\n", label, at, relative),
+                Language::En => format!("{}{} ({})\n", label, at, relative),
             }
-            "重置时间：每5小时重置\n".to_string()
         }
-        _ => String::new(),
+        None if limit.limit_type == "TOKENS_LIMIT" => {
+            format!("{}{}\n", label, lang.pick("每5小时重置", "every 5 hours"))
+        }
+        None => String::new(),
     }
 }
 
+/// 渲染最近更新时间
+fn render_updated_at(opts: &DisplayOptions) -> String {
+    format!(
+        "{}{}",
+        opts.language.pick("最近更新时间：", "Last updated: "),
+        format_now(opts)
+    )
+}
+
 /// 格式化数字和已使用/总量
-fn format_number_with_used(used: u64, total: u64) -> String {
+fn format_number_with_used(used: u64, total: u64, lang: Language) -> String {
     let used_str = format_number(used);
     let total_str = if total >= 1000000 {
         format!("{}M", total / 1000000)
@@ -126,28 +350,21 @@ fn format_number_with_used(used: u64, total: u64) -> String {
         format_number(total)
     };
 
-    format!("{} / {} 次", used_str, total_str)
+    format!("{} / {} {}", used_str, total_str, lang.pick("次", "calls"))
 }
 
 /// 格式化 token 使用量
-fn format_tokens_used(used: u64, total: u64) -> String {
-    let used_str = if used >= 100000000 {
-        format!("{:.1} 亿", used as f64 / 100000000.0)
-    } else if used >= 10000 {
-        format!("{:.1} 万", used as f64 / 10000.0)
-    } else {
-        format_number(used)
-    };
-
-    let total_str = if total >= 100000000 {
-        format!("{:.1} 亿", total as f64 / 100000000.0)
-    } else if total >= 10000 {
-        format!("{:.1} 万", total as f64 / 10000.0)
-    } else {
-        format_number(total)
+fn format_tokens_used(used: u64, total: u64, lang: Language) -> String {
+    let scale = |n: u64| match lang {
+        Language::Zh if n >= 100000000 => format!("{:.1} 亿", n as f64 / 100000000.0),
+        Language::Zh if n >= 10000 => format!("{:.1} 万", n as f64 / 10000.0),
+        Language::En if n >= 1000000000 => format!("{:.1}B", n as f64 / 1000000000.0),
+        Language::En if n >= 1000000 => format!("{:.1}M", n as f64 / 1000000.0),
+        Language::En if n >= 10000 => format!("{:.1}K", n as f64 / 1000.0),
+        _ => format_number(n),
     };
 
-    format!("{} / {} tokens", used_str, total_str)
+    format!("{} / {} tokens", scale(used), scale(total))
 }
 
 /// 格式化数字（添加千位分隔符）
@@ -157,7 +374,7 @@ fn format_number(num: u64) -> String {
     let mut result = String::new();
 
     for (i, c) in num_str.chars().enumerate() {
-        if i > 0 && (len - i) % 3 == 0 {
+        if i > 0 && (len - i).is_multiple_of(3) {
             result.push(',');
         }
         result.push(c);
@@ -167,14 +384,19 @@ fn format_number(num: u64) -> String {
 }
 
 /// 格式化当前时间
-fn format_now() -> String {
-    let now = Utc::now().with_timezone(&Local);
-    now.format("%Y-%m-%d %H:%M:%S").to_string()
+fn format_now(opts: &DisplayOptions) -> String {
+    let now = Utc::now();
+    format!(
+        "{} {}",
+        opts.timezone.convert(now).format("%Y-%m-%d %H:%M:%S"),
+        opts.timezone.label(now)
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::output::DisplayZone;
 
     #[test]
     fn test_render_progress_bar() {
@@ -193,10 +415,10 @@ mod tests {
 
     #[test]
     fn test_format_number_with_used() {
-        assert_eq!(format_number_with_used(164, 1000), "164 / 1K 次");
-        assert_eq!(format_number_with_used(500, 500), "500 / 500 次");
+        assert_eq!(format_number_with_used(164, 1000, Language::Zh), "164 / 1K 次");
+        assert_eq!(format_number_with_used(500, 500, Language::Zh), "500 / 500 次");
         assert_eq!(
-            format_number_with_used(1500000, 2000000),
+            format_number_with_used(1500000, 2000000, Language::Zh),
             "1,500,000 / 2M 次"
         );
     }
@@ -212,13 +434,25 @@ mod tests {
             remaining: 836,
             percentage: 16.0,
             next_reset_time: None,
+            extra: Default::default(),
         };
 
-        let output = render_limit_item(&limit);
+        let output = render_limit_item(&limit, &DisplayOptions::default());
         assert!(output.contains("MCP每月额度"));
         assert!(output.contains("16%"));
         assert!(output.contains("164 / 1K 次"));
-        assert!(output.contains("每月1号00:00重置"));
+        assert!(output.contains("重置时间："));
+
+        // 按区域时区（UTC+8）下月 1 号 00:00 重置，以 UTC 显示
+        let opts = DisplayOptions {
+            timezone: DisplayZone::Utc,
+            ..DisplayOptions::default()
+        };
+        let now = "2026-10-19T06:00:00Z".parse().unwrap();
+        assert_eq!(
+            render_reset_time(&limit, &opts, now),
+            "重置时间：2026-10-31 16:00 UTC（12d 10h 后）\n"
+        );
     }
 
     #[test]
@@ -232,25 +466,167 @@ mod tests {
             remaining: 67625968,
             percentage: 66.0,
             next_reset_time: Some(1768328328345),
+            extra: Default::default(),
         };
 
-        let output = render_limit_item(&limit);
+        let output = render_limit_item(&limit, &DisplayOptions::default());
         assert!(output.contains("每5小时使用限额"));
         assert!(output.contains("66%"));
         assert!(output.contains("tokens"));
-        // 验证包含重置时间格式 (HH:MM)
         assert!(output.contains("重置时间："));
-        assert!(output.contains(':')); // 时间格式包含冒号
+
+        // 重置时间在次日时显示完整日期
+        let opts = DisplayOptions {
+            timezone: "+08:00".parse().unwrap(),
+            ..DisplayOptions::default()
+        };
+        let now = "2026-01-13T16:00:00Z".parse().unwrap();
+        assert_eq!(
+            render_reset_time(&limit, &opts, now),
+            "重置时间：2026-01-14 02:18 UTC+08:00（2h 18m 后）\n"
+        );
+        let limit = LimitItem {
+            next_reset_time: None,
+            ..limit
+        };
+        assert_eq!(render_reset_time(&limit, &opts, now), "重置时间：每5小时重置\n");
+    }
+
+    fn create_test_plan() -> ApiPlan {
+        let info: crate::api::PlanInfo = serde_json::from_str(
+            r#"{"plan_id":"premium_plan","plan_name":"高级版","total_quota":1000000,
+                "used_quota":250000,"remaining_quota":750000,"usage_percentage":25.0,
+                "start_date":"2026-01-01T00:00:00Z","end_date":"2026-12-31T12:00:00Z",
+                "token_type":"tokens"}"#,
+        )
+        .unwrap();
+        ApiPlan::from_plan_info(&info)
+    }
+
+    #[test]
+    fn test_render_plan() {
+        let output = render_plan(&create_test_plan(), &DisplayOptions::default());
+        assert!(output.contains("订阅计划：高级版（premium_plan）"));
+        assert!(output.contains("25%"));
+        assert!(output.contains("25.0 万 / 100.0 万 tokens"));
+        assert!(output.contains("有效期："));
+    }
+
+    #[test]
+    fn test_render_validity() {
+        let plan = create_test_plan();
+        let now = "2026-12-01T12:00:00Z".parse().unwrap();
+        assert!(render_validity(&plan, now, &DisplayOptions::default()).contains("（剩余 30 天）"));
+        let now = "2027-01-01T00:00:00Z".parse().unwrap();
+        assert!(render_validity(&plan, now, &DisplayOptions::default()).contains("（已过期）"));
+    }
+
+    #[test]
+    fn test_render_limit_item_plan_quota() {
+        let limit = create_test_plan_info_item();
+        let output = render_limit_item(&limit, &DisplayOptions::default());
+        assert!(output.contains("高级版"));
+        assert!(output.contains("25%"));
+        assert!(output.contains("有效期："));
+    }
+
+    fn create_test_plan_info_item() -> LimitItem {
+        let info: crate::api::PlanInfo =
+            serde_json::from_str(r#"{"plan_name":"高级版","total_quota":1000000,"used_quota":250000,"end_date":"2026-12-31"}"#)
+                .unwrap();
+        info.to_limit_item()
+    }
+
+    #[test]
+    fn test_render_english() {
+        let opts = DisplayOptions {
+            language: Language::En,
+            reset_offset: chrono::FixedOffset::east_opt(0).unwrap(),
+            timezone: DisplayZone::Utc,
+            ..DisplayOptions::default()
+        };
+        let limit = LimitItem {
+            limit_type: "TIME_LIMIT".to_string(),
+            unit: 5,
+            number: 1,
+            usage: 1000,
+            current_value: 164,
+            remaining: 836,
+            percentage: 16.0,
+            next_reset_time: None,
+            extra: Default::default(),
+        };
+        let output = render_limit_item(&limit, &opts);
+        assert!(output.contains("MCP monthly quota"));
+        assert!(output.contains("164 / 1K calls"));
+        let now = "2026-10-19T06:00:00Z".parse().unwrap();
+        assert_eq!(
+            render_reset_time(&limit, &opts, now),
+            "Resets: 2026-11-01 00:00 UTC (in 12d 18h)\n"
+        );
+
+        assert_eq!(
+            format_tokens_used(132374032, 200000000, Language::En),
+            "132.4M / 200.0M tokens"
+        );
+        let plan = create_test_plan();
+        let now = "2026-12-01T12:00:00Z".parse().unwrap();
+        assert!(render_validity(&plan, now, &opts).contains("(30 days left)"));
+    }
+
+    #[test]
+    fn test_render_usage_breakdown() {
+        let body = serde_json::json!({"data": [
+            {"modelName": "glm-4.6", "totalTokens": 7500, "callCount": 3},
+            {"modelName": "glm-4.5-air", "totalTokens": 2500, "callCount": 1}
+        ]});
+        let start = "2026-10-12T00:00:00+08:00".parse().unwrap();
+        let end = "2026-10-18T23:59:59+08:00".parse().unwrap();
+        let breakdown = UsageBreakdown::from_response(UsageDimension::Model, start, end, &body).unwrap();
+
+        let output = render_usage_breakdown(&breakdown, &DisplayOptions::default());
+        assert!(output.contains("按模型统计用量（2026-10-12 至 2026-10-18）"));
+        assert!(output.contains("glm-4.6      ███████████████░░░░░  75.0%  7,500 tokens · 3 次"));
+        assert!(output.contains("合计：10,000 tokens · 4 次"));
+    }
+
+    #[test]
+    fn test_render_compact() {
+        let data: ApiData = serde_json::from_value(serde_json::json!({"limits": [
+            {"type": "TOKENS_LIMIT", "usage": 100, "currentValue": 66},
+            {"type": "TIME_LIMIT", "usage": 1000, "currentValue": 164}
+        ]}))
+        .unwrap();
+        assert_eq!(
+            render_compact(&data, &DisplayOptions::default()),
+            "每5小时使用限额 66% · MCP每月额度 16%"
+        );
+
+        let reset = Utc::now() + chrono::Duration::minutes(90) + chrono::Duration::seconds(30);
+        let data: ApiData = serde_json::from_value(serde_json::json!({"limits": [
+            {"type": "TOKENS_LIMIT", "usage": 100, "currentValue": 66, "nextResetTime": reset.timestamp_millis()}
+        ]}))
+        .unwrap();
+        assert_eq!(
+            render_compact(&data, &DisplayOptions::default()),
+            "每5小时使用限额 66%（1h 30m 后重置）"
+        );
+    }
+
+    #[test]
+    fn test_render_warnings() {
+        let output = render_warnings(&["A".to_string(), "B".to_string()], &DisplayOptions::default());
+        assert_eq!(output, "⚠ 数据一致性警告：\n- A\n- B");
     }
 
     #[test]
     fn test_format_tokens_used() {
         // 测试亿级
-        assert!(format_tokens_used(132374032, 200000000).contains("1.3 亿"));
+        assert!(format_tokens_used(132374032, 200000000, Language::Zh).contains("1.3 亿"));
         // 测试万级
-        assert!(format_tokens_used(50000, 100000).contains("5.0 万"));
+        assert!(format_tokens_used(50000, 100000, Language::Zh).contains("5.0 万"));
         // 测试千级
-        assert!(format_tokens_used(1000, 5000).contains("1,000"));
-        assert!(format_tokens_used(1000, 5000).contains("5,000"));
+        assert!(format_tokens_used(1000, 5000, Language::Zh).contains("1,000"));
+        assert!(format_tokens_used(1000, 5000, Language::Zh).contains("5,000"));
     }
 }
@@ -0,0 +1,188 @@
+//! 显示时区与重置时间格式
+//!
+//! 重置时间同时以绝对时间（日期 + 时间 + 时区）和相对时间（`2h 13m 后`）显示。
+//! 显示时区默认为本地时区，可用 `--tz` / 配置 `timezone:` 指定，`--utc` 强制使用 UTC。
+
+use crate::config::Language;
+use chrono::{DateTime, FixedOffset, Local, Offset, Utc};
+use chrono_tz::Tz;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// 显示时区
+#[derive(Debug, Clone, Default)]
+pub enum DisplayZone {
+    /// 本地时区
+    #[default]
+    Local,
+    /// UTC
+    Utc,
+    /// 固定偏移（如 `+08:00`）
+    Fixed(FixedOffset),
+    /// IANA 时区（如 `Asia/Shanghai`，按时刻计算夏令时）
+    Named(Tz),
+}
+
+impl DisplayZone {
+    /// 指定时刻在该时区的 UTC 偏移
+    pub fn offset_at(&self, at: DateTime<Utc>) -> FixedOffset {
+        match self {
+            DisplayZone::Local => at.with_timezone(&Local).offset().fix(),
+            DisplayZone::Utc => Utc.fix(),
+            DisplayZone::Fixed(offset) => *offset,
+            DisplayZone::Named(tz) => at.with_timezone(tz).offset().fix(),
+        }
+    }
+
+    /// 转换到该时区
+    pub fn convert(&self, at: DateTime<Utc>) -> DateTime<FixedOffset> {
+        at.with_timezone(&self.offset_at(at))
+    }
+
+    /// 时区标识（IANA 名称、`UTC` 或 `UTC+08:00`）
+    pub fn label(&self, at: DateTime<Utc>) -> String {
+        match self {
+            DisplayZone::Utc => "UTC".to_string(),
+            DisplayZone::Named(tz) => tz.name().to_string(),
+            DisplayZone::Local | DisplayZone::Fixed(_) => format!("UTC{}", self.offset_at(at)),
+        }
+    }
+
+    /// 带时区的绝对时间（如 `2026-10-19 14:00 UTC+08:00`）
+    pub fn format_datetime(&self, at: DateTime<Utc>) -> String {
+        format!("{} {}", self.convert(at).format("%Y-%m-%d %H:%M"), self.label(at))
+    }
+}
+
+impl FromStr for DisplayZone {
+    type Err = String;
+
+    /// 解析 `local`、`utc`、固定偏移（`+08:00`、`-0530`、`UTC+8`）或 IANA 时区名称
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.to_ascii_lowercase().as_str() {
+            "local" => return Ok(DisplayZone::Local),
+            "utc" | "z" | "gmt" => return Ok(DisplayZone::Utc),
+            _ => {}
+        }
+        if let Some(offset) = parse_offset(s) {
+            return Ok(DisplayZone::Fixed(offset));
+        }
+        s.parse::<Tz>()
+            .map(DisplayZone::Named)
+            .map_err(|_| format!("未知的时区: {}（示例: Asia/Shanghai、UTC、+08:00、local）", s))
+    }
+}
+
+/// 解析固定偏移（`+08:00`、`+0800`、`+8`，可带 `UTC` / `GMT` 前缀）
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let upper = s.to_ascii_uppercase();
+    let rest = upper
+        .strip_prefix("UTC")
+        .or_else(|| upper.strip_prefix("GMT"))
+        .unwrap_or(&upper);
+    let (sign, digits) = match rest.as_bytes().first()? {
+        b'+' => (1, &rest[1..]),
+        b'-' => (-1, &rest[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    if hours.is_empty() || hours.len() > 2 || minutes.len() > 2 {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// 将时长格式化为 `13d 4h` / `1h 23m` / `5m 10s` 形式
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match (secs / 3600, secs % 3600 / 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, _, _) if h >= 24 => format!("{}d {}h", h / 24, h % 24),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+/// 相对时间（如 `2h 13m 后` / `in 2h 13m`，已过去时为 `5m 前` / `5m ago`）
+pub fn format_relative(at: DateTime<Utc>, now: DateTime<Utc>, lang: Language) -> String {
+    let delta = at - now;
+    let duration = delta.abs().to_std().unwrap_or_default();
+    let text = format_duration(duration);
+    match (lang, delta >= chrono::Duration::zero()) {
+        (Language::Zh, true) => format!("{} 后", text),
+        (Language::Zh, false) => format!("{} 前", text),
+        (Language::En, true) => format!("in {}", text),
+        (Language::En, false) => format!("{} ago", text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_zone() {
+        assert!(matches!("local".parse(), Ok(DisplayZone::Local)));
+        assert!(matches!("UTC".parse(), Ok(DisplayZone::Utc)));
+        let east8 = FixedOffset::east_opt(8 * 3600).unwrap();
+        for s in ["+08:00", "+0800", "+8", "UTC+8", "utc+08:00"] {
+            assert!(matches!(s.parse(), Ok(DisplayZone::Fixed(offset)) if offset == east8), "{}", s);
+        }
+        assert!(matches!("-05:30".parse(), Ok(DisplayZone::Fixed(offset)) if offset.local_minus_utc() == -19800));
+        assert!("+25:00".parse::<DisplayZone>().is_err());
+        assert!("Mars/Olympus".parse::<DisplayZone>().unwrap_err().contains("未知的时区"));
+    }
+
+    #[test]
+    fn test_named_zone_follows_dst() {
+        let zone: DisplayZone = "America/New_York".parse().unwrap();
+        let winter = Utc.with_ymd_and_hms(2026, 1, 15, 12, 0, 0).unwrap();
+        let summer = Utc.with_ymd_and_hms(2026, 7, 15, 12, 0, 0).unwrap();
+        assert_eq!(zone.offset_at(winter).local_minus_utc(), -5 * 3600);
+        assert_eq!(zone.offset_at(summer).local_minus_utc(), -4 * 3600);
+        assert_eq!(zone.format_datetime(summer), "2026-07-15 08:00 America/New_York");
+    }
+
+    #[test]
+    fn test_format_datetime() {
+        let at = Utc.with_ymd_and_hms(2026, 10, 19, 6, 0, 0).unwrap();
+        assert_eq!(DisplayZone::Utc.format_datetime(at), "2026-10-19 06:00 UTC");
+        let zone: DisplayZone = "+08:00".parse().unwrap();
+        assert_eq!(zone.format_datetime(at), "2026-10-19 14:00 UTC+08:00");
+        // 跨日
+        let at = Utc.with_ymd_and_hms(2026, 10, 19, 20, 30, 0).unwrap();
+        assert_eq!(zone.format_datetime(at), "2026-10-20 04:30 UTC+08:00");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(310)), "5m 10s");
+        assert_eq!(format_duration(Duration::from_secs(4980)), "1h 23m");
+        assert_eq!(format_duration(Duration::from_secs(316 * 3600 + 59)), "13d 4h");
+    }
+
+    #[test]
+    fn test_format_relative() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 19, 6, 0, 0).unwrap();
+        let later = now + chrono::Duration::minutes(133);
+        assert_eq!(format_relative(later, now, Language::Zh), "2h 13m 后");
+        assert_eq!(format_relative(later, now, Language::En), "in 2h 13m");
+        assert_eq!(format_relative(now - chrono::Duration::minutes(5), now, Language::En), "5m 0s ago");
+        assert_eq!(
+            format_relative(now + chrono::Duration::hours(316), now, Language::Zh),
+            "13d 4h 后"
+        );
+    }
+}
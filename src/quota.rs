@@ -0,0 +1,235 @@
+//! 额度领域类型
+//!
+//! 面向嵌入本库的程序：把接口返回的额度项（[`LimitItem`]）转换为带类型的额度种类、
+//! 用量与重置时间，不暴露上游的字段命名（`currentValue`、毫秒时间戳等）。
+
+use crate::api::{ApiData, LimitItem, PLAN_QUOTA};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+use std::fmt;
+
+/// 额度种类
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaKind {
+    /// Token 额度（`TOKENS_LIMIT`，按 5 小时窗口重置）
+    Tokens,
+    /// MCP 每月调用额度（`TIME_LIMIT`，按月重置）
+    McpCalls,
+    /// 计划接口的订阅额度（`PLAN_QUOTA`）
+    Plan,
+    /// 未识别的额度类型（保留原始名称）
+    Other(String),
+}
+
+impl QuotaKind {
+    /// 由接口中的额度类型名称转换
+    pub fn from_limit_type(limit_type: &str) -> Self {
+        match limit_type {
+            "TOKENS_LIMIT" => QuotaKind::Tokens,
+            "TIME_LIMIT" => QuotaKind::McpCalls,
+            PLAN_QUOTA => QuotaKind::Plan,
+            other => QuotaKind::Other(other.to_string()),
+        }
+    }
+
+    /// 接口中的额度类型名称
+    pub fn as_limit_type(&self) -> &str {
+        match self {
+            QuotaKind::Tokens => "TOKENS_LIMIT",
+            QuotaKind::McpCalls => "TIME_LIMIT",
+            QuotaKind::Plan => PLAN_QUOTA,
+            QuotaKind::Other(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for QuotaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_limit_type())
+    }
+}
+
+/// 额度项的下次重置时间
+///
+/// 有 `nextResetTime` 时直接使用；`TIME_LIMIT` 按月重置，取 `offset` 时区下月 1 号 00:00。
+pub fn next_reset_at(item: &LimitItem, now: DateTime<Utc>, offset: FixedOffset) -> Option<DateTime<Utc>> {
+    if let Some(millis) = item.next_reset_time {
+        return DateTime::from_timestamp_millis(millis);
+    }
+    if item.limit_type != "TIME_LIMIT" {
+        return None;
+    }
+    let local = now.with_timezone(&offset);
+    let (year, month) = match local.month() {
+        12 => (local.year() + 1, 1),
+        month => (local.year(), month + 1),
+    };
+    let midnight = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+    offset
+        .from_local_datetime(&midnight)
+        .single()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// 单项额度
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuotaLimit {
+    /// 额度种类
+    pub kind: QuotaKind,
+
+    /// 总额度
+    pub total: u64,
+
+    /// 已使用量
+    pub used: u64,
+
+    /// 剩余量
+    pub remaining: u64,
+
+    /// 使用百分比（0-100）
+    pub percentage: f64,
+
+    /// 下次重置时间（接口未提供时为 None）
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+impl QuotaLimit {
+    /// 剩余百分比（0-100）
+    pub fn remaining_percentage(&self) -> f64 {
+        (100.0 - self.percentage).clamp(0.0, 100.0)
+    }
+
+    /// 额度是否已用尽
+    pub fn is_exhausted(&self) -> bool {
+        self.total > 0 && self.remaining == 0
+    }
+}
+
+impl From<&LimitItem> for QuotaLimit {
+    fn from(item: &LimitItem) -> Self {
+        QuotaLimit {
+            kind: QuotaKind::from_limit_type(&item.limit_type),
+            total: item.usage,
+            used: item.current_value,
+            remaining: item.remaining,
+            percentage: item.percentage,
+            resets_at: item.next_reset_time.and_then(DateTime::from_timestamp_millis),
+        }
+    }
+}
+
+/// 账户的全部额度
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Quota {
+    /// 各项额度（保持接口返回的顺序）
+    pub limits: Vec<QuotaLimit>,
+}
+
+impl Quota {
+    /// 指定种类的额度
+    pub fn get(&self, kind: &QuotaKind) -> Option<&QuotaLimit> {
+        self.limits.iter().find(|limit| &limit.kind == kind)
+    }
+
+    /// Token 额度（计划接口没有 Token 额度时返回订阅额度）
+    ///
+    /// 与 `glm guard` 判断额度时使用的额度项一致。
+    pub fn tokens(&self) -> Option<&QuotaLimit> {
+        self.get(&QuotaKind::Tokens)
+            .or_else(|| self.get(&QuotaKind::Plan))
+    }
+
+    /// MCP 每月调用额度
+    pub fn mcp_calls(&self) -> Option<&QuotaLimit> {
+        self.get(&QuotaKind::McpCalls)
+    }
+}
+
+impl From<&ApiData> for Quota {
+    fn from(data: &ApiData) -> Self {
+        Quota {
+            limits: data.limits.iter().map(QuotaLimit::from).collect(),
+        }
+    }
+}
+
+impl From<ApiData> for Quota {
+    fn from(data: ApiData) -> Self {
+        Quota::from(&data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> ApiData {
+        serde_json::from_value(serde_json::json!({
+            "limits": [
+                {"type": "TIME_LIMIT", "usage": 1000, "currentValue": 100},
+                {"type": "TOKENS_LIMIT", "usage": 1000, "currentValue": 1000, "nextResetTime": 1760000000000i64},
+                {"type": "SEARCH_LIMIT", "usage": 10, "currentValue": 1}
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn item(limit_type: &str, reset: Option<DateTime<Utc>>) -> LimitItem {
+        serde_json::from_value(serde_json::json!({
+            "type": limit_type,
+            "usage": 1000,
+            "currentValue": 0,
+            "nextResetTime": reset.map(|t| t.timestamp_millis())
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_next_reset_at() {
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        // 北京时间 2025-12-31 10:00
+        let now = Utc.with_ymd_and_hms(2025, 12, 31, 2, 0, 0).unwrap();
+        assert_eq!(
+            next_reset_at(&item("TIME_LIMIT", None), now, offset),
+            Some(Utc.with_ymd_and_hms(2025, 12, 31, 16, 0, 0).unwrap())
+        );
+        let reset = Utc.with_ymd_and_hms(2025, 12, 31, 5, 0, 0).unwrap();
+        assert_eq!(next_reset_at(&item("TOKENS_LIMIT", Some(reset)), now, offset), Some(reset));
+        assert_eq!(next_reset_at(&item("TOKENS_LIMIT", None), now, offset), None);
+    }
+
+    #[test]
+    fn test_quota_from_api_data() {
+        let quota = Quota::from(data());
+        let tokens = quota.tokens().unwrap();
+        assert_eq!(tokens.kind, QuotaKind::Tokens);
+        assert_eq!((tokens.total, tokens.used, tokens.remaining), (1000, 1000, 0));
+        assert!(tokens.is_exhausted());
+        assert_eq!(tokens.resets_at, DateTime::from_timestamp(1_760_000_000, 0));
+
+        let mcp = quota.mcp_calls().unwrap();
+        assert_eq!(mcp.remaining_percentage(), 90.0);
+        assert!(!mcp.is_exhausted());
+        assert_eq!(mcp.resets_at, None);
+
+        assert_eq!(quota.limits[2].kind, QuotaKind::Other("SEARCH_LIMIT".to_string()));
+    }
+
+    #[test]
+    fn test_quota_kind_roundtrip() {
+        for name in ["TOKENS_LIMIT", "TIME_LIMIT", PLAN_QUOTA, "SEARCH_LIMIT"] {
+            assert_eq!(QuotaKind::from_limit_type(name).as_limit_type(), name);
+        }
+        assert_eq!(QuotaKind::McpCalls.to_string(), "TIME_LIMIT");
+    }
+
+    #[test]
+    fn test_tokens_falls_back_to_plan_quota() {
+        let data: ApiData = serde_json::from_value(serde_json::json!({
+            "limits": [{"type": "PLAN_QUOTA", "usage": 1, "currentValue": 0}]
+        }))
+        .unwrap();
+        assert_eq!(Quota::from(&data).tokens().unwrap().kind, QuotaKind::Plan);
+    }
+}
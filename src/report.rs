@@ -0,0 +1,219 @@
+//! 使用报告
+//!
+//! 基于本地快照历史统计一段时间内的使用情况：每个 5 小时窗口的峰值使用率、
+//! 达到 100% 的窗口数、本月 MCP 调用次数以及平均剩余额度。
+
+use crate::history::Snapshot;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// 5 小时窗口的长度（毫秒），快照缺少重置时间时用于划分窗口
+const WINDOW_MILLIS: i64 = 5 * 60 * 60 * 1000;
+
+/// 报告周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    /// 最近 24 小时
+    Day,
+    /// 最近 7 天
+    Week,
+    /// 最近 30 天
+    Month,
+}
+
+impl ReportPeriod {
+    /// 周期长度
+    pub fn duration(self) -> Duration {
+        match self {
+            ReportPeriod::Day => Duration::days(1),
+            ReportPeriod::Week => Duration::days(7),
+            ReportPeriod::Month => Duration::days(30),
+        }
+    }
+}
+
+/// 单个 5 小时窗口的统计
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowStat {
+    /// 窗口重置时间（即窗口结束时间）
+    pub reset_at: DateTime<Utc>,
+
+    /// 峰值使用率（0-100）
+    pub peak_percentage: f64,
+
+    /// 峰值时的已使用 token 数
+    pub peak_tokens: u64,
+
+    /// 窗口总额度
+    pub quota_tokens: u64,
+
+    /// 窗口内的快照数
+    pub samples: usize,
+}
+
+impl WindowStat {
+    /// 剩余额度百分比（按峰值计算）
+    pub fn headroom(&self) -> f64 {
+        (100.0 - self.peak_percentage).max(0.0)
+    }
+
+    /// 是否达到 100%
+    pub fn hit_limit(&self) -> bool {
+        self.peak_percentage >= 100.0
+    }
+}
+
+/// 使用报告
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReport {
+    /// 报告周期
+    pub period: ReportPeriod,
+
+    /// 统计开始时间
+    pub start: DateTime<Utc>,
+
+    /// 统计结束时间
+    pub end: DateTime<Utc>,
+
+    /// 参与统计的快照数
+    pub snapshots: usize,
+
+    /// 各 5 小时窗口（按时间升序）
+    pub windows: Vec<WindowStat>,
+
+    /// 达到 100% 的窗口数
+    pub windows_at_limit: usize,
+
+    /// 所有窗口中的最高使用率
+    pub peak_percentage: Option<f64>,
+
+    /// 平均剩余额度百分比
+    pub average_headroom: Option<f64>,
+
+    /// 本月已使用的 MCP 调用次数（取最近一次快照）
+    pub mcp_calls: Option<u64>,
+
+    /// 本月 MCP 调用额度
+    pub mcp_quota: Option<u64>,
+}
+
+impl UsageReport {
+    /// 根据快照历史生成报告
+    ///
+    /// `snapshots` 应已按时间升序排列，且只包含统计区间内的快照。
+    pub fn build(period: ReportPeriod, snapshots: &[Snapshot], now: DateTime<Utc>) -> Self {
+        let mut windows: Vec<WindowStat> = Vec::new();
+
+        for snapshot in snapshots {
+            let Some(item) = snapshot.limit("TOKENS_LIMIT") else {
+                continue;
+            };
+            // 以重置时间标识窗口；缺失时按快照时间所在的 5 小时区间推算
+            let reset_millis = item.next_reset_time.unwrap_or_else(|| {
+                let ts = snapshot.taken_at.timestamp_millis();
+                ts - ts.rem_euclid(WINDOW_MILLIS) + WINDOW_MILLIS
+            });
+            let Some(reset_at) = DateTime::from_timestamp_millis(reset_millis) else {
+                continue;
+            };
+
+            match windows.iter_mut().find(|w| w.reset_at == reset_at) {
+                Some(window) => {
+                    window.samples += 1;
+                    if item.percentage > window.peak_percentage {
+                        window.peak_percentage = item.percentage;
+                        window.peak_tokens = item.current_value;
+                        window.quota_tokens = item.usage;
+                    }
+                }
+                None => windows.push(WindowStat {
+                    reset_at,
+                    peak_percentage: item.percentage,
+                    peak_tokens: item.current_value,
+                    quota_tokens: item.usage,
+                    samples: 1,
+                }),
+            }
+        }
+        windows.sort_by_key(|w| w.reset_at);
+
+        let peak_percentage = windows
+            .iter()
+            .map(|w| w.peak_percentage)
+            .reduce(f64::max);
+        let average_headroom = (!windows.is_empty())
+            .then(|| windows.iter().map(WindowStat::headroom).sum::<f64>() / windows.len() as f64);
+        let mcp = snapshots.iter().rev().find_map(|s| s.limit("TIME_LIMIT"));
+
+        UsageReport {
+            period,
+            start: now - period.duration(),
+            end: now,
+            snapshots: snapshots.len(),
+            windows_at_limit: windows.iter().filter(|w| w.hit_limit()).count(),
+            windows,
+            peak_percentage,
+            average_headroom,
+            mcp_calls: mcp.map(|item| item.current_value),
+            mcp_quota: mcp.map(|item| item.usage),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::LimitItem;
+
+    fn snapshot(minutes: i64, tokens: u64, reset_hours: i64, mcp_calls: u64) -> Snapshot {
+        let t0 = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+        let taken_at = t0 + Duration::minutes(minutes);
+        let limits: Vec<LimitItem> = serde_json::from_value(serde_json::json!([
+            {"type": "TIME_LIMIT", "usage": 1000, "currentValue": mcp_calls},
+            {
+                "type": "TOKENS_LIMIT",
+                "usage": 1000,
+                "currentValue": tokens,
+                "nextResetTime": (t0 + Duration::hours(reset_hours)).timestamp_millis()
+            }
+        ]))
+        .unwrap();
+        Snapshot { taken_at, limits }
+    }
+
+    #[test]
+    fn test_build_report() {
+        let snapshots = vec![
+            snapshot(0, 200, 5, 10),
+            snapshot(60, 600, 5, 12),
+            snapshot(120, 400, 5, 15),
+            snapshot(330, 1000, 10, 20),
+        ];
+        let now = snapshots[3].taken_at;
+        let report = UsageReport::build(ReportPeriod::Week, &snapshots, now);
+
+        assert_eq!(report.snapshots, 4);
+        assert_eq!(report.windows.len(), 2);
+        assert_eq!(report.windows[0].peak_percentage, 60.0);
+        assert_eq!(report.windows[0].peak_tokens, 600);
+        assert_eq!(report.windows[0].samples, 3);
+        assert!(report.windows[1].hit_limit());
+        assert_eq!(report.windows_at_limit, 1);
+        assert_eq!(report.peak_percentage, Some(100.0));
+        assert_eq!(report.average_headroom, Some(20.0));
+        assert_eq!(report.mcp_calls, Some(20));
+        assert_eq!(report.mcp_quota, Some(1000));
+        assert_eq!(report.start, now - Duration::days(7));
+    }
+
+    #[test]
+    fn test_build_empty_report() {
+        let report = UsageReport::build(ReportPeriod::Day, &[], Utc::now());
+        assert!(report.windows.is_empty());
+        assert_eq!(report.peak_percentage, None);
+        assert_eq!(report.average_headroom, None);
+        assert_eq!(report.mcp_calls, None);
+    }
+}
@@ -0,0 +1,226 @@
+//! StatsD 指标导出
+//!
+//! 通过 UDP 把 [`crate::metrics`] 中的指标发送给 StatsD 兼容的代理（Telegraf、
+//! Datadog Agent、statsd + Graphite 等）：
+//!
+//! - `glm.quota.used` / `total` / `remaining` / `pct` / `reset_seconds`：gauge
+//! - `glm.fetch.requests` / `glm.fetch.errors`：counter，每次查询加 1
+//! - `glm.fetch.duration`：timer（毫秒）
+//!
+//! 默认使用 DogStatsD 标签（`|#type:TOKENS_LIMIT,region:cn`，设置了配置名称时另有
+//! `profile`），Telegraf 需开启
+//! `datadog_extensions`；关闭标签时额度类型写入指标名（`glm.quota.tokens_limit.used`），
+//! 适用于不支持标签的 StatsD 与 Graphite。
+
+use crate::config::Config;
+use crate::error::CliError;
+use crate::metrics::{FetchStats, QuotaMetrics, Resource, Value};
+use anyhow::Result;
+use tokio::net::UdpSocket;
+
+/// 单个数据报的最大长度（避免在常见 MTU 下分片）
+const MAX_DATAGRAM: usize = 1432;
+
+/// StatsD 指标导出器
+pub struct StatsdExporter {
+    socket: UdpSocket,
+    addr: String,
+    prefix: String,
+    tags: bool,
+    resource: Resource,
+}
+
+impl StatsdExporter {
+    /// 创建导出器
+    ///
+    /// `addr` 为代理地址（如 `127.0.0.1:8125`），`prefix` 为指标名前缀（默认 `glm`），
+    /// `tags` 为 false 时不附带 DogStatsD 标签。
+    pub async fn new(config: &Config, addr: &str, prefix: &str, tags: bool) -> Result<Self> {
+        let target = tokio::net::lookup_host(addr)
+            .await
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .ok_or_else(|| {
+                CliError::ConfigError(format!("StatsD 地址无效: {}", addr))
+                    .suggest(["请使用 主机:端口 形式的地址，如 127.0.0.1:8125"])
+            })?;
+        let bind = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(bind).await?;
+        socket
+            .connect(target)
+            .await
+            .map_err(|e| CliError::NetworkError(format!("无法连接 StatsD 代理 {}: {}", addr, e)))?;
+        Ok(StatsdExporter {
+            socket,
+            addr: addr.to_string(),
+            prefix: prefix.trim_end_matches('.').to_string(),
+            tags,
+            resource: Resource::from_config(config),
+        })
+    }
+
+    /// 代理地址
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// 生成本次发送的指标行
+    ///
+    /// 请求计数与耗时只包含最近一次查询（StatsD 的计数器由代理按周期累加）。
+    pub fn lines(&self, quota: &[QuotaMetrics], stats: &FetchStats) -> Vec<String> {
+        let mut lines = Vec::new();
+        for item in quota {
+            for gauge in item.gauges() {
+                let value = match gauge.value {
+                    Value::Int(n) => n.to_string(),
+                    Value::Float(x) => x.to_string(),
+                };
+                lines.push(if self.tags {
+                    self.line(
+                        &format!("quota.{}", gauge.name),
+                        &value,
+                        "g",
+                        &[("type", &item.limit_type)],
+                    )
+                } else {
+                    let kind = item.limit_type.to_ascii_lowercase().replace(['.', ' ', ':', '|'], "_");
+                    self.line(&format!("quota.{}.{}", kind, gauge.name), &value, "g", &[])
+                });
+            }
+        }
+
+        if let Some(elapsed) = stats.last_duration {
+            lines.push(self.line("fetch.requests", "1", "c", &[]));
+            lines.push(self.line("fetch.duration", &elapsed.as_millis().to_string(), "ms", &[]));
+        }
+        if let Some(kind) = stats.last_error {
+            lines.push(if self.tags {
+                self.line("fetch.errors", "1", "c", &[("error.type", kind)])
+            } else {
+                self.line(&format!("fetch.errors.{}", kind), "1", "c", &[])
+            });
+        }
+        lines
+    }
+
+    /// 一行 `名称:值|类型[|#标签]`
+    fn line(&self, name: &str, value: &str, kind: &str, tags: &[(&str, &str)]) -> String {
+        let mut line = format!("{}.{}:{}|{}", self.prefix, name, value, kind);
+        if self.tags {
+            let tags: Vec<String> = tags
+                .iter()
+                .copied()
+                .chain(self.resource.tags())
+                .map(|(key, value)| format!("{}:{}", key, value.replace([',', '|', '#'], "_")))
+                .collect();
+            line.push_str("|#");
+            line.push_str(&tags.join(","));
+        }
+        line
+    }
+
+    /// 发送一次指标（按长度合并为若干个数据报）
+    pub async fn export(&self, quota: &[QuotaMetrics], stats: &FetchStats) -> Result<()> {
+        let lines = self.lines(quota, stats);
+        for datagram in pack(&lines) {
+            self.socket
+                .send(datagram.as_bytes())
+                .await
+                .map_err(|e| CliError::NetworkError(format!("发送 StatsD 指标失败: {}\n\n地址: {}", e, self.addr)))?;
+        }
+        tracing::info!(addr = %self.addr, metrics = lines.len(), "已发送 StatsD 指标");
+        Ok(())
+    }
+}
+
+/// 把指标行合并为不超过 [`MAX_DATAGRAM`] 字节的数据报（以换行分隔）
+fn pack(lines: &[String]) -> Vec<String> {
+    let mut datagrams: Vec<String> = Vec::new();
+    for line in lines {
+        match datagrams.last_mut() {
+            Some(datagram) if datagram.len() + 1 + line.len() <= MAX_DATAGRAM => {
+                datagram.push('\n');
+                datagram.push_str(line);
+            }
+            _ => datagrams.push(line.clone()),
+        }
+    }
+    datagrams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Utc};
+    use std::time::Duration;
+
+    fn sample() -> (Vec<QuotaMetrics>, FetchStats) {
+        let now = Utc::now();
+        let data = serde_json::from_value(serde_json::json!({
+            "limits": [{"type": "TOKENS_LIMIT", "usage": 1000, "currentValue": 250}]
+        }))
+        .unwrap();
+        let quota = QuotaMetrics::collect(&data, now, FixedOffset::east_opt(0).unwrap());
+        let mut stats = FetchStats::new(now);
+        stats.record(Duration::from_millis(42), None);
+        (quota, stats)
+    }
+
+    async fn exporter(tags: bool) -> StatsdExporter {
+        let config = Config {
+            api_key: "test_api_key_12345".to_string(),
+            ..Config::default()
+        };
+        StatsdExporter::new(&config, "127.0.0.1:8125", "glm.", tags).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_lines_with_tags() {
+        let (quota, mut stats) = sample();
+        let mut exporter = exporter(true).await;
+        assert_eq!(
+            exporter.lines(&quota, &stats),
+            [
+                "glm.quota.used:250|g|#type:TOKENS_LIMIT,region:cn",
+                "glm.quota.total:1000|g|#type:TOKENS_LIMIT,region:cn",
+                "glm.quota.remaining:750|g|#type:TOKENS_LIMIT,region:cn",
+                "glm.quota.pct:25|g|#type:TOKENS_LIMIT,region:cn",
+                "glm.fetch.requests:1|c|#region:cn",
+                "glm.fetch.duration:42|ms|#region:cn",
+            ]
+        );
+
+        stats.record(Duration::from_millis(7), Some(&CliError::Timeout("超时".to_string())));
+        let lines = exporter.lines(&[], &stats);
+        assert_eq!(lines.last().unwrap(), "glm.fetch.errors:1|c|#error.type:timeout,region:cn");
+
+        exporter.resource.profile = Some("team".to_string());
+        let lines = exporter.lines(&quota, &stats);
+        assert_eq!(lines[0], "glm.quota.used:250|g|#type:TOKENS_LIMIT,region:cn,profile:team");
+    }
+
+    #[tokio::test]
+    async fn test_lines_without_tags() {
+        let (quota, mut stats) = sample();
+        stats.record(Duration::from_millis(7), Some(&CliError::NetworkError("连接失败".to_string())));
+        let lines = exporter(false).await.lines(&quota, &stats);
+        assert_eq!(lines[0], "glm.quota.tokens_limit.used:250|g");
+        assert_eq!(lines.last().unwrap(), "glm.fetch.errors.network:1|c");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_addr() {
+        let config = Config::default();
+        let err = StatsdExporter::new(&config, "no-port", "glm", true).await.err().unwrap();
+        assert_eq!(CliError::exit_code_of(&err), crate::error::exit_code::CONFIG);
+    }
+
+    #[test]
+    fn test_pack_splits_long_batches() {
+        let lines: Vec<String> = (0..100).map(|i| format!("glm.quota.used:{}|g|#type:TOKENS_LIMIT", i)).collect();
+        let datagrams = pack(&lines);
+        assert!(datagrams.len() > 1);
+        assert!(datagrams.iter().all(|datagram| datagram.len() <= MAX_DATAGRAM));
+        assert_eq!(datagrams.join("\n").lines().count(), 100);
+    }
+}
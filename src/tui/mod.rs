@@ -0,0 +1,358 @@
+//! 全屏终端仪表盘（`glm tui`）
+//!
+//! 每个标签页对应一份配置（一个账号或区域）：当前配置，以及 `~/.glm/profiles/`
+//! 下的每份额外配置（见 [`CliConfig::load_profiles`]）。标签页显示全部额度项的进度条、
+//! 距下次重置的倒计时，以及近期快照的使用率走势。数据按间隔自动刷新，
+//! 当前配置成功获取的数据与其他命令一样记录到快照历史。
+//!
+//! 快捷键：`r` 立即刷新，`Tab` / `←` `→` 切换标签页，`↑` `↓` 选择额度项，
+//! `u` 切换百分比与用量显示，`q` / `Esc` 退出。
+
+mod ui;
+
+use crate::api::{ApiData, GlmClient};
+use crate::cli_config::CliConfig;
+use crate::error::CliError;
+use crate::history::{self, Snapshot};
+use crate::output::DisplayOptions;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// 走势图回看的时长（与 Token 额度窗口一致）
+const HISTORY_SPAN: Duration = Duration::hours(crate::forecast::WINDOW_HOURS);
+
+/// 倒计时的刷新间隔
+const TICK: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// 标签页
+pub struct Tab {
+    /// 标签名称
+    pub name: String,
+
+    /// 查询使用的客户端
+    pub client: GlmClient,
+
+    /// 对应的配置
+    pub config: CliConfig,
+}
+
+/// 用量的显示单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Units {
+    /// 使用百分比
+    Percent,
+    /// 已使用量 / 总额度
+    Absolute,
+}
+
+/// 单个标签页的状态
+struct TabState {
+    name: String,
+    client: Arc<GlmClient>,
+    config: CliConfig,
+    data: Option<ApiData>,
+    updated_at: Option<DateTime<Utc>>,
+    error: Option<String>,
+    loading: bool,
+    history: Vec<Snapshot>,
+}
+
+impl TabState {
+    /// 记录一次刷新结果
+    fn apply(&mut self, result: Result<ApiData, CliError>, now: DateTime<Utc>) {
+        self.loading = false;
+        match result {
+            Ok(data) => {
+                if self.config.history {
+                    if let Err(e) = history::history_file_path()
+                        .and_then(|path| history::record(&path, &data, now))
+                    {
+                        tracing::warn!("记录使用情况快照失败: {:#}", e);
+                    }
+                }
+                self.history.push(Snapshot {
+                    taken_at: now,
+                    limits: data.limits.clone(),
+                });
+                self.history.retain(|snapshot| snapshot.taken_at >= now - HISTORY_SPAN);
+                self.data = Some(data);
+                self.updated_at = Some(now);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+}
+
+/// 仪表盘状态
+struct App {
+    tabs: Vec<TabState>,
+    selected: usize,
+    limit: usize,
+    units: Units,
+    display: DisplayOptions,
+    quit: bool,
+}
+
+/// 按键对应的操作
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    None,
+    Refresh,
+}
+
+impl App {
+    fn new(tabs: Vec<Tab>, display: DisplayOptions) -> Self {
+        let since = Utc::now() - HISTORY_SPAN;
+        let tabs = tabs
+            .into_iter()
+            .map(|tab| {
+                let history = if tab.config.history {
+                    history::history_file_path()
+                        .and_then(|path| history::load(&path, since))
+                        .unwrap_or_else(|e| {
+                            tracing::warn!("读取快照历史失败: {:#}", e);
+                            Vec::new()
+                        })
+                } else {
+                    Vec::new()
+                };
+                TabState {
+                    name: tab.name,
+                    client: Arc::new(tab.client),
+                    config: tab.config,
+                    data: None,
+                    updated_at: None,
+                    error: None,
+                    loading: false,
+                    history,
+                }
+            })
+            .collect();
+        App {
+            tabs,
+            selected: 0,
+            limit: 0,
+            units: Units::Percent,
+            display,
+            quit: false,
+        }
+    }
+
+    fn current(&self) -> &TabState {
+        &self.tabs[self.selected]
+    }
+
+    fn select_tab(&mut self, index: usize) {
+        if index < self.tabs.len() && index != self.selected {
+            self.selected = index;
+            self.limit = 0;
+        }
+    }
+
+    /// 处理按键
+    fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::None;
+        }
+        let count = self.tabs.len();
+        let limits = self.current().data.as_ref().map_or(0, |data| data.limits.len());
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Char('r') => return Action::Refresh,
+            KeyCode::Char('u') => {
+                self.units = match self.units {
+                    Units::Percent => Units::Absolute,
+                    Units::Absolute => Units::Percent,
+                }
+            }
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => self.select_tab((self.selected + 1) % count),
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                self.select_tab((self.selected + count - 1) % count)
+            }
+            KeyCode::Char(c @ '1'..='9') => self.select_tab(c as usize - '1' as usize),
+            KeyCode::Down | KeyCode::Char('j') if limits > 0 => self.limit = (self.limit + 1) % limits,
+            KeyCode::Up | KeyCode::Char('k') if limits > 0 => self.limit = (self.limit + limits - 1) % limits,
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+/// 刷新结果：标签页序号与查询结果
+type Refreshed = (usize, Result<ApiData, CliError>);
+
+/// 在后台刷新一个标签页（已有刷新进行中时跳过）
+fn refresh(app: &mut App, index: usize, tx: &mpsc::UnboundedSender<Refreshed>) {
+    let tab = &mut app.tabs[index];
+    if tab.loading {
+        return;
+    }
+    tab.loading = true;
+    let client = Arc::clone(&tab.client);
+    let tx = tx.clone();
+    tokio::spawn(async move {
+        let _ = tx.send((index, client.fetch_usage().await));
+    });
+}
+
+/// 在独立线程中读取终端事件
+fn spawn_input(stop: Arc<AtomicBool>) -> mpsc::UnboundedReceiver<KeyEvent> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            match event::poll(std::time::Duration::from_millis(200)) {
+                Ok(true) => match event::read() {
+                    Ok(Event::Key(key)) => {
+                        if tx.send(key).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(_) => break,
+                },
+                Ok(false) => {}
+                Err(_) => break,
+            }
+        }
+    });
+    rx
+}
+
+/// 运行仪表盘，直到用户退出
+///
+/// `interval` 为自动刷新间隔（秒）。
+pub async fn run(tabs: Vec<Tab>, display: DisplayOptions, interval: u64) -> Result<()> {
+    if tabs.is_empty() {
+        return Err(CliError::ConfigError("没有可显示的配置".to_string()).into());
+    }
+    if !std::io::stdout().is_terminal() {
+        anyhow::bail!("glm tui 需要在交互式终端中运行\n\n建议: 在脚本中请使用 glm watch 或 glm -f json");
+    }
+
+    let mut app = App::new(tabs, display);
+    let (tx, mut results) = mpsc::unbounded_channel();
+    for index in 0..app.tabs.len() {
+        refresh(&mut app, index, &tx);
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut keys = spawn_input(Arc::clone(&stop));
+    let mut terminal = ratatui::init();
+    let mut tick = tokio::time::interval(TICK);
+    let mut auto_refresh = tokio::time::interval(std::time::Duration::from_secs(interval));
+    auto_refresh.tick().await;
+
+    let result = loop {
+        if let Err(e) = terminal.draw(|frame| ui::draw(frame, &app, Utc::now())) {
+            break Err(e.into());
+        }
+        tokio::select! {
+            Some(key) = keys.recv() => {
+                if app.handle_key(key) == Action::Refresh {
+                    let selected = app.selected;
+                    refresh(&mut app, selected, &tx);
+                }
+                if app.quit {
+                    break Ok(());
+                }
+            }
+            Some((index, result)) = results.recv() => app.tabs[index].apply(result, Utc::now()),
+            _ = auto_refresh.tick() => {
+                for index in 0..app.tabs.len() {
+                    refresh(&mut app, index, &tx);
+                }
+            }
+            _ = tick.tick() => {}
+        }
+    };
+
+    stop.store(true, Ordering::Relaxed);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    pub(super) fn sample_app() -> App {
+        let config = CliConfig {
+            history: false,
+            ..CliConfig::from(Config {
+                api_key: "test_api_key_12345".to_string(),
+                ..Config::default()
+            })
+        };
+        let tab = |name: &str| Tab {
+            name: name.to_string(),
+            client: GlmClient::new(config.base.clone()).unwrap(),
+            config: config.clone(),
+        };
+        App::new(vec![tab("cn"), tab("intl")], DisplayOptions::default())
+    }
+
+    pub(super) fn sample_data(reset: DateTime<Utc>) -> ApiData {
+        serde_json::from_value(serde_json::json!({
+            "limits": [
+                {"type": "TIME_LIMIT", "usage": 1000, "currentValue": 100},
+                {"type": "TOKENS_LIMIT", "usage": 50000, "currentValue": 33000, "nextResetTime": reset.timestamp_millis()}
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_handle_key() {
+        let mut app = sample_app();
+        app.tabs[0].apply(Ok(sample_data(Utc::now())), Utc::now());
+
+        assert_eq!(app.handle_key(press(KeyCode::Char('r'))), Action::Refresh);
+        app.handle_key(press(KeyCode::Char('u')));
+        assert_eq!(app.units, Units::Absolute);
+
+        app.handle_key(press(KeyCode::Down));
+        assert_eq!(app.limit, 1);
+        app.handle_key(press(KeyCode::Down));
+        assert_eq!(app.limit, 0);
+
+        app.handle_key(press(KeyCode::Tab));
+        assert_eq!(app.selected, 1);
+        app.handle_key(press(KeyCode::Tab));
+        assert_eq!(app.selected, 0);
+        app.handle_key(press(KeyCode::Char('2')));
+        assert_eq!(app.selected, 1);
+        app.handle_key(press(KeyCode::Char('9')));
+        assert_eq!(app.selected, 1);
+
+        assert!(!app.quit);
+        app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn test_apply_keeps_recent_history() {
+        let mut app = sample_app();
+        let now = Utc::now();
+        let tab = &mut app.tabs[0];
+        tab.apply(Ok(sample_data(now)), now - Duration::hours(6));
+        tab.apply(Ok(sample_data(now)), now);
+        assert_eq!(tab.history.len(), 1);
+        assert!(tab.error.is_none());
+
+        tab.apply(Err(CliError::NetworkError("连接失败".to_string())), now);
+        assert!(tab.error.as_deref().unwrap().contains("连接失败"));
+        assert!(tab.data.is_some());
+    }
+}
@@ -0,0 +1,232 @@
+//! 仪表盘绘制
+
+use super::{App, Units};
+use crate::api::{ApiPlan, LimitItem, PLAN_QUOTA};
+use crate::quota::next_reset_at;
+use crate::output::format_relative;
+use crate::output::table::{format_limit_used, limit_title};
+use chrono::{DateTime, Utc};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Gauge, Paragraph, Sparkline, Tabs};
+use ratatui::Frame;
+
+/// 每个额度项占用的行数
+const LIMIT_HEIGHT: u16 = 4;
+
+/// 绘制整个仪表盘
+pub(super) fn draw(frame: &mut Frame, app: &App, now: DateTime<Utc>) {
+    let [tabs, limits, chart, status] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(LIMIT_HEIGHT),
+        Constraint::Length(7),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    draw_tabs(frame, app, tabs);
+    draw_limits(frame, app, limits, now);
+    draw_chart(frame, app, chart);
+    draw_status(frame, app, status);
+}
+
+/// 使用率对应的颜色
+fn usage_color(percentage: f64) -> Color {
+    match percentage {
+        p if p >= 90.0 => Color::Red,
+        p if p >= 70.0 => Color::Yellow,
+        _ => Color::Green,
+    }
+}
+
+fn draw_tabs(frame: &mut Frame, app: &App, area: Rect) {
+    let titles = app
+        .tabs
+        .iter()
+        .enumerate()
+        .map(|(i, tab)| format!("{} {}", i + 1, tab.name));
+    let tabs = Tabs::new(titles)
+        .select(app.selected)
+        .block(Block::default().borders(Borders::ALL).title(" glm "))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED));
+    frame.render_widget(tabs, area);
+}
+
+fn draw_limits(frame: &mut Frame, app: &App, area: Rect, now: DateTime<Utc>) {
+    let lang = app.display.language;
+    let tab = app.current();
+    let Some(data) = &tab.data else {
+        let text = match &tab.error {
+            Some(error) => error.clone(),
+            None => lang.pick("正在获取使用情况…", "Loading usage…").to_string(),
+        };
+        frame.render_widget(Paragraph::new(text).block(Block::default().borders(Borders::ALL)), area);
+        return;
+    };
+
+    let rows = Layout::vertical(data.limits.iter().map(|_| Constraint::Length(LIMIT_HEIGHT)))
+        .split(area);
+    for (i, (limit, row)) in data.limits.iter().zip(rows.iter()).enumerate() {
+        draw_limit(frame, app, limit, *row, i == app.limit, now);
+    }
+}
+
+fn draw_limit(frame: &mut Frame, app: &App, limit: &LimitItem, area: Rect, selected: bool, now: DateTime<Utc>) {
+    let lang = app.display.language;
+    let plan = (limit.limit_type == PLAN_QUOTA).then(|| ApiPlan::from_limit_item(limit));
+    let border = if selected {
+        Style::default().add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(border)
+        .title(limit_title(limit, lang));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let [gauge, info] = Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).areas(inner);
+
+    let label = match app.units {
+        Units::Percent => format!("{:.1}%", limit.percentage),
+        Units::Absolute => format_limit_used(limit, plan.as_ref(), lang),
+    };
+    frame.render_widget(
+        Gauge::default()
+            .gauge_style(Style::default().fg(usage_color(limit.percentage)))
+            .ratio((limit.percentage / 100.0).clamp(0.0, 1.0))
+            .label(label),
+        gauge,
+    );
+
+    let remaining = match app.units {
+        Units::Percent => format!("{:.1}%", (100.0 - limit.percentage).max(0.0)),
+        Units::Absolute => limit.remaining.to_string(),
+    };
+    let mut spans = vec![Span::raw(format!("{}{}", lang.pick("剩余 ", "Remaining "), remaining))];
+    if let Some(reset) = next_reset_at(limit, now, app.current().config.region.reset_offset()) {
+        let relative = format_relative(reset, now, lang);
+        let at = app.display.timezone.format_datetime(reset);
+        spans.push(Span::raw(" · "));
+        spans.push(Span::styled(
+            match lang {
+                crate::config::Language::Zh => format!("{}重置（{}）", relative, at),
+                crate::config::Language::En => format!("resets {} ({})", relative, at),
+            },
+            Style::default().fg(Color::Cyan),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), info);
+}
+
+fn draw_chart(frame: &mut Frame, app: &App, area: Rect) {
+    let lang = app.display.language;
+    let tab = app.current();
+    let Some(limit) = tab.data.as_ref().and_then(|data| data.limits.get(app.limit)) else {
+        frame.render_widget(Block::default().borders(Borders::ALL), area);
+        return;
+    };
+
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        "{}{}",
+        limit_title(limit, lang),
+        lang.pick(" 使用率走势（近 5 小时）", " usage trend (last 5 hours)")
+    ));
+    let width = block.inner(area).width as usize;
+    let points: Vec<u64> = tab
+        .history
+        .iter()
+        .filter_map(|snapshot| snapshot.limit(&limit.limit_type))
+        .map(|item| item.percentage.round().clamp(0.0, 100.0) as u64)
+        .collect();
+    let points = &points[points.len().saturating_sub(width)..];
+    frame.render_widget(
+        Sparkline::default()
+            .block(block)
+            .data(points)
+            .max(100)
+            .style(Style::default().fg(usage_color(limit.percentage))),
+        area,
+    );
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let lang = app.display.language;
+    let tab = app.current();
+    let status = if tab.loading {
+        Span::styled(lang.pick("刷新中…", "Refreshing…"), Style::default().fg(Color::Yellow))
+    } else if let Some(error) = &tab.error {
+        let first_line = error.lines().next().unwrap_or_default();
+        Span::styled(
+            format!("{}{}", lang.pick("刷新失败: ", "Refresh failed: "), first_line),
+            Style::default().fg(Color::Red),
+        )
+    } else if let Some(updated_at) = tab.updated_at {
+        Span::raw(format!(
+            "{}{}",
+            lang.pick("更新于 ", "Updated "),
+            app.display.timezone.convert(updated_at).format("%H:%M:%S")
+        ))
+    } else {
+        Span::raw("")
+    };
+    let help = Span::styled(
+        lang.pick(
+            "  r 刷新 · Tab 切换 · ↑↓ 选择 · u 单位 · q 退出",
+            "  r refresh · Tab switch · ↑↓ select · u units · q quit",
+        ),
+        Style::default().fg(Color::DarkGray),
+    );
+    frame.render_widget(Paragraph::new(Line::from(vec![status, help])), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{sample_app, sample_data};
+    use super::*;
+    use crate::config::Language;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn render(app: &App, now: DateTime<Utc>) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(90, 24)).unwrap();
+        terminal.draw(|frame| draw(frame, app, now)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_draw_dashboard() {
+        let now = Utc::now();
+        let mut app = sample_app();
+        app.display.language = Language::En;
+        let screen = render(&app, now);
+        assert!(screen.contains("1 cn"));
+        assert!(screen.contains("2 intl"));
+        assert!(screen.contains("Loading usage"));
+
+        let reset = now + chrono::Duration::minutes(83) + chrono::Duration::seconds(30);
+        app.tabs[0].apply(Ok(sample_data(reset)), now);
+        app.limit = 1;
+        let screen = render(&app, now);
+        assert!(screen.contains("5-hour usage limit"));
+        assert!(screen.contains("66.0%"));
+        assert!(screen.contains("resets in 1h 23m"));
+        assert!(screen.contains("MCP monthly quota"));
+        assert!(screen.contains("usage trend"));
+        assert!(screen.contains("Updated"));
+
+        app.units = Units::Absolute;
+        let screen = render(&app, now);
+        assert!(screen.contains("33.0K / 50.0K tokens"));
+        assert!(screen.contains("Remaining 17000"));
+    }
+}
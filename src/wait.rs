@@ -0,0 +1,217 @@
+//! 等待额度恢复
+//!
+//! `glm wait --until-reset` 睡眠到 Token 额度窗口重置（再加一段安全余量），
+//! `glm wait --until-below 50%` 按退避间隔轮询，直到使用率降到目标以下。
+//! 超过 `--timeout` 仍未满足条件时返回超时错误，便于批处理脚本判断。
+
+use crate::api::{GlmClient, LimitItem};
+use crate::error::CliError;
+use crate::guard::guarded_limit;
+use crate::output::format_duration;
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// 轮询的初始间隔
+const INITIAL_POLL: Duration = Duration::from_secs(30);
+
+/// 轮询的最大间隔
+const MAX_POLL: Duration = Duration::from_secs(300);
+
+/// 等待窗口重置时输出进度的间隔
+const PROGRESS_EVERY: Duration = Duration::from_secs(300);
+
+/// 等待条件
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WaitTarget {
+    /// 等到当前窗口重置
+    Reset,
+    /// 等到使用率低于指定百分比
+    Below(f64),
+}
+
+/// 解析使用率百分比（如 `50%` 或 `50`）
+pub fn parse_percentage(s: &str) -> Result<f64, String> {
+    let s = s.trim();
+    match s.strip_suffix('%').unwrap_or(s).trim().parse::<f64>() {
+        Ok(p) if (0.0..=100.0).contains(&p) => Ok(p),
+        _ => Err(format!("无效的百分比: {}（应在 0%-100% 之间）", s)),
+    }
+}
+
+/// 解析时长（如 `90`、`90s`、`30m`、`2h`，纯数字按秒计算）
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let multiplier = match unit.trim() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => return Err(format!("无效的时长: {}（示例: 90s、30m、2h）", s)),
+    };
+    let number = number
+        .parse::<u64>()
+        .map_err(|_| format!("无效的时长: {}（示例: 90s、30m、2h）", s))?;
+    number
+        .checked_mul(multiplier)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("时长超出范围: {}", s))
+}
+
+/// 额度项的重置时间
+fn reset_at(item: &LimitItem) -> Option<DateTime<Utc>> {
+    item.next_reset_time.and_then(DateTime::from_timestamp_millis)
+}
+
+/// 等待条件满足
+///
+/// `margin` 为窗口重置后额外等待的时间；`timeout` 为最长等待时间，超过后返回
+/// [`CliError::Timeout`]。进度通过 `progress` 回调输出（调用方通常写到标准错误）。
+pub async fn run(
+    client: &GlmClient,
+    target: WaitTarget,
+    margin: Duration,
+    timeout: Option<Duration>,
+    progress: &mut dyn FnMut(String),
+) -> Result<()> {
+    let deadline = match timeout {
+        Some(t) => Some(Instant::now().checked_add(t).ok_or_else(|| {
+            CliError::ConfigError(format!("等待时限超出范围: {}", format_duration(t)))
+                .suggest(["将 --timeout 设置为合理的时长，如 2h"])
+        })?),
+        None => None,
+    };
+    let timed_out = |what: String| -> anyhow::Error {
+        CliError::Timeout(what).suggest(["增大 --timeout，或稍后重试"]).into()
+    };
+
+    match target {
+        WaitTarget::Reset => {
+            let data = client.fetch_usage().await?;
+            let reset = guarded_limit(&data).and_then(reset_at).ok_or_else(|| {
+                CliError::ValidationError("响应中没有 Token 额度的下次重置时间，无法等待窗口重置".to_string())
+            })?;
+            let until = (reset - Utc::now())
+                .to_std()
+                .unwrap_or_default()
+                .checked_add(margin)
+                .and_then(|wait| Instant::now().checked_add(wait))
+                .ok_or_else(|| {
+                    CliError::ConfigError("等待时长超出范围".to_string())
+                        .suggest(["减小 --margin"])
+                })?;
+            if deadline.is_some_and(|d| until > d) {
+                return Err(timed_out(format!(
+                    "额度窗口将于 {} 重置，超出等待时限",
+                    reset.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+                )));
+            }
+
+            loop {
+                let remaining = until.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                progress(format!(
+                    "等待额度窗口重置（{}，还需 {}）",
+                    reset.with_timezone(&Local).format("%H:%M:%S"),
+                    format_duration(remaining)
+                ));
+                tokio::time::sleep(remaining.min(PROGRESS_EVERY)).await;
+            }
+            progress("额度窗口已重置".to_string());
+            Ok(())
+        }
+        WaitTarget::Below(threshold) => {
+            let mut interval = INITIAL_POLL;
+            loop {
+                match client.fetch_usage().await {
+                    Ok(data) => {
+                        let Some(item) = guarded_limit(&data) else {
+                            return Err(CliError::ValidationError(
+                                "响应中没有 Token 额度信息，无法判断使用率".to_string(),
+                            )
+                            .into());
+                        };
+                        if item.percentage < threshold {
+                            progress(format!("当前使用 {:.1}%，已低于 {}%", item.percentage, threshold));
+                            return Ok(());
+                        }
+                        // 窗口重置时使用率会回落，不必睡过重置时间
+                        if let Some(until_reset) = reset_at(item)
+                            .and_then(|reset| (reset - Utc::now()).to_std().ok())
+                        {
+                            interval = interval.min(until_reset.saturating_add(margin));
+                        }
+                        progress(format!(
+                            "当前使用 {:.1}%，目标低于 {}%，{} 后重新检查",
+                            item.percentage,
+                            threshold,
+                            format_duration(interval)
+                        ));
+                    }
+                    Err(e) if e.retryable() => progress(format!(
+                        "获取使用情况失败: {:#}，{} 后重试",
+                        e,
+                        format_duration(interval)
+                    )),
+                    Err(e) => return Err(e.into()),
+                }
+
+                let now = Instant::now();
+                if let Some(deadline) = deadline {
+                    if now >= deadline {
+                        return Err(timed_out(format!("等待使用率低于 {}% 超时", threshold)));
+                    }
+                    interval = interval.min(deadline - now);
+                }
+                tokio::time::sleep(interval).await;
+                interval = interval.mul_f64(1.5).clamp(INITIAL_POLL, MAX_POLL);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_percentage() {
+        assert_eq!(parse_percentage("50%"), Ok(50.0));
+        assert_eq!(parse_percentage("12.5"), Ok(12.5));
+        assert!(parse_percentage("120%").is_err());
+        assert!(parse_percentage("half").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(1800)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert!(parse_duration("2d").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("9999999999999999h").is_err());
+        assert_eq!(parse_duration("18446744073709551615"), Ok(Duration::from_secs(u64::MAX)));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_out_of_range() {
+        let config = crate::config::Config {
+            api_key: "test_api_key_12345".to_string(),
+            api_url: "http://127.0.0.1:9/api".to_string(),
+            ..crate::config::Config::default()
+        };
+        let client = GlmClient::new(config).unwrap();
+        let timeout = parse_duration("18446744073709551615").unwrap();
+        let err = run(&client, WaitTarget::Below(50.0), Duration::ZERO, Some(timeout), &mut |_| {})
+            .await
+            .unwrap_err();
+        assert_eq!(CliError::exit_code_of(&err), crate::error::exit_code::CONFIG);
+    }
+}
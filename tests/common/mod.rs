@@ -0,0 +1,234 @@
+//! 集成测试公共工具
+//!
+//! 提供一个基于 tokio 的本地假 HTTP 服务，用于替代真实的 GLM API。
+
+#![allow(dead_code)]
+
+use std::net::SocketAddr;
+use std::process::{Command, Output};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// 假服务收到的请求
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    /// 请求方法
+    pub method: String,
+    /// 请求路径（含查询参数）
+    pub path: String,
+    /// 请求头（名称均为小写）
+    pub headers: Vec<(String, String)>,
+    /// 请求体
+    pub body: String,
+}
+
+impl RecordedRequest {
+    /// 获取指定请求头的值
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+type Responder = dyn Fn(&RecordedRequest) -> (u16, String) + Send + Sync;
+
+/// 本地假 HTTP 服务
+pub struct FakeServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl FakeServer {
+    /// 启动一个对所有请求返回固定状态码和响应体的服务
+    pub async fn start(status: u16, body: impl Into<String>) -> Self {
+        let body = body.into();
+        Self::start_with(move |_| (status, body.clone())).await
+    }
+
+    /// 启动一个按请求动态生成响应的服务
+    pub async fn start_with<F>(responder: F) -> Self
+    where
+        F: Fn(&RecordedRequest) -> (u16, String) + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let responder: Arc<Responder> = Arc::new(responder);
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    break;
+                };
+                let recorded = recorded.clone();
+                let responder = responder.clone();
+                tokio::spawn(async move {
+                    let Some(request) = read_request(&mut stream).await else {
+                        return;
+                    };
+                    let (status, body) = responder(&request);
+                    recorded.lock().unwrap().push(request);
+                    let response = format!(
+                        "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+
+        FakeServer { addr, requests }
+    }
+
+    /// 服务根地址，如 `http://127.0.0.1:12345`
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// 服务监听地址
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 已收到的全部请求
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// 读取并解析一个 HTTP/1.1 请求
+async fn read_request(stream: &mut tokio::net::TcpStream) -> Option<RecordedRequest> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+        .collect();
+
+    let content_length = headers
+        .iter()
+        .find(|(k, _)| k == "content-length")
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+
+    Some(RecordedRequest {
+        method,
+        path,
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+    })
+}
+
+/// 一个典型的额度查询成功响应
+pub fn quota_response_body() -> String {
+    serde_json::json!({
+        "code": 200,
+        "msg": "操作成功",
+        "success": true,
+        "data": {
+            "limits": [
+                {
+                    "type": "TIME_LIMIT",
+                    "unit": 5,
+                    "number": 1,
+                    "usage": 1000,
+                    "currentValue": 164,
+                    "remaining": 836,
+                    "percentage": 16
+                },
+                {
+                    "type": "TOKENS_LIMIT",
+                    "unit": 3,
+                    "number": 5,
+                    "usage": 200000000,
+                    "currentValue": 132374032,
+                    "remaining": 67625968,
+                    "percentage": 66,
+                    "nextResetTime": 4102444800000i64
+                }
+            ]
+        }
+    })
+    .to_string()
+}
+
+/// 在隔离的 HOME 目录中运行 `glm` 二进制
+///
+/// 清空继承的环境变量，仅设置 `HOME` 与传入的变量。
+pub async fn run_glm(args: &[&str], envs: &[(&str, &str)]) -> Output {
+    static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let home = std::env::temp_dir().join(format!(
+        "glm-test-home-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+    ));
+    std::fs::create_dir_all(&home).unwrap();
+
+    let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+    let envs: Vec<(String, String)> = envs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    tokio::task::spawn_blocking(move || {
+        Command::new(env!("CARGO_BIN_EXE_glm"))
+            .args(&args)
+            .env_clear()
+            .env("HOME", &home)
+            .envs(envs)
+            .output()
+            .unwrap()
+    })
+    .await
+    .unwrap()
+}
+
+/// 计划接口（`/paas/v4/plans`）的成功响应体
+pub fn plans_response_body() -> String {
+    serde_json::json!({
+        "code": 200,
+        "msg": "success",
+        "data": {
+            "plan_id": "premium_plan",
+            "plan_name": "高级版",
+            "total_quota": 1000000,
+            "used_quota": 250000,
+            "remaining_quota": 750000,
+            "usage_percentage": 25.0,
+            "start_date": "2026-01-01T00:00:00Z",
+            "end_date": "2099-12-31T23:59:59Z",
+            "token_type": "tokens"
+        }
+    })
+    .to_string()
+}
@@ -0,0 +1,1081 @@
+//! 针对本地假服务的端到端测试
+
+mod common;
+
+use common::{plans_response_body, quota_response_body, run_glm, FakeServer};
+use glm::api::GlmClient;
+use glm::config::Config;
+
+fn local_config(server: &FakeServer) -> Config {
+    Config {
+        api_key: "test_api_key_12345".to_string(),
+        api_url: server.url(),
+        ..Config::default()
+    }
+}
+
+#[tokio::test]
+async fn test_fetch_usage_from_local_server() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let config = local_config(&server);
+    assert!(config.validate().is_ok());
+
+    let client = GlmClient::new(config).unwrap();
+    let data = client.fetch_usage().await.unwrap();
+    assert_eq!(data.limits.len(), 2);
+    assert_eq!(data.limits[1].limit_type, "TOKENS_LIMIT");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/monitor/usage/quota/limit");
+    assert_eq!(
+        requests[0].header("authorization"),
+        Some("Bearer test_api_key_12345")
+    );
+}
+
+#[tokio::test]
+async fn test_library_builder_fetch_quota() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let client = glm::GlmClient::builder()
+        .api_key("test_api_key_12345")
+        .api_url(server.url())
+        .timeout(std::time::Duration::from_millis(1500))
+        .http_client(reqwest::Client::new())
+        .build()
+        .unwrap();
+    assert_eq!(client.config().timeout, 2);
+
+    let quota = client.fetch_quota().await.unwrap();
+    let tokens = quota.tokens().unwrap();
+    assert_eq!(tokens.kind, glm::QuotaKind::Tokens);
+    assert!(tokens.resets_at.is_some());
+    assert!(quota.mcp_calls().is_some());
+
+    let err = glm::GlmClient::builder().api_key("short").build().err().unwrap();
+    assert_eq!(err.kind(), "config");
+
+    let server = FakeServer::start(401, r#"{"code":401,"msg":"令牌已过期"}"#).await;
+    let client = glm::GlmClient::builder()
+        .api_key("test_api_key_12345")
+        .api_url(server.url())
+        .build()
+        .unwrap();
+    let err: glm::CliError = client.fetch_quota().await.unwrap_err();
+    assert_eq!(err.exit_code(), glm::error::exit_code::AUTH);
+}
+
+#[cfg(feature = "blocking")]
+#[tokio::test]
+async fn test_blocking_client_fetch_usage() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let url = server.url();
+    let data = tokio::task::spawn_blocking(move || {
+        let client = glm::GlmClient::builder()
+            .api_key("test_api_key_12345")
+            .api_url(url)
+            .build_blocking()
+            .unwrap();
+        client.fetch_usage()
+    })
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(data.limits[1].limit_type, "TOKENS_LIMIT");
+
+    let server = FakeServer::start(429, r#"{"code":1302,"msg":"请求过于频繁"}"#).await;
+    let url = server.url();
+    let err = tokio::task::spawn_blocking(move || {
+        glm::GlmClient::builder()
+            .api_key("test_api_key_12345")
+            .api_url(url)
+            .build_blocking()
+            .unwrap()
+            .fetch_quota()
+            .unwrap_err()
+    })
+    .await
+    .unwrap();
+    assert_eq!(err.kind(), "rate_limit");
+    assert!(err.retryable());
+}
+
+#[tokio::test]
+async fn test_binary_against_local_server() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let url = server.url();
+    let output = run_glm(
+        &[],
+        &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &url)],
+    )
+    .await;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(stdout.contains("每5小时使用限额"));
+    assert!(stdout.contains("66%"));
+    assert!(stderr.contains("明文 HTTP"));
+}
+
+#[tokio::test]
+async fn test_reset_times_in_display_timezone() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let url = server.url();
+    let envs = [("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", url.as_str())];
+
+    let output = run_glm(&["--utc"], &envs).await;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("重置时间：2100-01-01 00:00 UTC（"), "stdout: {}", stdout);
+    assert!(stdout.contains("d "), "stdout: {}", stdout);
+
+    let output = run_glm(&["--tz", "+08:00", "-f", "json"], &envs).await;
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value["timezone"], "UTC+08:00");
+    assert_eq!(value["limits"][1]["resetAt"], "2100-01-01T08:00:00+08:00");
+    assert!(value["limits"][1]["resetsInSeconds"].as_i64().unwrap() > 0);
+    assert!(value["limits"][0]["resetAt"].as_str().unwrap().ends_with("T00:00:00+08:00"));
+
+    let output = run_glm(&["--tz", "Nowhere/Bad"], &envs).await;
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = run_glm(&[], &[envs[0], envs[1], ("GLM_TIMEZONE", "Nowhere/Bad")]).await;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("未知的时区"));
+}
+
+#[tokio::test]
+async fn test_doctor_json_against_local_server() {
+    let server = FakeServer::start(401, r#"{"code":401,"msg":"令牌已过期"}"#).await;
+    let url = server.url();
+    let output = run_glm(
+        &["doctor", "--json"],
+        &[
+            ("GLM_API_KEY", "abcdef.test_api_key_12345"),
+            ("GLM_API_URL", &url),
+        ],
+    )
+    .await;
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("test_api_key_12345"));
+    let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let status_of = |id: &str| {
+        report["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["id"] == id)
+            .map(|c| c["status"].as_str().unwrap().to_string())
+            .unwrap()
+    };
+    assert_eq!(status_of("config"), "warn");
+    assert_eq!(status_of("key_format"), "pass");
+    assert_eq!(status_of("dns"), "pass");
+    assert_eq!(status_of("reachability"), "pass");
+    assert_eq!(status_of("auth"), "fail");
+    assert_eq!(status_of("payload"), "skip");
+}
+
+#[tokio::test]
+async fn test_verbose_logging_redacts_key() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let url = server.url();
+    let output = run_glm(
+        &["-vv"],
+        &[("GLM_API_KEY", "abcdef.secret_key_9876"), ("GLM_API_URL", &url)],
+    )
+    .await;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(stderr.contains("发送请求"));
+    assert!(stderr.contains("Bearer abcde****9876"));
+    assert!(stderr.contains("status=200"));
+    assert!(!stderr.contains("secret_key"));
+}
+
+#[tokio::test]
+async fn test_debug_logs_raw_body_on_parse_failure() {
+    let server = FakeServer::start(200, r#"{"code":200,"unexpected":true}"#).await;
+    let url = server.url();
+    let log_file = std::env::temp_dir().join(format!("glm-debug-{}.log", std::process::id()));
+    let log_path = log_file.to_string_lossy().to_string();
+    let output = run_glm(
+        &["--debug", "--log-file", &log_path],
+        &[("GLM_API_KEY", "abcdef.secret_key_9876"), ("GLM_API_URL", &url)],
+    )
+    .await;
+
+    assert!(!output.status.success());
+    let log = std::fs::read_to_string(&log_file).unwrap();
+    let _ = std::fs::remove_file(&log_file);
+    assert!(log.contains("解析 API 响应失败"));
+    assert!(log.contains("unexpected"));
+    assert!(!log.contains("secret_key"));
+}
+
+#[tokio::test]
+async fn test_exit_codes_from_local_server() {
+    let cases = [
+        (401, 5),
+        (403, 5),
+        (429, 6),
+        (503, 7),
+        (404, 9),
+    ];
+    for (status, expected) in cases {
+        let server = FakeServer::start(status, r#"{"code":0,"msg":"错误"}"#).await;
+        let url = server.url();
+        let output = run_glm(
+            &[],
+            &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &url)],
+        )
+        .await;
+        assert_eq!(output.status.code(), Some(expected), "HTTP {}", status);
+    }
+
+    let server = FakeServer::start(200, "not json").await;
+    let url = server.url();
+    let output = run_glm(
+        &[],
+        &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &url)],
+    )
+    .await;
+    assert_eq!(output.status.code(), Some(8));
+}
+
+#[tokio::test]
+async fn test_exit_code_for_missing_config_and_network() {
+    let output = run_glm(&[], &[]).await;
+    assert_eq!(output.status.code(), Some(3));
+
+    // 绑定后立即释放端口，确保连接被拒绝
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let url = format!("http://127.0.0.1:{}", port);
+    let output = run_glm(
+        &[],
+        &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &url)],
+    )
+    .await;
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[tokio::test]
+async fn test_json_error_format() {
+    let server = FakeServer::start(401, r#"{"code":401,"msg":"令牌已过期"}"#).await;
+    let url = server.url();
+    let output = run_glm(
+        &["--error-format", "json"],
+        &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &url)],
+    )
+    .await;
+
+    assert_eq!(output.status.code(), Some(5));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let line = stderr.lines().last().unwrap();
+    let doc: serde_json::Value = serde_json::from_str(line).unwrap();
+    assert_eq!(doc["error"]["kind"], "auth");
+    assert_eq!(doc["error"]["http_status"], 401);
+    assert_eq!(doc["error"]["upstream_msg"], "令牌已过期");
+    assert_eq!(doc["error"]["retryable"], false);
+    assert!(!doc["error"]["suggestions"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_json_error_format_for_usage_error() {
+    let output = run_glm(&["--error-format", "json", "--bogus"], &[]).await;
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let doc: serde_json::Value = serde_json::from_str(stderr.trim()).unwrap();
+    assert_eq!(doc["error"]["kind"], "usage");
+    assert_eq!(doc["error"]["exit_code"], 2);
+    assert!(doc["error"]["message"].as_str().unwrap().contains("--bogus"));
+    assert!(!doc["error"]["suggestions"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_business_error_in_http_200() {
+    let body = r#"{"code":1001,"msg":"令牌无效","success":false,"requestId":"req-abc"}"#;
+    let server = FakeServer::start(200, body).await;
+    let url = server.url();
+    let output = run_glm(
+        &["--error-format", "json"],
+        &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &url)],
+    )
+    .await;
+
+    assert_eq!(output.status.code(), Some(5));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let doc: serde_json::Value = serde_json::from_str(stderr.lines().last().unwrap()).unwrap();
+    assert_eq!(doc["error"]["kind"], "auth");
+    assert_eq!(doc["error"]["upstream_code"], "1001");
+    assert_eq!(doc["error"]["request_id"], "req-abc");
+    assert_eq!(doc["error"]["upstream_msg"], "令牌无效");
+}
+
+#[tokio::test]
+async fn test_lenient_and_strict_schema() {
+    let body = serde_json::json!({
+        "code": 200,
+        "msg": "操作成功",
+        "success": true,
+        "data": {
+            "limits": [{
+                "type": "TOKENS_LIMIT",
+                "unit": 3,
+                "number": 5,
+                "usage": "200000000",
+                "currentValue": 132374032,
+                "percentage": null,
+                "nextResetTime": 1768328328345i64,
+                "windowId": "w-1"
+            }]
+        }
+    })
+    .to_string();
+    let server = FakeServer::start(200, body).await;
+    let url = server.url();
+    let envs = [("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", url.as_str())];
+
+    // 宽松模式：推算缺失字段
+    let output = run_glm(&[], &envs).await;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("66%"));
+
+    // 严格模式：逐字段报告差异
+    let output = run_glm(&["--strict-schema"], &envs).await;
+    assert_eq!(output.status.code(), Some(8));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("data.limits[0].usage: 期望整数"));
+    assert!(stderr.contains("data.limits[0].remaining: 缺少必填字段"));
+    assert!(stderr.contains("data.limits[0].percentage: 期望数字，实际为 null"));
+    assert!(stderr.contains("data.limits[0].windowId: 未知字段"));
+}
+
+#[tokio::test]
+async fn test_consistency_warnings_and_strict() {
+    let body = serde_json::json!({
+        "code": 200,
+        "msg": "操作成功",
+        "success": true,
+        "data": {
+            "limits": [{
+                "type": "TOKENS_LIMIT",
+                "unit": 3,
+                "number": 5,
+                "usage": 1000,
+                "currentValue": 600,
+                "remaining": 100,
+                "percentage": 10,
+                "nextResetTime": 1000i64
+            }]
+        }
+    })
+    .to_string();
+    let server = FakeServer::start(200, body).await;
+    let url = server.url();
+    let envs = [("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", url.as_str())];
+
+    // 表格输出：附加警告段落
+    let output = run_glm(&[], &envs).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("数据一致性警告"));
+    assert!(stdout.contains("不等于总额度"));
+
+    // JSON 输出：warnings 字段
+    let output = run_glm(&["--format", "json"], &envs).await;
+    assert!(output.status.success());
+    let doc: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let warnings = doc["warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 3);
+    assert!(warnings[1].as_str().unwrap().contains("60.0%"));
+    assert!(warnings[2].as_str().unwrap().contains("已过去"));
+
+    // 严格模式：校验失败
+    let output = run_glm(&["--strict"], &envs).await;
+    assert_eq!(output.status.code(), Some(8));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("数据不一致"));
+
+    // 其他子命令同样在查询后校验
+    let output = run_glm(&["--strict", "alert", "--dry-run"], &envs).await;
+    assert_eq!(output.status.code(), Some(8));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("数据不一致"));
+}
+
+#[tokio::test]
+async fn test_consistent_data_has_no_warnings() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let url = server.url();
+    let output = run_glm(
+        &["--strict", "-f", "json"],
+        &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &url)],
+    )
+    .await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let doc: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(doc["warnings"], serde_json::json!([]));
+    assert_eq!(doc["limits"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_plans_flavor_auto_detected() {
+    let server = FakeServer::start(200, plans_response_body()).await;
+    let url = format!("{}/api/paas/v4", server.url());
+    let envs = [("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", url.as_str())];
+
+    // 默认查询：订阅额度经由通用输出
+    let output = run_glm(&["--strict", "--strict-schema"], &envs).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("高级版"));
+    assert!(stdout.contains("25%"));
+
+    // glm plan：订阅名称与有效期
+    let output = run_glm(&["plan"], &envs).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("订阅计划：高级版（premium_plan）"));
+    assert!(stdout.contains("2099-12-31") || stdout.contains("2100-01-01"));
+
+    let output = run_glm(&["plan", "-f", "json"], &envs).await;
+    let doc: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(doc["plan_id"], "premium_plan");
+    assert_eq!(doc["total_quota"], 1000000);
+
+    let requests = server.requests();
+    assert!(requests.iter().all(|r| r.path == "/api/paas/v4/plans"));
+}
+
+#[tokio::test]
+async fn test_plan_command_on_monitor_flavor() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let url = server.url();
+    let output = run_glm(
+        &["plan"],
+        &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &url)],
+    )
+    .await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("订阅计划：Token 额度"));
+    assert!(stdout.contains("接口未提供"));
+    assert_eq!(server.requests()[0].path, "/monitor/usage/quota/limit");
+}
+
+#[tokio::test]
+async fn test_region_intl() {
+    // 未显式设置 api_url 时使用国际站地址
+    let output = run_glm(&["config", "--region", "intl"], &[("GLM_API_KEY", "test_api_key_12345")]).await;
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("https://api.z.ai/api"));
+    assert!(stdout.contains("区域：intl"));
+
+    // 显式设置的 api_url 保持不变，显示语言切换为英文
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let url = server.url();
+    let output = run_glm(
+        &["--region", "intl"],
+        &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &url)],
+    )
+    .await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("5-hour usage limit"));
+    assert!(stdout.contains("Last updated: "));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_usage_breakdown_by_model() {
+    let body = serde_json::json!({
+        "code": 200,
+        "msg": "操作成功",
+        "success": true,
+        "data": {"list": [
+            {"modelName": "glm-4.6", "totalTokens": 7500, "callCount": 3},
+            {"modelName": "glm-4.5-air", "totalTokens": 2500, "callCount": 1}
+        ]}
+    })
+    .to_string();
+    let server = FakeServer::start(200, body).await;
+    let url = server.url();
+    let envs = [("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", url.as_str())];
+
+    let output = run_glm(&["usage", "--by", "model", "--from", "2026-10-01", "--to", "2026-10-07"], &envs).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("按模型统计用量（2026-10-01 至 2026-10-07）"));
+    assert!(stdout.contains("75.0%"));
+
+    let request = &server.requests()[0];
+    assert!(request.path.starts_with("/monitor/usage/model-usage?"));
+    assert!(request.path.contains("startTime=2026-10-01+00%3A00%3A00"));
+    assert!(request.path.contains("endTime=2026-10-07+23%3A59%3A59"));
+
+    let output = run_glm(&["usage", "-f", "json", "--from", "2026-10-01", "--to", "2026-10-07"], &envs).await;
+    let doc: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(doc["by"], "model");
+    assert_eq!(doc["entries"][0]["name"], "glm-4.6");
+    assert_eq!(doc["entries"][0]["tokens"], 7500);
+
+    // 日期范围颠倒
+    let output = run_glm(&["usage", "--from", "2026-10-07", "--to", "2026-10-01"], &envs).await;
+    assert_eq!(output.status.code(), Some(8));
+}
+
+#[tokio::test]
+async fn test_report_from_recorded_history() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let url = server.url();
+    let home = std::env::temp_dir().join(format!("glm-report-home-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&home);
+    std::fs::create_dir_all(&home).unwrap();
+    let home = home.to_string_lossy().to_string();
+    let envs = [
+        ("GLM_API_KEY", "test_api_key_12345"),
+        ("GLM_API_URL", url.as_str()),
+        ("HOME", home.as_str()),
+    ];
+
+    // 两次查询记录两个快照
+    for _ in 0..2 {
+        let output = run_glm(&[], &envs).await;
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    let history = std::fs::read_to_string(format!("{}/.glm/history.jsonl", home)).unwrap();
+    assert_eq!(history.lines().count(), 2);
+
+    let output = run_glm(&["report", "-f", "md"], &[("HOME", home.as_str())]).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("## GLM 使用报告（近 7 天）"));
+    assert!(stdout.contains("| 5 小时窗口数 | 1 |"));
+    assert!(stdout.contains("| 本月 MCP 调用 | 164 / 1000 |"));
+    assert!(stdout.contains("| 66.0% | 132374032 / 200000000 | 34.0% | 2 |"));
+
+    let output = run_glm(&["report", "--period", "day", "-f", "csv"], &[("HOME", home.as_str())]).await;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(",66.0,132374032,200000000,34.0,false,2\n"));
+    assert!(stdout.contains("\naverage_headroom_percentage,34.0\n"));
+
+    // csv / md 仅适用于报告
+    let output = run_glm(&["-f", "csv"], &envs).await;
+    assert_eq!(output.status.code(), Some(2));
+
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[tokio::test]
+async fn test_alert_delivered_once_per_window() {
+    let body = serde_json::json!({
+        "code": 200,
+        "msg": "操作成功",
+        "success": true,
+        "data": {"limits": [{
+            "type": "TOKENS_LIMIT",
+            "unit": 3,
+            "number": 5,
+            "usage": 1000,
+            "currentValue": 850,
+            "remaining": 150,
+            "percentage": 85,
+            "nextResetTime": 4102444800000i64
+        }]}
+    })
+    .to_string();
+    let api = FakeServer::start(200, body).await;
+    let receiver = FakeServer::start(200, r#"{"code":0}"#).await;
+    let broken = FakeServer::start(500, "boom").await;
+
+    let home = std::env::temp_dir().join(format!("glm-alert-home-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&home);
+    std::fs::create_dir_all(home.join(".glm")).unwrap();
+    std::fs::write(
+        home.join(".glm/config.yaml"),
+        format!(
+            "api_key: test_api_key_12345\napi_url: {}\nalerts:\n  sinks:\n    - kind: feishu\n      url: {}/hook\n    - kind: webhook\n      url: {}/hook\n",
+            api.url(),
+            receiver.url(),
+            broken.url()
+        ),
+    )
+    .unwrap();
+    let home = home.to_string_lossy().to_string();
+    let envs = [("HOME", home.as_str())];
+
+    // 预演：不推送
+    let output = run_glm(&["alert", "--dry-run"], &envs).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("TOKENS_LIMIT 已使用 85.0%（阈值 >= 80%）"));
+    assert!(receiver.requests().is_empty());
+
+    // 第一次检查推送 80% 告警，第二次同一窗口内不重复推送
+    for _ in 0..2 {
+        let output = run_glm(&["alert"], &envs).await;
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    let requests = receiver.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/hook");
+    let payload: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(payload["msg_type"], "text");
+    assert_eq!(payload["content"]["text"], "[GLM] TOKENS_LIMIT 已使用 85.0%（阈值 >= 80%）");
+    assert_eq!(broken.requests().len(), 1);
+
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_hooks_on_threshold_and_error() {
+    let body = serde_json::json!({
+        "code": 200,
+        "msg": "操作成功",
+        "success": true,
+        "data": {"limits": [{
+            "type": "TOKENS_LIMIT",
+            "usage": 1000,
+            "currentValue": 960,
+            "percentage": 96,
+            "nextResetTime": 4102444800000i64
+        }]}
+    })
+    .to_string();
+    let api = FakeServer::start(200, body).await;
+    let broken = FakeServer::start(401, r#"{"code":401,"msg":"令牌已过期"}"#).await;
+
+    let home = std::env::temp_dir().join(format!("glm-hooks-home-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&home);
+    std::fs::create_dir_all(home.join(".glm")).unwrap();
+    let log = home.join("hooks.log");
+    let hooks = format!(
+        "hooks:\n  on_threshold: echo \"$GLM_EVENT $GLM_LIMIT_TYPE $GLM_PERCENTAGE $GLM_RESET_AT\" >> '{0}'\n  on_error: cat >> '{0}'\n",
+        log.display()
+    );
+    let write_config = |url: String| {
+        std::fs::write(
+            home.join(".glm/config.yaml"),
+            format!("api_key: test_api_key_12345\napi_url: {}\n{}", url, hooks),
+        )
+        .unwrap();
+    };
+    let home_str = home.to_string_lossy().to_string();
+    let envs = [("HOME", home_str.as_str())];
+
+    // 普通查询也会检查规则：80% 与 95% 两条默认规则各触发一次钩子
+    write_config(api.url());
+    let output = run_glm(&[], &envs).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let lines: Vec<String> = std::fs::read_to_string(&log).unwrap().lines().map(String::from).collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], "threshold TOKENS_LIMIT 96.0 2100-01-01T00:00:00+00:00");
+
+    // glm alert 共用去重状态，同一窗口内不再重复触发
+    let output = run_glm(&["alert"], &envs).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(std::fs::read_to_string(&log).unwrap().lines().count(), 2);
+
+    // 查询失败时执行 on_error，标准输入为错误详情 JSON
+    std::fs::remove_file(&log).unwrap();
+    write_config(broken.url());
+    let output = run_glm(&[], &envs).await;
+    assert_eq!(output.status.code(), Some(5));
+    let event: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&log).unwrap()).unwrap();
+    assert_eq!(event["event"], "error");
+    assert_eq!(event["kind"], "auth");
+
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_guard_refuses_or_runs_command() {
+    let usage = |current_value: u64| {
+        serde_json::json!({
+            "code": 200,
+            "msg": "操作成功",
+            "success": true,
+            "data": {"limits": [{
+                "type": "TOKENS_LIMIT",
+                "usage": 1000,
+                "currentValue": current_value,
+                "nextResetTime": 4102444800000i64
+            }]}
+        })
+        .to_string()
+    };
+
+    // 剩余 4% 低于默认下限 10%：拒绝启动，退出码 11（与 HTTP 429 的 6 区分）
+    let server = FakeServer::start(200, usage(960)).await;
+    let output = run_glm(
+        &["guard", "--", "sh", "-c", "echo started"],
+        &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &server.url())],
+    )
+    .await;
+    assert_eq!(output.status.code(), Some(11));
+    assert!(String::from_utf8_lossy(&output.stdout).is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("TOKENS_LIMIT 剩余 4.0%"));
+
+    // 调低下限后启动命令，并原样传递退出码
+    let output = run_glm(
+        &["guard", "--min-remaining", "2%", "--", "sh", "-c", "echo started; exit 7"],
+        &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &server.url())],
+    )
+    .await;
+    assert_eq!(output.status.code(), Some(7));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "started\n");
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_wait_until_reset_and_below() {
+    let usage = |current_value: u64, next_reset: i64| {
+        serde_json::json!({
+            "code": 200,
+            "msg": "操作成功",
+            "success": true,
+            "data": {"limits": [{
+                "type": "TOKENS_LIMIT",
+                "usage": 1000,
+                "currentValue": current_value,
+                "nextResetTime": next_reset
+            }]}
+        })
+        .to_string()
+    };
+    let soon = chrono::Utc::now().timestamp_millis() + 500;
+
+    // 窗口即将重置：等待后成功退出，进度输出在标准错误
+    let server = FakeServer::start(200, usage(900, soon)).await;
+    let url = server.url();
+    let envs = [("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", url.as_str())];
+    let output = run_glm(&["wait", "--until-reset", "--margin", "0"], &envs).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("额度窗口已重置"));
+
+    // 重置时间晚于超时：立即以退出码 10 结束
+    let server = FakeServer::start(200, usage(900, 4102444800000)).await;
+    let url = server.url();
+    let envs = [("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", url.as_str())];
+    let output = run_glm(&["wait", "--until-reset", "--timeout", "1m"], &envs).await;
+    assert_eq!(output.status.code(), Some(10));
+
+    // 使用率已低于目标：立即成功
+    let output = run_glm(&["wait", "--until-below", "95%"], &envs).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("当前使用 90.0%，已低于 95%"));
+
+    // 必须指定等待条件
+    let output = run_glm(&["wait"], &envs).await;
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_daemon_serves_cached_usage() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let url = server.url();
+    let home = std::env::temp_dir().join(format!("glm-daemon-home-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&home);
+    std::fs::create_dir_all(&home).unwrap();
+    let home_str = home.to_string_lossy().to_string();
+    let envs = [
+        ("HOME", home_str.as_str()),
+        ("GLM_API_KEY", "test_api_key_12345"),
+        ("GLM_API_URL", url.as_str()),
+    ];
+
+    let mut daemon = std::process::Command::new(env!("CARGO_BIN_EXE_glm"))
+        .arg("daemon")
+        .env_clear()
+        .envs(envs)
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let socket = home.join(".glm/daemon.sock");
+    for _ in 0..50 {
+        if socket.exists() && !server.requests().is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(server.requests().len(), 1);
+
+    // 套接字及其目录只允许当前用户访问
+    use std::os::unix::fs::PermissionsExt;
+    let mode = |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode(&socket), 0o600);
+    assert_eq!(mode(&home.join(".glm")), 0o700);
+
+    // 普通命令读取守护进程缓存，不再请求 API
+    for _ in 0..2 {
+        let output = run_glm(&["-f", "json"], &envs).await;
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("TOKENS_LIMIT"));
+    }
+    assert_eq!(server.requests().len(), 1);
+
+    // --no-daemon 或密钥不一致时直接请求 API
+    let output = run_glm(&["--no-daemon"], &envs).await;
+    assert!(output.status.success());
+    let mut other_key = envs;
+    other_key[1] = ("GLM_API_KEY", "another_api_key_67890");
+    let output = run_glm(&[], &other_key).await;
+    assert!(output.status.success());
+    assert_eq!(server.requests().len(), 3);
+
+    let output = run_glm(&["daemon", "status", "-f", "json"], &envs).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(status["pid"], daemon.id());
+    assert_eq!(status["snapshots"], 1);
+
+    let output = run_glm(&["daemon", "stop"], &envs).await;
+    assert!(output.status.success());
+    let exited = tokio::task::spawn_blocking(move || daemon.wait().unwrap()).await.unwrap();
+    assert!(exited.success());
+    assert!(!socket.exists());
+
+    let output = run_glm(&["daemon", "status"], &envs).await;
+    assert_eq!(output.status.code(), Some(4));
+
+    std::fs::remove_dir_all(&home).unwrap();
+}
+
+#[cfg(feature = "otel")]
+#[tokio::test]
+async fn test_export_otlp_to_local_collector() {
+    let api = FakeServer::start(200, quota_response_body()).await;
+    let collector = FakeServer::start(200, "{}").await;
+    let api_url = api.url();
+    let collector_url = collector.url();
+    let envs = [("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", api_url.as_str())];
+
+    let output = run_glm(
+        &[
+            "export",
+            "otlp",
+            "--endpoint",
+            &collector_url,
+            "--header",
+            "Authorization=Bearer otel-token",
+            "--attribute",
+            "deployment.environment=test",
+        ],
+        &envs,
+    )
+    .await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("已推送 2 项额度指标"));
+
+    let requests = collector.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/v1/metrics");
+    assert_eq!(requests[0].header("authorization"), Some("Bearer otel-token"));
+    assert_eq!(requests[0].header("content-type"), Some("application/json"));
+
+    let payload: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    let resource = &payload["resourceMetrics"][0]["resource"]["attributes"];
+    let attribute = |key: &str| {
+        resource
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|attr| attr["key"] == key)
+            .map(|attr| attr["value"]["stringValue"].clone())
+    };
+    assert_eq!(attribute("service.name"), Some("glm".into()));
+    assert_eq!(attribute("glm.region"), Some("cn".into()));
+    assert_eq!(attribute("deployment.environment"), Some("test".into()));
+    assert!(attribute("host.name").is_some());
+
+    let metrics = payload["resourceMetrics"][0]["scopeMetrics"][0]["metrics"].as_array().unwrap();
+    let metric = |name: &str| metrics.iter().find(|m| m["name"] == name).unwrap();
+    let used = &metric("glm.quota.used")["gauge"]["dataPoints"];
+    assert_eq!(used[1]["asInt"], "132374032");
+    assert_eq!(used[1]["attributes"][0]["value"]["stringValue"], "TOKENS_LIMIT");
+    assert_eq!(metric("glm.fetch.requests")["sum"]["dataPoints"][0]["asInt"], "1");
+    assert_eq!(metric("glm.fetch.errors")["sum"]["dataPoints"].as_array().unwrap().len(), 0);
+    assert_eq!(metric("glm.fetch.duration")["histogram"]["dataPoints"][0]["count"], "1");
+
+    // API 鉴权失败：仍推送失败计数，以鉴权错误的退出码结束
+    let denied = FakeServer::start(401, r#"{"code":401,"msg":"令牌已过期"}"#).await;
+    let denied_url = denied.url();
+    let output = run_glm(
+        &["export", "otlp"],
+        &[
+            envs[0],
+            ("GLM_API_URL", denied_url.as_str()),
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", collector_url.as_str()),
+        ],
+    )
+    .await;
+    assert_eq!(output.status.code(), Some(5));
+    let payload: serde_json::Value = serde_json::from_str(&collector.requests()[1].body).unwrap();
+    let metrics = payload["resourceMetrics"][0]["scopeMetrics"][0]["metrics"].as_array().unwrap();
+    let errors = metrics.iter().find(|m| m["name"] == "glm.fetch.errors").unwrap();
+    assert_eq!(errors["sum"]["dataPoints"][0]["attributes"][0]["value"]["stringValue"], "auth");
+    assert!(!metrics.iter().any(|m| m["name"] == "glm.quota.used"));
+
+    // 接收端不可用：网络错误
+    let broken = FakeServer::start(503, "unavailable").await;
+    let output = run_glm(&["export", "otlp", "--endpoint", &broken.url()], &envs).await;
+    assert_eq!(output.status.code(), Some(4));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("OTLP 接收端返回 HTTP 503"));
+
+    // 未指定地址：配置错误
+    let output = run_glm(&["export", "otlp"], &envs).await;
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[tokio::test]
+async fn test_influx_line_protocol_output() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let url = server.url();
+    let output = run_glm(
+        &["-f", "influx"],
+        &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &url), ("GLM_PROFILE", "team")],
+    )
+    .await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "stdout: {}", stdout);
+    assert!(lines[0].starts_with("glm_quota,type=TIME_LIMIT,region=cn,profile=team used=164i,total=1000i,remaining=836i,pct=16,reset_seconds="));
+    let (head, timestamp) = lines[1].rsplit_once(' ').unwrap();
+    assert!(head.starts_with("glm_quota,type=TOKENS_LIMIT,region=cn,profile=team used=132374032i,total=200000000i,remaining=67625968i,pct=66,"));
+    assert!(timestamp.parse::<i64>().unwrap() > 1_700_000_000_000_000_000);
+}
+
+#[tokio::test]
+async fn test_export_statsd_over_udp() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let url = server.url();
+    let agent = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = agent.local_addr().unwrap().to_string();
+    let envs = [("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", url.as_str())];
+
+    let output = run_glm(&["export", "statsd", "--addr", &addr], &envs).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    let mut buf = vec![0u8; 2048];
+    let n = tokio::time::timeout(std::time::Duration::from_secs(5), agent.recv(&mut buf))
+        .await
+        .unwrap()
+        .unwrap();
+    let datagram = String::from_utf8_lossy(&buf[..n]).to_string();
+    let lines: Vec<&str> = datagram.lines().collect();
+    assert!(lines.contains(&"glm.quota.used:132374032|g|#type:TOKENS_LIMIT,region:cn"), "{}", datagram);
+    assert!(lines.contains(&"glm.quota.pct:16|g|#type:TIME_LIMIT,region:cn"), "{}", datagram);
+    assert!(lines.contains(&"glm.fetch.requests:1|c|#region:cn"), "{}", datagram);
+    assert!(lines.iter().any(|line| line.starts_with("glm.fetch.duration:") && line.contains("|ms")));
+
+    let output = run_glm(&["export", "statsd", "--addr", &addr, "--no-tags", "--prefix", "ai.glm"], &envs).await;
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let n = tokio::time::timeout(std::time::Duration::from_secs(5), agent.recv(&mut buf))
+        .await
+        .unwrap()
+        .unwrap();
+    let datagram = String::from_utf8_lossy(&buf[..n]).to_string();
+    assert!(datagram.lines().any(|line| line == "ai.glm.quota.tokens_limit.remaining:67625968|g"), "{}", datagram);
+    assert!(!datagram.contains('#'));
+
+    let output = run_glm(&["export", "statsd", "--addr", "not an address"], &envs).await;
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[tokio::test]
+async fn test_tui_requires_terminal() {
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let url = server.url();
+    let output = run_glm(
+        &["tui"],
+        &[("GLM_API_KEY", "test_api_key_12345"), ("GLM_API_URL", &url)],
+    )
+    .await;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr.contains("交互式终端"), "stderr: {}", stderr);
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn test_mcp_stdio_session() {
+    use std::io::Write;
+
+    let server = FakeServer::start(200, quota_response_body()).await;
+    let home = std::env::temp_dir().join(format!("glm-mcp-home-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&home);
+    std::fs::create_dir_all(&home).unwrap();
+
+    let script = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{},"clientInfo":{"name":"test","version":"0"}}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"get_quota","arguments":{}}}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"time_until_reset","arguments":{"limit":"TOKENS_LIMIT"}}}"#,
+        r#"{"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"forecast_exhaustion","arguments":{"limit":"NO_SUCH_LIMIT"}}}"#,
+    ];
+    let url = server.url();
+    let home_str = home.to_string_lossy().to_string();
+    let output = tokio::task::spawn_blocking(move || {
+        let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_glm"))
+            .arg("mcp")
+            .env_clear()
+            .env("HOME", &home_str)
+            .env("GLM_API_KEY", "test_api_key_12345")
+            .env("GLM_API_URL", &url)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        for line in script {
+            writeln!(stdin, "{}", line).unwrap();
+        }
+        drop(stdin);
+        child.wait_with_output().unwrap()
+    })
+    .await
+    .unwrap();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    // 通知没有应答，其余每条请求一行应答
+    let responses: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(responses.len(), 5);
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2025-06-18");
+    assert_eq!(responses[1]["result"]["tools"].as_array().unwrap().len(), 3);
+
+    let quota = &responses[2]["result"];
+    assert_eq!(quota["isError"], false);
+    let limits = quota["structuredContent"]["limits"].as_array().unwrap();
+    assert!(limits.iter().any(|limit| limit["type"] == "TOKENS_LIMIT"));
+
+    let reset = &responses[3]["result"]["structuredContent"];
+    assert_eq!(reset["reset_at"], "2100-01-01T00:00:00Z");
+    assert!(reset["seconds_until_reset"].as_u64().unwrap() > 0);
+
+    assert_eq!(responses[4]["id"], 5);
+    assert_eq!(responses[4]["result"]["isError"], true);
+
+    std::fs::remove_dir_all(&home).unwrap();
+}